@@ -5,8 +5,9 @@
 ///
 /// module providing shortcut functions/macros to help configuration of items
 /// by providing a CfgMap instance and the key to be retrieved
-use cfgmap::CfgMap;
+use cfgmap::{CfgMap, CfgValue};
 use regex::Regex;
+use serde_json::{Map, Value};
 
 use crate::utility::result::{Error, Kind, Result};
 use crate::constants::*;
@@ -564,4 +565,68 @@ pub fn cfg_vec_string_check_regex(
     cfg_vec_string_check(cfgmap, key, |s| check.is_match(s))
 }
 
+/// convert a configuration value to JSON, for instance to report it
+pub fn cfg_value_to_json(value: &CfgValue) -> Value {
+    match value {
+        CfgValue::Int(v) => Value::from(*v),
+        CfgValue::Float(v) => Value::from(*v),
+        CfgValue::Str(v) => Value::from(v.as_str()),
+        CfgValue::Bool(v) => Value::from(*v),
+        CfgValue::Map(m) => cfg_map_to_json(m),
+        CfgValue::List(l) => Value::Array(l.iter().map(cfg_value_to_json).collect()),
+        CfgValue::Datetime(d) => Value::from(d.to_string()),
+    }
+}
+
+/// convert a configuration map to a JSON object
+pub fn cfg_map_to_json(cfgmap: &CfgMap) -> Value {
+    let mut res = Map::new();
+    for (k, v) in cfgmap.iter() {
+        res.insert(k.clone(), cfg_value_to_json(v));
+    }
+    Value::Object(res)
+}
+
+/// find a named item in a section (`task`, `condition` or `event`) of the
+/// whole configuration map
+pub fn cfg_find_item<'a>(cfgmap: &'a CfgMap, section: &str, name: &str) -> Option<&'a CfgMap> {
+    cfgmap.get(section)?.as_list()?.iter().find_map(|item| {
+        let item = item.as_map()?;
+        if item.get("name")?.as_str()? == name {
+            Some(item)
+        } else {
+            None
+        }
+    })
+}
+
+/// check whether an item has a tag: when `tags` is an array, the tag must
+/// be one of its string elements, and when it is a table one of its keys
+pub fn cfg_item_has_tag(item: &CfgMap, tag: &str) -> bool {
+    match item.get("tags") {
+        Some(CfgValue::List(tags)) => tags.iter().any(|t| t.as_str().is_some_and(|t| t == tag)),
+        Some(CfgValue::Map(tags)) => tags.iter().any(|(k, _)| k == tag),
+        _ => false,
+    }
+}
+
+/// find the names of the items in a section (`task`, `condition` or `event`)
+/// of the whole configuration map that have the provided tag
+pub fn cfg_items_with_tag(cfgmap: &CfgMap, section: &str, tag: &str) -> Vec<String> {
+    let mut res: Vec<String> = cfgmap
+        .get(section)
+        .and_then(|x| x.as_list())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_map())
+                .filter(|item| cfg_item_has_tag(item, tag))
+                .filter_map(|item| item.get("name")?.as_str().cloned())
+                .collect()
+        })
+        .unwrap_or_default();
+    res.sort();
+    res
+}
+
 // end.
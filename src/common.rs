@@ -832,6 +832,606 @@ pub mod cmditem {
     }
 }
 
+#[cfg(feature = "http")]
+#[allow(dead_code)]
+/// This module helps HTTP based items perform requests and check responses
+pub mod httpitem {
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    use cfgmap::CfgMap;
+    use itertools::Itertools;
+    use minreq::{Method, Request};
+    use serde_json::Value;
+
+    use crate::cfghelp::{
+        cfg_bool, cfg_err_invalid_config, cfg_int_check_above_eq, cfg_int_check_interval,
+        cfg_string, cfg_value_to_json,
+    };
+    use crate::constants::*;
+    use crate::utility::logging::LogType;
+
+    /// In case of failure, the reason will be one of the provided values
+    #[derive(Debug, PartialEq)]
+    pub enum FailureReason {
+        NoFailure,
+        Body,
+        Status,
+        ResponseTime,
+        Header,
+        Json,
+        Other,
+    }
+
+    impl FailureReason {
+        /// A short description of the failed check, to be used in logs
+        pub fn check_name(&self) -> &'static str {
+            match self {
+                FailureReason::NoFailure => "no",
+                FailureReason::Body => "body",
+                FailureReason::Status => "status",
+                FailureReason::ResponseTime => "response time",
+                FailureReason::Header => "header",
+                FailureReason::Json => "JSON",
+                FailureReason::Other => "request",
+            }
+        }
+    }
+
+    /// The checks that HTTP based items perform on the responses they receive
+    #[derive(Clone, Debug, Default, Hash)]
+    pub struct ResponseChecks {
+        pub match_exact: bool,
+        pub match_regexp: bool,
+        pub case_sensitive: bool,
+        pub success_body: Option<String>,
+        pub success_status: Option<u32>,
+        pub failure_body: Option<String>,
+        pub failure_status: Option<u32>,
+        pub max_response_time: Option<Duration>,
+        pub success_headers: Vec<(String, String)>,
+        pub json_pointer: Option<String>,
+        pub json_value: Option<String>,
+    }
+
+    /// The parts of a response that are used by HTTP based items, along with
+    /// the time that was needed to receive it
+    pub struct HttpResponse {
+        pub status: u32,
+        pub headers: Vec<(String, String)>,
+        pub body: String,
+        pub duration: Duration,
+    }
+
+    /// Read the headers to be sent with a request from a configuration map,
+    /// where they are given as a table whose keys are the header names and
+    /// whose values are strings: an empty map is returned if the entry is
+    /// not present.
+    pub fn cfg_http_headers(
+        cfgmap: &CfgMap,
+        key: &str,
+    ) -> crate::utility::result::Result<HashMap<String, String>> {
+        let mut headers: HashMap<String, String> = HashMap::new();
+        if let Some(item) = cfgmap.get(key) {
+            if !item.is_map() {
+                return Err(cfg_err_invalid_config(
+                    key,
+                    STR_INVALID_TYPE,
+                    ERR_INVALID_PARAMETER,
+                ));
+            }
+            for (name, value) in item.as_map().unwrap().iter() {
+                if !RE_HTTP_HEADER_NAME.is_match(name) {
+                    return Err(cfg_err_invalid_config(key, name, ERR_INVALID_HEADER_NAME));
+                } else if let Some(value) = value.as_str() {
+                    headers.insert(name.clone(), value.clone());
+                } else {
+                    return Err(cfg_err_invalid_config(
+                        key,
+                        STR_UNKNOWN_VALUE,
+                        ERR_INVALID_HEADER_VALUE,
+                    ));
+                }
+            }
+        }
+        Ok(headers)
+    }
+
+    /// Read the response checks from a configuration map: the entries have
+    /// the same names and meaning as the ones used by command based items,
+    /// except for the body, which replaces _stdout_ and _stderr_.
+    pub fn cfg_response_checks(cfgmap: &CfgMap) -> crate::utility::result::Result<ResponseChecks> {
+        let mut checks = ResponseChecks::default();
+        if let Some(v) = cfg_bool(cfgmap, "match_exact")? {
+            checks.match_exact = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "match_regular_expression")? {
+            checks.match_regexp = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "case_sensitive")? {
+            checks.case_sensitive = v;
+        }
+
+        checks.success_body = cfg_string(cfgmap, "success_body")?;
+        if let Some(v) = cfg_int_check_interval(cfgmap, "success_status", 100, 599)? {
+            checks.success_status = Some(v as u32);
+        }
+
+        checks.failure_body = cfg_string(cfgmap, "failure_body")?;
+        if let Some(v) = cfg_int_check_interval(cfgmap, "failure_status", 100, 599)? {
+            checks.failure_status = Some(v as u32);
+        }
+
+        Ok(checks)
+    }
+
+    /// Read the further response checks that are only available to HTTP
+    /// based conditions, adding them to the provided ones: the expected
+    /// headers are kept sorted by name, and the expected JSON value is
+    /// stored in its textual form so that it can be hashed.
+    pub fn cfg_response_extra_checks(
+        cfgmap: &CfgMap,
+        mut checks: ResponseChecks,
+    ) -> crate::utility::result::Result<ResponseChecks> {
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "max_response_milliseconds", 1)? {
+            checks.max_response_time = Some(Duration::from_millis(v as u64));
+        }
+        checks.success_headers = cfg_http_headers(cfgmap, "success_headers")?
+            .into_iter()
+            .sorted()
+            .collect();
+
+        let cur_key = "json_pointer";
+        if let Some(v) = cfg_string(cfgmap, cur_key)? {
+            if !v.is_empty() && !v.starts_with('/') {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_VALUE));
+            }
+            checks.json_pointer = Some(v);
+        }
+        let cur_key = "json_value";
+        if let Some(v) = cfgmap.get(cur_key) {
+            if checks.json_pointer.is_none() {
+                return Err(cfg_err_invalid_config(
+                    cur_key,
+                    STR_UNKNOWN_VALUE,
+                    ERR_INVALID_PARAMETER,
+                ));
+            }
+            checks.json_value = Some(cfg_value_to_json(v).to_string());
+        }
+
+        Ok(checks)
+    }
+
+    /// Convert the name of a method to the corresponding `minreq` method:
+    /// only the standard methods that make sense for items are supported,
+    /// and `None` is returned for unknown names
+    pub fn http_method(name: &str) -> Option<Method> {
+        match name.to_ascii_uppercase().as_str() {
+            "GET" => Some(Method::Get),
+            "HEAD" => Some(Method::Head),
+            "POST" => Some(Method::Post),
+            "PUT" => Some(Method::Put),
+            "DELETE" => Some(Method::Delete),
+            "OPTIONS" => Some(Method::Options),
+            "PATCH" => Some(Method::Patch),
+            _ => None,
+        }
+    }
+
+    /// Perform a request and collect the response: the body is decoded as
+    /// UTF-8, replacing invalid sequences. The method must have been checked
+    /// by the caller, and an error is returned when no response is received
+    /// (including the case in which the timeout, if any, is reached).
+    ///
+    /// This helper is used by:
+    ///
+    /// * `task::http_task::HttpTask::_run()`
+    /// * `condition::http_cond::HttpCondition::_check_condition()`
+    pub fn send_request(
+        method: &str,
+        url: &str,
+        headers: &HashMap<String, String>,
+        body: Option<&[u8]>,
+        timeout: Option<Duration>,
+    ) -> Result<HttpResponse, minreq::Error> {
+        let method = http_method(method).unwrap_or(Method::Get);
+        let mut req = Request::new(method, url).with_headers(headers.clone());
+        if let Some(body) = body {
+            req = req.with_body(body);
+        }
+        if let Some(timeout) = timeout {
+            // the timeout is expressed in whole seconds, and zero means none
+            req = req.with_timeout(timeout.as_secs().max(1));
+        }
+
+        let startup_time = Instant::now();
+        let resp = req.send()?;
+        let duration = startup_time.elapsed();
+        Ok(HttpResponse {
+            status: resp.status_code as u32,
+            body: String::from_utf8_lossy(resp.as_bytes()).to_string(),
+            headers: resp.headers,
+            duration,
+        })
+    }
+
+    /// Match a text against a pattern using the same rules that are applied
+    /// by `cmditem::check_process_outcome()` to the output of commands: the
+    /// pattern is either a regular expression or a plain string, which has
+    /// to be either found in the text or to match it entirely; note that,
+    /// also as for commands, regular expressions have to be anchored by the
+    /// user in order to match the whole text. `None` is returned when the
+    /// regular expression is invalid.
+    pub fn text_matches(
+        text: &str,
+        pattern: &str,
+        match_exact: bool,
+        match_regexp: bool,
+        case_sensitive: bool,
+    ) -> Option<bool> {
+        if match_regexp {
+            let re = regex::RegexBuilder::new(pattern)
+                .case_insensitive(!case_sensitive)
+                .build()
+                .ok()?;
+            Some(re.is_match(text))
+        } else if case_sensitive {
+            if match_exact {
+                Some(text == pattern)
+            } else {
+                Some(text.contains(pattern))
+            }
+        } else if match_exact {
+            Some(text.to_uppercase() == pattern.to_uppercase())
+        } else {
+            Some(text.to_uppercase().contains(&pattern.to_uppercase()))
+        }
+    }
+
+    /// Check the response received by HTTP based items, following the same
+    /// priority rules that are used for the outcome of commands: the status
+    /// code is checked first, giving precedence to the expected failure code,
+    /// then the response time and the expected headers, the expected success
+    /// body, the expected failure body and at last the JSON pointer. When no
+    /// status code is expected at all, any code outside of the `2xx` range is
+    /// considered a failure. The returned tuple contains
+    ///
+    /// * the failure reason as a `FailureReason`
+    ///
+    /// and what is needed to build a log message:
+    ///
+    /// * the severity of the log line
+    /// * the _when_ part of the log line
+    /// * the _status_ part of the log line
+    /// * the payload (human readable) message of the log line
+    ///
+    /// This helper is used by:
+    ///
+    /// * `task::http_task::HttpTask::_run()`
+    /// * `condition::http_cond::HttpCondition::_check_condition()`
+    pub fn check_response_outcome(
+        response: &HttpResponse,
+        checks: &ResponseChecks,
+    ) -> (
+        FailureReason, // failure_reason
+        LogType,       // the log severity
+        &'static str,  // log/when (LOG_WHEN_...)
+        &'static str,  // log/status (LOG_STATUS_...)
+        String,        // log message
+    ) {
+        let status = response.status;
+
+        // status code checks: an expected failure code takes precedence
+        if let Some(expectedf) = checks.failure_status
+            && status == expectedf
+        {
+            return (
+                FailureReason::Status,
+                LogType::Debug,
+                LOG_WHEN_PROC,
+                LOG_STATUS_OK,
+                format!("expected failure status code {expectedf} matched"),
+            );
+        }
+        if let Some(expected) = checks.success_status {
+            if status != expected {
+                return (
+                    FailureReason::Status,
+                    LogType::Debug,
+                    LOG_WHEN_PROC,
+                    LOG_STATUS_OK,
+                    format!("expected success status code {expected} NOT matched: {status}"),
+                );
+            }
+        } else if checks.failure_status.is_none() && !(200..300).contains(&status) {
+            return (
+                FailureReason::Status,
+                LogType::Debug,
+                LOG_WHEN_PROC,
+                LOG_STATUS_OK,
+                format!("response received with FAILURE status code {status}"),
+            );
+        }
+
+        // response time check
+        if let Some(max_time) = checks.max_response_time
+            && response.duration > max_time
+        {
+            return (
+                FailureReason::ResponseTime,
+                LogType::Debug,
+                LOG_WHEN_PROC,
+                LOG_STATUS_OK,
+                format!(
+                    "response time {}ms exceeds the maximum of {}ms",
+                    response.duration.as_millis(),
+                    max_time.as_millis(),
+                ),
+            );
+        }
+
+        // header checks: names are case insensitive, while values follow
+        // the same matching rules that are used for the body
+        for (name, p) in checks.success_headers.iter() {
+            let value = response
+                .headers
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str());
+            let Some(value) = value else {
+                return (
+                    FailureReason::Header,
+                    LogType::Debug,
+                    LOG_WHEN_PROC,
+                    LOG_STATUS_OK,
+                    format!("expected header {name} NOT found"),
+                );
+            };
+            match text_matches(
+                value,
+                p,
+                checks.match_exact,
+                checks.match_regexp,
+                checks.case_sensitive,
+            ) {
+                Some(true) => (),
+                Some(false) => {
+                    return (
+                        FailureReason::Header,
+                        LogType::Debug,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_OK,
+                        format!("expected value {p:?} for header {name} NOT matched: {value:?}"),
+                    );
+                }
+                None => {
+                    return (
+                        FailureReason::Header,
+                        LogType::Error,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_FAIL,
+                        format!("provided INVALID regex {p:?} for header {name} NOT found/matched"),
+                    );
+                }
+            }
+        }
+
+        // body checks, only performed when the status is acceptable
+        if let Some(p) = &checks.success_body
+            && !p.is_empty()
+        {
+            match text_matches(
+                &response.body,
+                p,
+                checks.match_exact,
+                checks.match_regexp,
+                checks.case_sensitive,
+            ) {
+                Some(true) => (),
+                Some(false) => {
+                    return (
+                        FailureReason::Body,
+                        LogType::Debug,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_OK,
+                        format!("success body {p:?} NOT matched"),
+                    );
+                }
+                None => {
+                    return (
+                        FailureReason::Body,
+                        LogType::Error,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_FAIL,
+                        format!("provided INVALID body regex {p:?} NOT found/matched"),
+                    );
+                }
+            }
+        }
+        if let Some(p) = &checks.failure_body
+            && !p.is_empty()
+        {
+            match text_matches(
+                &response.body,
+                p,
+                checks.match_exact,
+                checks.match_regexp,
+                checks.case_sensitive,
+            ) {
+                Some(true) => {
+                    return (
+                        FailureReason::Body,
+                        LogType::Debug,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_OK,
+                        format!("failure body {p:?} matched"),
+                    );
+                }
+                Some(false) => (),
+                None => {
+                    // as for commands, an invalid failure regex is reported
+                    // but does not cause the check to fail
+                    return (
+                        FailureReason::NoFailure,
+                        LogType::Error,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_FAIL,
+                        format!("provided INVALID failure body regex {p:?} NOT found/matched"),
+                    );
+                }
+            }
+        }
+
+        // JSON check: the pointed value must exist, and match the expected
+        // one when provided
+        if let Some(pointer) = &checks.json_pointer {
+            let Ok(document) = serde_json::from_str::<Value>(&response.body) else {
+                return (
+                    FailureReason::Json,
+                    LogType::Debug,
+                    LOG_WHEN_PROC,
+                    LOG_STATUS_OK,
+                    String::from("response body is NOT valid JSON"),
+                );
+            };
+            let Some(found) = document.pointer(pointer) else {
+                return (
+                    FailureReason::Json,
+                    LogType::Debug,
+                    LOG_WHEN_PROC,
+                    LOG_STATUS_OK,
+                    format!("JSON pointer {pointer:?} NOT found in response"),
+                );
+            };
+            if let Some(expected) = &checks.json_value {
+                // the expected value is parsed back from its textual form,
+                // which has been generated from a valid JSON value
+                let expected: Value = serde_json::from_str(expected).unwrap_or(Value::Null);
+                if *found != expected {
+                    return (
+                        FailureReason::Json,
+                        LogType::Debug,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_OK,
+                        format!("value at JSON pointer {pointer:?} NOT matched: {found}"),
+                    );
+                }
+            }
+        }
+
+        (
+            FailureReason::NoFailure,
+            LogType::Debug,
+            LOG_WHEN_PROC,
+            LOG_STATUS_OK,
+            format!("response received with SUCCESS status code {status}"),
+        )
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn response(status: u32, body: &str) -> HttpResponse {
+            HttpResponse {
+                status,
+                headers: vec![(String::from("Content-Type"), String::from("text/plain"))],
+                body: String::from(body),
+                duration: Duration::from_millis(10),
+            }
+        }
+
+        #[test]
+        fn text_matching_rules() {
+            let m = |pattern, exact, regexp, case| {
+                text_matches("Hello World", pattern, exact, regexp, case)
+            };
+            assert_eq!(m("world", false, false, false), Some(true));
+            assert_eq!(m("world", false, false, true), Some(false));
+            assert_eq!(m("hello world", true, false, false), Some(true));
+            assert_eq!(m("Hello", true, false, true), Some(false));
+            assert_eq!(m("^hel+o", false, true, false), Some(true));
+            assert_eq!(m("^hel+o", false, true, true), Some(false));
+            assert_eq!(m("(unclosed", false, true, true), None);
+        }
+
+        #[test]
+        fn non_2xx_status_fails_by_default() {
+            let checks = ResponseChecks::default();
+            let (reason, ..) = check_response_outcome(&response(200, "ok"), &checks);
+            assert_eq!(reason, FailureReason::NoFailure);
+            let (reason, ..) = check_response_outcome(&response(404, "ok"), &checks);
+            assert_eq!(reason, FailureReason::Status);
+            let (reason, ..) = check_response_outcome(&response(302, "ok"), &checks);
+            assert_eq!(reason, FailureReason::Status);
+        }
+
+        #[test]
+        fn failure_status_takes_precedence() {
+            let checks = ResponseChecks {
+                success_status: Some(503),
+                failure_status: Some(503),
+                ..Default::default()
+            };
+            let (reason, ..) = check_response_outcome(&response(503, ""), &checks);
+            assert_eq!(reason, FailureReason::Status);
+
+            // an expected failure code alone makes any other code acceptable
+            let checks = ResponseChecks {
+                failure_status: Some(503),
+                ..Default::default()
+            };
+            let (reason, ..) = check_response_outcome(&response(404, ""), &checks);
+            assert_eq!(reason, FailureReason::NoFailure);
+
+            // the status is checked before the body
+            let checks = ResponseChecks {
+                success_status: Some(200),
+                success_body: Some(String::from("ok")),
+                ..Default::default()
+            };
+            let (reason, ..) = check_response_outcome(&response(201, "ok"), &checks);
+            assert_eq!(reason, FailureReason::Status);
+            let (reason, ..) = check_response_outcome(&response(200, "ko"), &checks);
+            assert_eq!(reason, FailureReason::Body);
+        }
+
+        #[test]
+        fn invalid_regexes() {
+            // an invalid success regex fails the check and is reported
+            let checks = ResponseChecks {
+                match_regexp: true,
+                success_body: Some(String::from("(unclosed")),
+                ..Default::default()
+            };
+            let (reason, severity, ..) = check_response_outcome(&response(200, "x"), &checks);
+            assert_eq!(reason, FailureReason::Body);
+            assert!(matches!(severity, LogType::Error));
+
+            // an invalid failure regex is reported but does not fail
+            let checks = ResponseChecks {
+                match_regexp: true,
+                failure_body: Some(String::from("(unclosed")),
+                ..Default::default()
+            };
+            let (reason, severity, ..) = check_response_outcome(&response(200, "x"), &checks);
+            assert_eq!(reason, FailureReason::NoFailure);
+            assert!(matches!(severity, LogType::Error));
+
+            // the same applies to expected header values
+            let checks = ResponseChecks {
+                match_regexp: true,
+                success_headers: vec![(String::from("content-type"), String::from("[text"))],
+                ..Default::default()
+            };
+            let (reason, severity, ..) = check_response_outcome(&response(200, "x"), &checks);
+            assert_eq!(reason, FailureReason::Header);
+            assert!(matches!(severity, LogType::Error));
+        }
+    }
+}
+
 #[allow(dead_code)]
 /// This module provides utilities for Lua based items
 pub mod luaitem {
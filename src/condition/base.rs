@@ -23,10 +23,14 @@
 
 use std::time::Instant;
 
+use chrono::{DateTime, Local};
+
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::{Error, Kind, Result};
 
+use super::retry::{RetryPolicy, RetryWait};
 use crate::constants::*;
+use crate::event::payload::EventPayload;
 use crate::task::registry::TaskRegistry;
 
 /// Define the interface for `Condition` objects
@@ -108,6 +112,25 @@ pub trait Condition: Send {
     /// Fully reset internal state of the condition
     fn reset(&mut self);
 
+    /// Restore the persistent part of the internal state of the condition,
+    /// that is the _succeeded_ state and the number of retries left
+    fn restore_state(
+        &mut self,
+        has_succeeded: bool,
+        last_succeeded: Option<Instant>,
+        left_retries: Option<u64>,
+    );
+
+    /// Return the wall clock time of the last success, only for conditions
+    /// that need it to catch up with missed occurrences
+    fn last_fired(&self) -> Option<DateTime<Local>> {
+        None
+    }
+
+    /// Restore the wall clock time of the last success, which is ignored by
+    /// conditions that do not catch up with missed occurrences
+    fn restore_last_fired(&mut self, _when: DateTime<Local>) {}
+
     /// Return how many times the tasks can be retried, `None` means forever
     fn left_retries(&self) -> Option<u64>;
 
@@ -123,6 +146,15 @@ pub trait Condition: Send {
         }
     }
 
+    /// Return the policy that determines the delay between retries
+    fn retry_policy(&self) -> RetryPolicy;
+
+    /// Return the pending retry, if the next one has to be delayed
+    fn retry_wait(&self) -> Option<RetryWait>;
+
+    /// Set or clear the pending retry
+    fn set_retry_wait(&mut self, wait: Option<RetryWait>);
+
     /// Set the startup time to `Instant::now()`
     fn start(&mut self);
 
@@ -135,6 +167,12 @@ pub trait Condition: Send {
     /// Get a list of task names as owned strings
     fn task_names(&self) -> Vec<String>;
 
+    /// Return the payload attached by the event that last fired the
+    /// `Condition`: only conditions fired by events can have one
+    fn event_payload(&self) -> Option<EventPayload> {
+        None
+    }
+
     /// Check whether or not there are associated tasks
     fn has_tasks(&self) -> bool {
         !self.task_names().is_empty()
@@ -240,9 +278,8 @@ pub trait Condition: Send {
             self.get_name(),
         );
 
-        // bail out if the condition has no associated tasks, if it
-        // is suspended, or if it has been successful once and is not
-        // set to be recurrent, and check otherwise
+        // bail out if the condition has no associated tasks, otherwise
+        // perform the same evaluation as for conditions used as inputs
         if !self.has_tasks() {
             self.log(
                 LogType::Debug,
@@ -251,7 +288,32 @@ pub trait Condition: Send {
                 "skipping check: condition has no associated tasks",
             );
             Ok(None)
-        } else if self.suspended() {
+        } else {
+            self.evaluate()
+        }
+    }
+
+    /// Interface to condition checks regardless of associated tasks
+    ///
+    /// This method performs the same checks as `test`, except that it does
+    /// not require the condition to have associated tasks: it is used by
+    /// composite conditions to evaluate the conditions they refer to, when
+    /// these only act as inputs. The return values are the same as `test`.
+    ///
+    /// # Panics
+    ///
+    /// As for `test`, this method can only be invoked on **registered**
+    /// conditions.
+    fn evaluate(&mut self) -> Result<Option<bool>> {
+        assert!(
+            self.get_id() != 0,
+            "condition {} not registered",
+            self.get_name(),
+        );
+
+        // bail out if the condition is suspended, or if it has been
+        // successful once and is not set to be recurrent
+        if self.suspended() {
             self.log(
                 LogType::Debug,
                 LOG_WHEN_PROC,
@@ -275,6 +337,18 @@ pub trait Condition: Send {
                 "skipping check: condition has no retries left and is not recurring",
             );
             Ok(None)
+        } else if self.has_succeeded()
+            && !self.recurring()
+            && self.retry_wait().is_some_and(|w| w.until > Instant::now())
+        {
+            let secs = (self.retry_wait().unwrap().until - Instant::now()).as_secs_f64();
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_PROC,
+                LOG_STATUS_MSG,
+                &format!("skipping check: waiting {secs:.1}s before retrying"),
+            );
+            Ok(None)
         } else {
             self.reset_succeeded();
             self.log(
@@ -406,7 +480,8 @@ pub trait Condition: Send {
     /// Run the associated tasks
     ///
     /// The assocaiated tasks are run, either sequentially or simultaneously
-    /// according to condition configuration. Task execution is logged as well
+    /// according to condition configuration, or following their dependencies
+    /// when any of them depends on other tasks. Task execution is logged as well
     /// as task outcomes after execution.
     ///
     /// **Note**: This function waits for all tasks to finish prior to
@@ -451,7 +526,20 @@ pub trait Condition: Send {
             return Ok(None);
         }
 
-        let res = if self.exec_sequence() {
+        let payload = self.event_payload();
+        let res = if registry.has_dependencies(&names.iter().map(|s| s.as_str()).collect()) {
+            self.log(
+                LogType::Info,
+                LOG_WHEN_START,
+                LOG_STATUS_MSG,
+                &format!("running tasks according to dependencies: {s_task_names}"),
+            );
+            registry.run_tasks_graph(
+                &self.get_name(),
+                &names.iter().map(|s| s.as_str()).collect(),
+                payload.as_ref(),
+            )?
+        } else if self.exec_sequence() {
             self.log(
                 LogType::Info,
                 LOG_WHEN_START,
@@ -463,6 +551,7 @@ pub trait Condition: Send {
                 &names.iter().map(|s| s.as_str()).collect(),
                 self.break_on_failure(),
                 self.break_on_success(),
+                payload.as_ref(),
             )?
         } else {
             self.log(
@@ -474,6 +563,7 @@ pub trait Condition: Send {
             registry.run_tasks_par(
                 &self.get_name(),
                 &names.iter().map(|s| s.as_str()).collect(),
+                payload.as_ref(),
             )?
         };
 
@@ -556,6 +646,25 @@ pub trait Condition: Send {
                     "some tasks failed: will retry until all succeed",
                 );
             }
+
+            // the retry policy may require to wait before the next attempt
+            if self.can_retry() {
+                let wait = self.retry_policy().next(self.retry_wait());
+                self.set_retry_wait(wait);
+                if let Some(wait) = wait {
+                    self.log(
+                        LogType::Debug,
+                        LOG_WHEN_END,
+                        LOG_STATUS_MSG,
+                        &format!(
+                            "next retry delayed by {:.1}s",
+                            (wait.until - Instant::now()).as_secs_f64(),
+                        ),
+                    );
+                }
+            }
+        } else if !some_failed {
+            self.set_retry_wait(None);
         }
 
         Ok(Some(true))
@@ -9,17 +9,24 @@
 //! conditions that have to be run at the next tick. At each tick the bucket is
 //! checked, and all conditions (of this kind) are verified. Therefore, after
 //! each tick, the execution bucket will be empty again.
+//!
+//! Insertions can also be _deferred_, so that a condition only enters the
+//! bucket when a quiet period has elapsed, and _throttled_, so that it does
+//! not enter the bucket more than once within a given time window: this is
+//! used by events that have a firing policy.
 
 use parking_lot::Mutex;
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use cfgmap::CfgMap;
 use regex::Regex;
 
 use super::base::Condition;
+use super::retry::{RetryPolicy, RetryWait, cfg_retry_policy};
+use crate::event::payload::EventPayload;
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::Result;
 use crate::task::registry::TaskRegistry;
@@ -27,13 +34,27 @@ use crate::{cfg_mandatory, constants::*};
 
 use crate::cfghelp::*;
 
+// a deferred insertion: the instant it is due, an optional throttling
+// window to be checked at that moment and the payload to be inserted
+struct DeferredInsertion {
+    until: Instant,
+    window: Option<Duration>,
+    payload: EventPayload,
+}
+
 /// Execution Bucket
 ///
 /// Contains the names of the conditions that have to be executed at the next
-/// tick. A name can be present only once, so multiple insertions are
-/// automatically rejected (or _debounced_).
+/// tick, each with the payload attached by the event that fired it. A name
+/// can be present only once, so multiple insertions are automatically
+/// rejected (or _debounced_), and only their payloads are merged.
 pub struct ExecutionBucket {
-    execution_list: Arc<Mutex<HashSet<String>>>,
+    execution_list: Arc<Mutex<HashMap<String, EventPayload>>>,
+
+    // deferred insertions, and the instant of the last insertion of
+    // throttled conditions
+    deferred_list: Arc<Mutex<HashMap<String, DeferredInsertion>>>,
+    last_inserted: Arc<Mutex<HashMap<String, Instant>>>,
 }
 
 #[allow(dead_code)]
@@ -41,45 +62,131 @@ impl ExecutionBucket {
     /// Create a new empty condition `ExecutionBucket`
     pub fn new() -> Self {
         ExecutionBucket {
-            execution_list: Arc::new(Mutex::new(HashSet::new())),
+            execution_list: Arc::new(Mutex::new(HashMap::new())),
+            deferred_list: Arc::new(Mutex::new(HashMap::new())),
+            last_inserted: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    // move the deferred conditions that are due into the execution list
+    fn release_deferred(&self) {
+        let now = Instant::now();
+        let due: Vec<(String, DeferredInsertion)> = {
+            let mut dl = self.deferred_list.lock();
+            let names: Vec<String> = dl
+                .iter()
+                .filter(|(_, d)| d.until <= now)
+                .map(|(name, _)| name.clone())
+                .collect();
+            names
+                .into_iter()
+                .map(|name| {
+                    let d = dl.remove(&name).unwrap();
+                    (name, d)
+                })
+                .collect()
+        };
+        for (name, d) in due {
+            match d.window {
+                Some(window) => {
+                    self.insert_condition_throttled(&name, window, d.payload);
+                }
+                None => {
+                    self.insert_condition(&name, d.payload);
+                }
+            }
         }
     }
 
     /// Return `true` if the condition name is in the bucket
     pub fn has_condition(&self, name: &str) -> bool {
-        self.execution_list
-            .clone()
-            .lock()
-            .contains(&String::from(name))
+        self.release_deferred();
+        self.execution_list.clone().lock().contains_key(name)
     }
 
     /// Try to insert the condition in the bucket, return `false` if the name
-    /// is already present, in which case the condition is not inserted
-    pub fn insert_condition(&self, name: &str) -> bool {
-        if !self.has_condition(name) {
-            self.execution_list
-                .clone()
-                .lock()
-                .insert(String::from(name))
-        } else {
+    /// is already present, in which case the condition is not inserted and
+    /// the payload is merged with the one already in the bucket
+    pub fn insert_condition(&self, name: &str, payload: EventPayload) -> bool {
+        let mut el = self.execution_list.lock();
+        if let Some(existing) = el.get_mut(name) {
+            existing.merge(payload);
             false
+        } else {
+            el.insert(String::from(name), payload);
+            true
         }
     }
 
-    /// Remove a condition if present and return `true`, `false` if not present
-    pub fn remove_condition(&self, name: &str) -> bool {
-        if self.has_condition(name) {
-            self.execution_list
-                .clone()
-                .lock()
-                .remove(&String::from(name))
+    /// Try to insert the condition in the bucket unless it has already been
+    /// inserted within the provided time window, return `false` if the
+    /// condition is not inserted
+    pub fn insert_condition_throttled(
+        &self,
+        name: &str,
+        window: Duration,
+        payload: EventPayload,
+    ) -> bool {
+        let now = Instant::now();
+        let mut li = self.last_inserted.lock();
+        if let Some(last) = li.get(name)
+            && now.duration_since(*last) < window
+        {
+            return false;
+        }
+        if self.insert_condition(name, payload) {
+            li.insert(String::from(name), now);
+            true
         } else {
             false
         }
     }
 
+    /// Schedule the insertion of the condition after the provided quiet
+    /// period: if an insertion is already scheduled it is postponed, so that
+    /// the condition is only inserted when no further requests arrive for the
+    /// whole period; if a throttling window is given, it is checked when the
+    /// condition is actually inserted
+    pub fn defer_condition(
+        &self,
+        name: &str,
+        quiet: Duration,
+        window: Option<Duration>,
+        payload: EventPayload,
+    ) -> bool {
+        let mut dl = self.deferred_list.lock();
+        let payload = match dl.remove(name) {
+            Some(mut d) => {
+                d.payload.merge(payload);
+                d.payload
+            }
+            None => payload,
+        };
+        dl.insert(
+            String::from(name),
+            DeferredInsertion {
+                until: Instant::now() + quiet,
+                window,
+                payload,
+            },
+        );
+        true
+    }
+
+    /// Remove a condition if present and return `true`, `false` if not present
+    pub fn remove_condition(&self, name: &str) -> bool {
+        self.take_condition(name).is_some()
+    }
+
+    /// Remove a condition if present and return the attached payload
+    pub fn take_condition(&self, name: &str) -> Option<EventPayload> {
+        self.release_deferred();
+        self.execution_list.clone().lock().remove(name)
+    }
+
     /// Clear the execution list (result can be ignored)
     pub fn clear(&self) -> Result<bool> {
+        self.deferred_list.lock().clear();
         if self.execution_list.clone().lock().is_empty() {
             Ok(false)
         } else {
@@ -102,6 +209,7 @@ pub struct BucketCondition {
     task_names: Vec<String>,
     recurring: bool,
     max_retries: i64,
+    retry_policy: RetryPolicy,
     exec_sequence: bool,
     break_on_failure: bool,
     break_on_success: bool,
@@ -114,6 +222,7 @@ pub struct BucketCondition {
     startup_time: Option<Instant>,
     task_registry: Option<&'static TaskRegistry>,
     left_retries: i64,
+    retry_wait: Option<RetryWait>,
     tasks_failed: bool,
 
     // specific members
@@ -122,6 +231,7 @@ pub struct BucketCondition {
 
     // internal values
     execution_bucket: Option<&'static ExecutionBucket>,
+    event_payload: Option<EventPayload>,
 }
 
 // implement the hash protocol
@@ -131,6 +241,9 @@ impl Hash for BucketCondition {
         self.cond_name.hash(state);
         self.recurring.hash(state);
         self.max_retries.hash(state);
+        if self.retry_policy != RetryPolicy::default() {
+            self.retry_policy.hash(state);
+        }
         self.exec_sequence.hash(state);
         self.break_on_failure.hash(state);
         self.break_on_success.hash(state);
@@ -167,6 +280,7 @@ impl BucketCondition {
             task_names: Vec::new(),
             recurring: false,
             max_retries: 0,
+            retry_policy: RetryPolicy::default(),
             exec_sequence: true,
             break_on_failure: false,
             break_on_success: false,
@@ -179,6 +293,7 @@ impl BucketCondition {
             has_succeeded: false,
             task_registry: None,
             left_retries: 0,
+            retry_wait: None,
             tasks_failed: false,
 
             // specific members initialization
@@ -187,6 +302,7 @@ impl BucketCondition {
 
             // specific members initialization
             execution_bucket: None,
+            event_payload: None,
         }
     }
 
@@ -244,6 +360,10 @@ impl BucketCondition {
             "tasks",
             "recurring",
             "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
             "execute_sequence",
             "break_on_failure",
             "break_on_success",
@@ -310,6 +430,7 @@ impl BucketCondition {
         if let Some(v) = cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)? {
             new_condition.max_retries = v;
         }
+        new_condition.retry_policy = cfg_retry_policy(cfgmap)?;
         if let Some(v) = cfg_bool(cfgmap, "execute_sequence")? {
             new_condition.exec_sequence = v;
         }
@@ -348,6 +469,10 @@ impl BucketCondition {
             "tasks",
             "recurring",
             "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
             "execute_sequence",
             "break_on_failure",
             "break_on_success",
@@ -392,6 +517,7 @@ impl BucketCondition {
 
         cfg_bool(cfgmap, "recurring")?;
         cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)?;
+        cfg_retry_policy(cfgmap)?;
         cfg_bool(cfgmap, "execute_sequence")?;
         cfg_bool(cfgmap, "break_on_failure")?;
         cfg_bool(cfgmap, "break_on_success")?;
@@ -479,9 +605,23 @@ impl Condition for BucketCondition {
         self.last_succeeded = None;
         self.has_succeeded = false;
         self.left_retries = self.max_retries + 1;
+        self.retry_wait = None;
         self.tasks_failed = true;
     }
 
+    fn restore_state(
+        &mut self,
+        has_succeeded: bool,
+        last_succeeded: Option<Instant>,
+        left_retries: Option<u64>,
+    ) {
+        self.has_succeeded = has_succeeded;
+        self.last_succeeded = last_succeeded;
+        if let Some(left_retries) = left_retries {
+            self.left_retries = left_retries as i64;
+        }
+    }
+
     fn left_retries(&self) -> Option<u64> {
         if self.max_retries < 0 {
             None
@@ -496,6 +636,18 @@ impl Condition for BucketCondition {
         }
     }
 
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    fn retry_wait(&self) -> Option<RetryWait> {
+        self.retry_wait
+    }
+
+    fn set_retry_wait(&mut self, wait: Option<RetryWait>) {
+        self.retry_wait = wait;
+    }
+
     fn start(&mut self) {
         self.suspended = false;
         self.left_retries = self.max_retries + 1;
@@ -525,6 +677,10 @@ impl Condition for BucketCondition {
         }
     }
 
+    fn event_payload(&self) -> Option<EventPayload> {
+        self.event_payload.clone()
+    }
+
     fn task_names(&self) -> Vec<String> {
         self.task_names.clone()
     }
@@ -574,8 +730,8 @@ impl Condition for BucketCondition {
         );
         if let Some(bucket) = self.execution_bucket {
             let name = self.get_name();
-            if bucket.has_condition(&name) {
-                bucket.remove_condition(&name);
+            if let Some(payload) = bucket.take_condition(&name) {
+                self.event_payload = Some(payload);
                 self.log(
                     LogType::Debug,
                     LOG_WHEN_END,
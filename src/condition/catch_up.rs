@@ -0,0 +1,76 @@
+//! Catch-up policies for time based conditions
+//!
+//! Time based conditions are only verified when their scheduled instants
+//! fall within a scheduler tick: if the computer is suspended, or if the
+//! scheduler is not running at that moment, the occurrence is simply lost.
+//! A _catch-up_ policy allows these conditions to run their tasks anyway,
+//! as soon as possible, for occurrences that have been missed since the last
+//! time they fired. The available policies are:
+//!
+//! * `none` - missed occurrences are ignored (the default)
+//! * `once` - tasks are run once if at least one occurrence was missed
+//! * `all` - tasks are run once for each missed occurrence, optionally up to
+//!   a maximum number of times.
+//!
+//! Missed occurrences are run one per tick, as any other condition success.
+
+use cfgmap::CfgMap;
+
+use crate::cfghelp::*;
+use crate::constants::*;
+use crate::utility::result::Result;
+
+/// The catch-up policy for missed occurrences
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CatchUp {
+    None,
+    Once,
+    All(Option<u64>),
+}
+
+impl CatchUp {
+    /// Return how many of the provided missed occurrences should be run
+    pub fn runs(&self, missed: u64) -> u64 {
+        match self {
+            CatchUp::None => 0,
+            CatchUp::Once => missed.min(1),
+            CatchUp::All(None) => missed,
+            CatchUp::All(Some(max)) => missed.min(*max),
+        }
+    }
+
+    /// Return the number of missed occurrences beyond which counting is
+    /// useless, as no more runs would be performed anyway
+    pub fn limit(&self) -> Option<u64> {
+        match self {
+            CatchUp::None => Some(0),
+            CatchUp::Once => Some(1),
+            CatchUp::All(max) => *max,
+        }
+    }
+}
+
+/// Retrieve the catch-up policy from a condition configuration
+///
+/// Both the `catch_up` and the `max_catch_up_runs` entries are checked: the
+/// latter is only accepted when the policy is `all`.
+pub fn cfg_catch_up(cfgmap: &CfgMap) -> Result<Option<CatchUp>> {
+    let policy = cfg_string_check_within(cfgmap, "catch_up", &vec!["none", "once", "all"])?;
+    let cur_key = "max_catch_up_runs";
+    let max_runs = cfg_int_check_above_eq(cfgmap, cur_key, 1)?.map(|v| v as u64);
+    if max_runs.is_some() && policy.as_deref() != Some("all") {
+        return Err(cfg_err_invalid_config(
+            cur_key,
+            STR_UNKNOWN_VALUE,
+            ERR_INVALID_PARAMETER,
+        ));
+    }
+
+    Ok(policy.map(|s| match s.as_str() {
+        "once" => CatchUp::Once,
+        "all" => CatchUp::All(max_runs),
+        _ => CatchUp::None,
+    }))
+}
+
+// end.
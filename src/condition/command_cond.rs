@@ -28,6 +28,7 @@ use subprocess::{Exec, Redirection};
 use cfgmap::CfgMap;
 
 use super::base::Condition;
+use super::retry::{RetryPolicy, RetryWait, cfg_retry_policy};
 use crate::common::cmditem::*;
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::{Error, Kind, Result};
@@ -48,6 +49,7 @@ pub struct CommandCondition {
     task_names: Vec<String>,
     recurring: bool,
     max_retries: i64,
+    retry_policy: RetryPolicy,
     exec_sequence: bool,
     break_on_failure: bool,
     break_on_success: bool,
@@ -60,6 +62,7 @@ pub struct CommandCondition {
     startup_time: Option<Instant>,
     task_registry: Option<&'static TaskRegistry>,
     left_retries: i64,
+    retry_wait: Option<RetryWait>,
     tasks_failed: bool,
 
     // specific members
@@ -104,6 +107,9 @@ impl Hash for CommandCondition {
         self.cond_name.hash(state);
         self.recurring.hash(state);
         self.max_retries.hash(state);
+        if self.retry_policy != RetryPolicy::default() {
+            self.retry_policy.hash(state);
+        }
         self.exec_sequence.hash(state);
         self.break_on_failure.hash(state);
         self.break_on_success.hash(state);
@@ -193,6 +199,7 @@ impl CommandCondition {
             task_names: Vec::new(),
             recurring: false,
             max_retries: 0,
+            retry_policy: RetryPolicy::default(),
             exec_sequence: true,
             break_on_failure: false,
             break_on_success: false,
@@ -205,6 +212,7 @@ impl CommandCondition {
             has_succeeded: false,
             task_registry: None,
             left_retries: 0,
+            retry_wait: None,
             tasks_failed: false,
 
             // specific members initialization
@@ -460,6 +468,10 @@ impl CommandCondition {
             "tasks",
             "recurring",
             "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
             "execute_sequence",
             "break_on_failure",
             "break_on_success",
@@ -538,6 +550,7 @@ impl CommandCondition {
         if let Some(v) = cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)? {
             new_condition.max_retries = v;
         }
+        new_condition.retry_policy = cfg_retry_policy(cfgmap)?;
         if let Some(v) = cfg_bool(cfgmap, "execute_sequence")? {
             new_condition.exec_sequence = v;
         }
@@ -663,6 +676,10 @@ impl CommandCondition {
             "tasks",
             "recurring",
             "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
             "execute_sequence",
             "break_on_failure",
             "break_on_success",
@@ -731,6 +748,7 @@ impl CommandCondition {
 
         cfg_bool(cfgmap, "recurring")?;
         cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)?;
+        cfg_retry_policy(cfgmap)?;
         cfg_bool(cfgmap, "execute_sequence")?;
         cfg_bool(cfgmap, "break_on_failure")?;
         cfg_bool(cfgmap, "break_on_success")?;
@@ -881,9 +899,23 @@ impl Condition for CommandCondition {
         self.last_succeeded = None;
         self.has_succeeded = false;
         self.left_retries = self.max_retries + 1;
+        self.retry_wait = None;
         self.tasks_failed = true;
     }
 
+    fn restore_state(
+        &mut self,
+        has_succeeded: bool,
+        last_succeeded: Option<Instant>,
+        left_retries: Option<u64>,
+    ) {
+        self.has_succeeded = has_succeeded;
+        self.last_succeeded = last_succeeded;
+        if let Some(left_retries) = left_retries {
+            self.left_retries = left_retries as i64;
+        }
+    }
+
     fn left_retries(&self) -> Option<u64> {
         if self.max_retries < 0 {
             None
@@ -898,6 +930,18 @@ impl Condition for CommandCondition {
         }
     }
 
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    fn retry_wait(&self) -> Option<RetryWait> {
+        self.retry_wait
+    }
+
+    fn set_retry_wait(&mut self, wait: Option<RetryWait>) {
+        self.retry_wait = wait;
+    }
+
     fn start(&mut self) {
         self.suspended = false;
         self.left_retries = self.max_retries + 1;
@@ -0,0 +1,752 @@
+//! Define a composite condition
+//!
+//! This type of condition combines the outcomes of other conditions using a
+//! boolean expression, in which conditions are referred to by name and can be
+//! combined using the `and`, `or` and `not` operators and parentheses, for
+//! example: `Idle and (CheckDisk or not OnBattery)`.
+//!
+//! The conditions used in the expression (the _inputs_) can either have tasks
+//! of their own, in which case they are checked independently and the outcome
+//! of their most recent check is used, or be _non-triggering_, that is have
+//! no associated tasks: in the latter case they are only checked when the
+//! composite condition is evaluated. Composite conditions cannot be used as
+//! inputs of other composite conditions.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::Instant;
+
+use cfgmap::CfgMap;
+
+use super::base::Condition;
+use super::retry::{RetryPolicy, RetryWait, cfg_retry_policy};
+use super::registry::ConditionRegistry;
+use crate::utility::logging::{LogType, log};
+use crate::utility::result::{Error, Kind, Result};
+use crate::task::registry::TaskRegistry;
+use crate::{cfg_mandatory, constants::*};
+
+use crate::cfghelp::*;
+
+// the tokens of a boolean expression
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Name(String),
+}
+
+// split an expression into tokens: names are checked against the same rules
+// that apply to condition names, and the operators are case insensitive
+fn tokenize(s: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    for c in s.chars().chain(std::iter::once(' ')) {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+        if !word.is_empty() {
+            tokens.push(match word.to_ascii_lowercase().as_str() {
+                "and" => Token::And,
+                "or" => Token::Or,
+                "not" => Token::Not,
+                _ if RE_COND_NAME.is_match(&word) => Token::Name(word.clone()),
+                _ => return None,
+            });
+            word.clear();
+        }
+        match c {
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            c if c.is_whitespace() => (),
+            _ => return None,
+        }
+    }
+    Some(tokens)
+}
+
+/// A parsed boolean expression over condition outcomes
+#[derive(Hash)]
+enum Expression {
+    Input(String),
+    Not(Box<Expression>),
+    And(Vec<Expression>),
+    Or(Vec<Expression>),
+}
+
+impl Expression {
+    /// Parse an expression, returning `None` if it is not valid
+    pub fn parse(s: &str) -> Option<Expression> {
+        let tokens = tokenize(s)?;
+        let mut pos = 0;
+        let expr = Expression::parse_or(&tokens, &mut pos)?;
+        if pos == tokens.len() { Some(expr) } else { None }
+    }
+
+    // the usual precedence applies, that is: `not`, then `and`, then `or`
+    fn parse_or(tokens: &[Token], pos: &mut usize) -> Option<Expression> {
+        let mut operands = vec![Expression::parse_and(tokens, pos)?];
+        while let Some(Token::Or) = tokens.get(*pos) {
+            *pos += 1;
+            operands.push(Expression::parse_and(tokens, pos)?);
+        }
+        Some(if operands.len() == 1 {
+            operands.pop().unwrap()
+        } else {
+            Expression::Or(operands)
+        })
+    }
+
+    fn parse_and(tokens: &[Token], pos: &mut usize) -> Option<Expression> {
+        let mut operands = vec![Expression::parse_not(tokens, pos)?];
+        while let Some(Token::And) = tokens.get(*pos) {
+            *pos += 1;
+            operands.push(Expression::parse_not(tokens, pos)?);
+        }
+        Some(if operands.len() == 1 {
+            operands.pop().unwrap()
+        } else {
+            Expression::And(operands)
+        })
+    }
+
+    fn parse_not(tokens: &[Token], pos: &mut usize) -> Option<Expression> {
+        let token = tokens.get(*pos)?;
+        *pos += 1;
+        match token {
+            Token::Not => Some(Expression::Not(Box::new(Expression::parse_not(
+                tokens, pos,
+            )?))),
+            Token::Name(name) => Some(Expression::Input(name.clone())),
+            Token::Open => {
+                let expr = Expression::parse_or(tokens, pos)?;
+                if let Some(Token::Close) = tokens.get(*pos) {
+                    *pos += 1;
+                    Some(expr)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Collect the names of the conditions used in the expression, once each
+    pub fn inputs(&self, names: &mut Vec<String>) {
+        match self {
+            Expression::Input(name) => {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+            Expression::Not(expr) => expr.inputs(names),
+            Expression::And(operands) | Expression::Or(operands) => {
+                for expr in operands {
+                    expr.inputs(names);
+                }
+            }
+        }
+    }
+
+    /// Evaluate the expression given the outcomes of its inputs
+    pub fn eval(&self, outcomes: &HashMap<String, bool>) -> bool {
+        match self {
+            Expression::Input(name) => *outcomes.get(name).unwrap_or(&false),
+            Expression::Not(expr) => !expr.eval(outcomes),
+            Expression::And(operands) => operands.iter().all(|x| x.eval(outcomes)),
+            Expression::Or(operands) => operands.iter().any(|x| x.eval(outcomes)),
+        }
+    }
+}
+
+/// Composite Condition
+///
+/// This condition is verified when the boolean expression that combines the
+/// outcomes of other conditions is true.
+pub struct CompositeCondition {
+    // commom members
+    // parameters
+    cond_id: i64,
+    cond_name: String,
+    task_names: Vec<String>,
+    recurring: bool,
+    max_retries: i64,
+    retry_policy: RetryPolicy,
+    exec_sequence: bool,
+    break_on_failure: bool,
+    break_on_success: bool,
+    suspended: bool,
+
+    // internal values
+    has_succeeded: bool,
+    last_tested: Option<Instant>,
+    last_succeeded: Option<Instant>,
+    startup_time: Option<Instant>,
+    task_registry: Option<&'static TaskRegistry>,
+    left_retries: i64,
+    retry_wait: Option<RetryWait>,
+    tasks_failed: bool,
+
+    // specific members
+    // parameters
+    expression: Option<Expression>,
+
+    // internal values
+    inputs: Vec<String>,
+    cond_registry: Option<&'static ConditionRegistry>,
+}
+
+// implement the hash protocol
+impl Hash for CompositeCondition {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // common part
+        self.cond_name.hash(state);
+        self.recurring.hash(state);
+        self.max_retries.hash(state);
+        if self.retry_policy != RetryPolicy::default() {
+            self.retry_policy.hash(state);
+        }
+        self.exec_sequence.hash(state);
+        self.break_on_failure.hash(state);
+        self.break_on_success.hash(state);
+        // suspended is more a status: let's not consider it yet
+        // self.suspended.hash(state);
+        // task order is significant: hash on vec is not sorted
+        self.task_names.hash(state);
+
+        // specific part
+        self.expression.hash(state);
+    }
+}
+
+#[allow(dead_code)]
+impl CompositeCondition {
+    /// Create a new composite condition with the given name
+    pub fn new(name: &str) -> Self {
+        log(
+            LogType::Debug,
+            LOG_EMITTER_CONDITION_COMPOSITE,
+            LOG_ACTION_NEW,
+            Some((name, 0)),
+            LOG_WHEN_INIT,
+            LOG_STATUS_MSG,
+            &format!("CONDITION {name}: creating a new composite condition"),
+        );
+        CompositeCondition {
+            // common members initialization
+            // reset ID
+            cond_id: 0,
+
+            // parameters
+            cond_name: String::from(name),
+            task_names: Vec::new(),
+            recurring: false,
+            max_retries: 0,
+            retry_policy: RetryPolicy::default(),
+            exec_sequence: true,
+            break_on_failure: false,
+            break_on_success: false,
+            suspended: true,
+
+            // internal values
+            startup_time: None,
+            last_tested: None,
+            last_succeeded: None,
+            has_succeeded: false,
+            task_registry: None,
+            left_retries: 0,
+            retry_wait: None,
+            tasks_failed: false,
+
+            // specific members initialization
+            // parameters
+            expression: None,
+
+            // internal values
+            inputs: Vec::new(),
+            cond_registry: None,
+        }
+    }
+
+    // constructor modifiers
+    /// Set the command execution to sequence or parallel
+    pub fn execs_sequentially(mut self, yes: bool) -> Self {
+        self.exec_sequence = yes;
+        self
+    }
+
+    /// If true, *sequential* task execution will break on first success
+    pub fn breaks_on_success(mut self, yes: bool) -> Self {
+        self.break_on_success = yes;
+        self
+    }
+
+    /// If true, *sequential* task execution will break on first failure
+    pub fn breaks_on_failure(mut self, yes: bool) -> Self {
+        self.break_on_failure = yes;
+        self
+    }
+
+    /// If true, create a recurring condition
+    pub fn repeats(mut self, yes: bool) -> Self {
+        self.recurring = yes;
+        self
+    }
+
+    /// Retry `num` times on task failure if not recurring
+    pub fn retries(mut self, num: i64) -> Self {
+        assert!(num >= -1, "max number of retries must be positive or -1");
+        self.max_retries = num;
+        self
+    }
+
+    /// Set the registry where the inputs of the expression are found
+    pub fn set_condition_registry(&mut self, reg: &'static ConditionRegistry) {
+        self.cond_registry = Some(reg);
+    }
+
+    /// Set the boolean expression to be evaluated
+    pub fn set_expression(&mut self, expression: &str) -> Result<bool> {
+        let Some(expr) = Expression::parse(expression) else {
+            return Err(Error::new(
+                Kind::Invalid,
+                &format!("{ERR_INVALID_COMPOSITE_EXPR}: `{expression}`"),
+            ));
+        };
+        self.inputs.clear();
+        expr.inputs(&mut self.inputs);
+        self.expression = Some(expr);
+        Ok(true)
+    }
+
+    /// Load a `CompositeCondition` from a [`CfgMap`](https://docs.rs/cfgmap/latest/)
+    ///
+    /// The `CompositeCondition` is initialized according to the values
+    /// provided in the `CfgMap` argument. If the `CfgMap` format does not
+    /// comply with the requirements of a `CompositeCondition` an error is
+    /// raised. The existence of the referenced conditions is not verified
+    /// here, as they might be registered later.
+    pub fn load_cfgmap(
+        cfgmap: &CfgMap,
+        cond_registry: &'static ConditionRegistry,
+        task_registry: &'static TaskRegistry,
+    ) -> Result<CompositeCondition> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "expression",
+            "tasks",
+            "recurring",
+            "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
+            "execute_sequence",
+            "break_on_failure",
+            "break_on_success",
+            "suspended",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // type and name are both mandatory but type is only checked
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "composite"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_COND_NAME))?.unwrap();
+
+        // specific mandatory parameter retrieval
+        let cur_key = "expression";
+        let expression = cfg_mandatory!(cfg_string(cfgmap, cur_key))?.unwrap();
+
+        // initialize the structure
+        let mut new_condition = CompositeCondition::new(&name);
+        new_condition.task_registry = Some(task_registry);
+        new_condition.cond_registry = Some(cond_registry);
+        if new_condition.set_expression(&expression).is_err() {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                &expression,
+                ERR_INVALID_COMPOSITE_EXPR,
+            ));
+        }
+
+        // by default make condition active if loaded from configuration: if
+        // the configuration changes this state the condition will not start
+        new_condition.suspended = false;
+
+        // common optional parameter initialization
+
+        // tags are always simply checked this way as no value is needed
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // retrieve task list and try to directly add each task
+        let cur_key = "tasks";
+        if let Some(v) = cfg_vec_string_check_regex(cfgmap, cur_key, &RE_TASK_NAME)? {
+            for s in v {
+                if !new_condition.add_task(&s)? {
+                    return Err(cfg_err_invalid_config(cur_key, &s, ERR_INVALID_TASK));
+                }
+            }
+        }
+
+        if let Some(v) = cfg_bool(cfgmap, "recurring")? {
+            new_condition.recurring = v;
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)? {
+            new_condition.max_retries = v;
+        }
+        new_condition.retry_policy = cfg_retry_policy(cfgmap)?;
+        if let Some(v) = cfg_bool(cfgmap, "execute_sequence")? {
+            new_condition.exec_sequence = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "break_on_failure")? {
+            new_condition.break_on_failure = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "break_on_success")? {
+            new_condition.break_on_success = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "suspended")? {
+            new_condition.suspended = v;
+        }
+
+        // start the condition if the configuration did not suspend it
+        if !new_condition.suspended {
+            new_condition.start();
+        }
+
+        Ok(new_condition)
+    }
+
+    /// Check a configuration map and return item name if Ok
+    ///
+    /// The check is performed exactly in the same way and in the same order
+    /// as in `load_cfgmap`, the only difference is that no actual item is
+    /// created and that a name is returned, which is the name of the item that
+    /// _would_ be created via the equivalent call to `load_cfgmap`; moreover
+    /// the conditions used in the expression are checked against the list of
+    /// the ones that can be used as inputs.
+    pub fn check_cfgmap(
+        cfgmap: &CfgMap,
+        available_tasks: &Vec<&str>,
+        available_inputs: &Vec<&str>,
+    ) -> Result<String> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "expression",
+            "tasks",
+            "recurring",
+            "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
+            "execute_sequence",
+            "break_on_failure",
+            "break_on_success",
+            "suspended",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // common mandatory parameter check
+
+        // type and name are both mandatory: type is checked and name is kept
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "composite"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_COND_NAME))?.unwrap();
+
+        // specific mandatory parameter check
+        let cur_key = "expression";
+        let expression = cfg_mandatory!(cfg_string(cfgmap, cur_key))?.unwrap();
+        let Some(expr) = Expression::parse(&expression) else {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                &expression,
+                ERR_INVALID_COMPOSITE_EXPR,
+            ));
+        };
+        let mut inputs = Vec::new();
+        expr.inputs(&mut inputs);
+        for s in inputs {
+            if !available_inputs.contains(&s.as_str()) {
+                return Err(cfg_err_invalid_config(
+                    cur_key,
+                    &s,
+                    ERR_INVALID_COMPOSITE_INPUT,
+                ));
+            }
+        }
+
+        // also for optional parameters just check and throw away the result
+
+        // tags are always simply checked this way
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // check configuration task list against the provided ones
+        let cur_key = "tasks";
+        if let Some(v) = cfg_vec_string_check_regex(cfgmap, cur_key, &RE_TASK_NAME)? {
+            for s in v {
+                if !available_tasks.contains(&s.as_str()) {
+                    return Err(cfg_err_invalid_config(cur_key, &s, ERR_INVALID_TASK));
+                }
+            }
+        }
+
+        cfg_bool(cfgmap, "recurring")?;
+        cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)?;
+        cfg_retry_policy(cfgmap)?;
+        cfg_bool(cfgmap, "execute_sequence")?;
+        cfg_bool(cfgmap, "break_on_failure")?;
+        cfg_bool(cfgmap, "break_on_success")?;
+        cfg_bool(cfgmap, "suspended")?;
+
+        Ok(name)
+    }
+}
+
+impl Condition for CompositeCondition {
+    fn set_id(&mut self, id: i64) {
+        self.cond_id = id;
+    }
+    fn get_name(&self) -> String {
+        self.cond_name.clone()
+    }
+    fn get_id(&self) -> i64 {
+        self.cond_id
+    }
+    fn get_type(&self) -> &str {
+        "composite"
+    }
+
+    /// Return a hash of this item for comparison
+    fn _hash(&self) -> u64 {
+        let mut s = DefaultHasher::new();
+        self.hash(&mut s);
+        s.finish()
+    }
+
+    fn set_task_registry(&mut self, reg: &'static TaskRegistry) {
+        self.task_registry = Some(reg);
+    }
+
+    fn task_registry(&self) -> Option<&'static TaskRegistry> {
+        self.task_registry
+    }
+
+    fn suspended(&self) -> bool {
+        self.suspended
+    }
+    fn recurring(&self) -> bool {
+        self.recurring
+    }
+    fn has_succeeded(&self) -> bool {
+        self.has_succeeded
+    }
+
+    fn exec_sequence(&self) -> bool {
+        self.exec_sequence
+    }
+    fn break_on_success(&self) -> bool {
+        self.break_on_success
+    }
+    fn break_on_failure(&self) -> bool {
+        self.break_on_failure
+    }
+
+    fn last_checked(&self) -> Option<Instant> {
+        self.last_tested
+    }
+    fn last_succeeded(&self) -> Option<Instant> {
+        self.last_succeeded
+    }
+    fn startup_time(&self) -> Option<Instant> {
+        self.startup_time
+    }
+
+    fn set_checked(&mut self) {
+        self.last_tested = Some(Instant::now());
+    }
+
+    fn set_succeeded(&mut self) {
+        self.last_succeeded = self.last_tested;
+        self.has_succeeded = true;
+    }
+
+    fn reset_succeeded(&mut self) {
+        self.last_succeeded = None;
+        self.has_succeeded = false;
+    }
+
+    fn reset(&mut self) {
+        self.last_tested = None;
+        self.last_succeeded = None;
+        self.has_succeeded = false;
+        self.left_retries = self.max_retries + 1;
+        self.retry_wait = None;
+        self.tasks_failed = true;
+    }
+
+    fn restore_state(
+        &mut self,
+        has_succeeded: bool,
+        last_succeeded: Option<Instant>,
+        left_retries: Option<u64>,
+    ) {
+        self.has_succeeded = has_succeeded;
+        self.last_succeeded = last_succeeded;
+        if let Some(left_retries) = left_retries {
+            self.left_retries = left_retries as i64;
+        }
+    }
+
+    fn left_retries(&self) -> Option<u64> {
+        if self.max_retries < 0 {
+            None
+        } else {
+            Some(self.left_retries as u64)
+        }
+    }
+
+    fn set_retried(&mut self) {
+        if self.left_retries > 0 {
+            self.left_retries -= 1;
+        }
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    fn retry_wait(&self) -> Option<RetryWait> {
+        self.retry_wait
+    }
+
+    fn set_retry_wait(&mut self, wait: Option<RetryWait>) {
+        self.retry_wait = wait;
+    }
+
+    fn start(&mut self) {
+        self.suspended = false;
+        self.left_retries = self.max_retries + 1;
+        self.startup_time = Some(Instant::now());
+
+        // set the tasks_failed flag upon start: no task has been run now
+        // and this is equivalent to a failure; the flag was set to `false`
+        // upon creation in order to have a zero-initialization
+        self.tasks_failed = true;
+    }
+
+    fn suspend(&mut self) -> bool {
+        if self.suspended {
+            false
+        } else {
+            self.suspended = true;
+            true
+        }
+    }
+
+    fn resume(&mut self) -> bool {
+        if self.suspended {
+            self.suspended = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn task_names(&self) -> Vec<String> {
+        self.task_names.clone()
+    }
+
+    fn any_tasks_failed(&self) -> bool {
+        self.tasks_failed
+    }
+
+    fn set_tasks_failed(&mut self, failed: bool) {
+        self.tasks_failed = failed;
+    }
+
+    fn _add_task(&mut self, name: &str) -> bool {
+        let name = String::from(name);
+        if self.task_names.contains(&name) {
+            false
+        } else {
+            self.task_names.push(name);
+            true
+        }
+    }
+
+    fn _remove_task(&mut self, name: &str) -> bool {
+        let name = String::from(name);
+        if self.task_names.contains(&name) {
+            self.task_names
+                .remove(self.task_names.iter().position(|x| x == &name).unwrap());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Mandatory check function
+    ///
+    /// This function actually performs the test: all the inputs are evaluated
+    /// first, regardless of whether or not their outcome is actually needed,
+    /// so that non-triggering inputs are checked at every tick, and then the
+    /// expression is evaluated using their outcomes.
+    fn _check_condition(&mut self) -> Result<Option<bool>> {
+        assert!(
+            self.cond_registry.is_some(),
+            "condition registry not initialized",
+        );
+        let Some(expression) = &self.expression else {
+            return Err(Error::new(Kind::Invalid, ERR_INVALID_COMPOSITE_EXPR));
+        };
+
+        self.log(
+            LogType::Debug,
+            LOG_WHEN_START,
+            LOG_STATUS_MSG,
+            "checking composite condition",
+        );
+
+        let registry = self.cond_registry.unwrap();
+        let mut outcomes = HashMap::new();
+        for name in self.inputs.iter() {
+            let outcome = registry.evaluate_input(name)?;
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_PROC,
+                LOG_STATUS_MSG,
+                &format!("input condition {name} evaluated to {outcome}"),
+            );
+            outcomes.insert(name.clone(), outcome);
+        }
+
+        Ok(Some(expression.eval(&outcomes)))
+    }
+}
+
+// end.
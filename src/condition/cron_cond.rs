@@ -0,0 +1,905 @@
+//! Define a cron expression based condition
+//!
+//! This type of condition is verified when the current time has just reached
+//! one of the instants described by a list of cron expressions. Expressions
+//! follow the usual crontab syntax, and consist of either five fields:
+//!
+//! * minute (0-59)
+//! * hour (0-23)
+//! * day of month (1-31)
+//! * month (1-12, or `jan`..`dec`)
+//! * day of week (0-7, where both 0 and 7 are Sunday, or `sun`..`sat`)
+//!
+//! or six fields, in which case the first one specifies the second (0-59).
+//! Each field accepts `*`, single values, ranges (`a-b`), steps (`*/n`,
+//! `a-b/n` and `a/n`) and comma separated lists of the above. The usual
+//! shortcuts `@yearly`, `@annually`, `@monthly`, `@weekly`, `@daily`,
+//! `@midnight` and `@hourly` are also accepted. As in cron, when both the
+//! day of month and the day of week are restricted, a date matches if it
+//! satisfies at least one of them.
+//!
+//! Occurrences that have been missed, because the computer was suspended or
+//! the scheduler was not running, can be recovered using a catch-up policy.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::Instant;
+
+use cfgmap::CfgMap;
+use chrono::TimeDelta;
+use chrono::prelude::*;
+
+use super::base::Condition;
+use super::retry::{RetryPolicy, RetryWait, cfg_retry_policy};
+use super::catch_up::{CatchUp, cfg_catch_up};
+use crate::utility::logging::{LogType, log};
+use crate::utility::result::{Error, Kind, Result};
+use crate::task::registry::TaskRegistry;
+use crate::{cfg_mandatory, constants::*};
+
+use crate::cfghelp::*;
+
+// names accepted in the month and day of week fields, in the same order as
+// their numeric values starting from the lowest one
+const CRON_MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const CRON_WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+// a parsed cron expression: each field is a bit mask where bit `n` is set
+// when value `n` is accepted; the structure remains private as in the case
+// of time specifications, because it can only be built by parsing a string
+#[derive(Hash)]
+struct CronSpecification {
+    expression: String,
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64, // Sunday=0, ..., Saturday=6
+    any_day: bool,
+    any_weekday: bool,
+}
+
+// parse a single value, either numeric or (if names are provided) symbolic,
+// where the first name corresponds to the lowest accepted value
+fn cron_parse_value(s: &str, min: u32, max: u32, names: &[&str]) -> Option<u32> {
+    let v = if let Ok(n) = s.parse::<u32>() {
+        n
+    } else {
+        let s = s.to_ascii_lowercase();
+        min + names.iter().position(|x| *x == s)? as u32
+    };
+    if v < min || v > max { None } else { Some(v) }
+}
+
+// parse a field into a bit mask of accepted values
+fn cron_parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Option<u64> {
+    let mut mask = 0_u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<u32>().ok()?)),
+            None => (part, None),
+        };
+        if step == Some(0) {
+            return None;
+        }
+        let (first, last) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (
+                cron_parse_value(a, min, max, names)?,
+                cron_parse_value(b, min, max, names)?,
+            )
+        } else {
+            let a = cron_parse_value(range, min, max, names)?;
+            // a single value followed by a step means "from here to the end"
+            if step.is_some() { (a, max) } else { (a, a) }
+        };
+        if first > last {
+            return None;
+        }
+        for v in (first..=last).step_by(step.unwrap_or(1) as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Some(mask)
+}
+
+impl CronSpecification {
+    /// Parse a cron expression, returning `None` if it is not valid
+    pub fn parse(expression: &str) -> Option<CronSpecification> {
+        let expanded = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            s => s,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let (seconds, fields) = match fields.len() {
+            5 => (1, &fields[..]),
+            6 => (cron_parse_field(fields[0], 0, 59, &[])?, &fields[1..]),
+            _ => return None,
+        };
+
+        // day of week accepts 7 as an alias for Sunday, which is folded to 0
+        let mut weekdays = cron_parse_field(fields[4], 0, 7, &CRON_WEEKDAY_NAMES)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Some(CronSpecification {
+            expression: String::from(expression.trim()),
+            seconds,
+            minutes: cron_parse_field(fields[0], 0, 59, &[])?,
+            hours: cron_parse_field(fields[1], 0, 23, &[])?,
+            days: cron_parse_field(fields[2], 1, 31, &[])?,
+            months: cron_parse_field(fields[3], 1, 12, &CRON_MONTH_NAMES)?,
+            weekdays,
+            any_day: fields[2].starts_with('*'),
+            any_weekday: fields[4].starts_with('*'),
+        })
+    }
+
+    /// Tell whether or not the provided date and time match the expression,
+    /// regardless of the second
+    pub fn matches_minute<T: Datelike + Timelike>(&self, dt: &T) -> bool {
+        let has = |mask: u64, v: u32| mask & (1 << v) != 0;
+        let day = has(self.days, dt.day());
+        let weekday = has(self.weekdays, dt.weekday().num_days_from_sunday());
+        let day_matches = if self.any_day || self.any_weekday {
+            day && weekday
+        } else {
+            day || weekday
+        };
+
+        day_matches
+            && has(self.minutes, dt.minute())
+            && has(self.hours, dt.hour())
+            && has(self.months, dt.month())
+    }
+
+    /// Tell whether or not the provided date and time match the expression
+    pub fn matches<T: Datelike + Timelike>(&self, dt: &T) -> bool {
+        self.seconds & (1 << dt.second()) != 0 && self.matches_minute(dt)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.expression
+    }
+}
+
+/// Cron Expression Based Condition
+///
+/// This condition is verified when current time matches one of the provided
+/// cron expressions.
+pub struct CronCondition {
+    // commom members
+    // parameters
+    cond_id: i64,
+    cond_name: String,
+    task_names: Vec<String>,
+    recurring: bool,
+    max_retries: i64,
+    retry_policy: RetryPolicy,
+    exec_sequence: bool,
+    break_on_failure: bool,
+    break_on_success: bool,
+    suspended: bool,
+
+    // internal values
+    has_succeeded: bool,
+    last_tested: Option<Instant>,
+    last_succeeded: Option<Instant>,
+    startup_time: Option<Instant>,
+    task_registry: Option<&'static TaskRegistry>,
+    left_retries: i64,
+    retry_wait: Option<RetryWait>,
+    tasks_failed: bool,
+
+    // specific members
+    // parameters
+    cron_specifications: Vec<CronSpecification>,
+    tick_duration: i64,
+    catch_up: CatchUp,
+
+    // internal values
+    last_window_end: Option<DateTime<Local>>,
+    last_fired: Option<DateTime<Local>>,
+    catch_up_from: Option<DateTime<Local>>,
+    catch_up_pending: u64,
+}
+
+// implement the hash protocol
+impl Hash for CronCondition {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // common part
+        self.cond_name.hash(state);
+        self.recurring.hash(state);
+        self.max_retries.hash(state);
+        if self.retry_policy != RetryPolicy::default() {
+            self.retry_policy.hash(state);
+        }
+        self.exec_sequence.hash(state);
+        self.break_on_failure.hash(state);
+        self.break_on_success.hash(state);
+        // suspended is more a status: let's not consider it yet
+        // self.suspended.hash(state);
+        // task order is significant: hash on vec is not sorted
+        self.task_names.hash(state);
+
+        // specific part
+        self.tick_duration.hash(state);
+        self.cron_specifications.hash(state);
+
+        // only hash the catch-up policy when set, so that conditions that do
+        // not use it keep the same hash (and thus their saved state)
+        if self.catch_up != CatchUp::None {
+            self.catch_up.hash(state);
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl CronCondition {
+    /// Create a new cron expression based condition with the given name
+    pub fn new(name: &str) -> Self {
+        log(
+            LogType::Debug,
+            LOG_EMITTER_CONDITION_CRON,
+            LOG_ACTION_NEW,
+            Some((name, 0)),
+            LOG_WHEN_INIT,
+            LOG_STATUS_MSG,
+            &format!("CONDITION {name}: creating a new cron expression based condition"),
+        );
+        CronCondition {
+            // common members initialization
+            // reset ID
+            cond_id: 0,
+
+            // parameters
+            cond_name: String::from(name),
+            task_names: Vec::new(),
+            recurring: false,
+            max_retries: 0,
+            retry_policy: RetryPolicy::default(),
+            exec_sequence: true,
+            break_on_failure: false,
+            break_on_success: false,
+            suspended: true,
+
+            // internal values
+            startup_time: None,
+            last_tested: None,
+            last_succeeded: None,
+            has_succeeded: false,
+            task_registry: None,
+            left_retries: 0,
+            retry_wait: None,
+            tasks_failed: false,
+
+            // specific members initialization
+            // parameters
+            cron_specifications: Vec::new(),
+            tick_duration: 0,
+            catch_up: CatchUp::None,
+
+            // internal values
+            last_window_end: None,
+            last_fired: None,
+            catch_up_from: None,
+            catch_up_pending: 0,
+        }
+    }
+
+    // constructor modifiers
+    /// Set the command execution to sequence or parallel
+    pub fn execs_sequentially(mut self, yes: bool) -> Self {
+        self.exec_sequence = yes;
+        self
+    }
+
+    /// If true, *sequential* task execution will break on first success
+    pub fn breaks_on_success(mut self, yes: bool) -> Self {
+        self.break_on_success = yes;
+        self
+    }
+
+    /// If true, *sequential* task execution will break on first failure
+    pub fn breaks_on_failure(mut self, yes: bool) -> Self {
+        self.break_on_failure = yes;
+        self
+    }
+
+    /// If true, create a recurring condition
+    pub fn repeats(mut self, yes: bool) -> Self {
+        self.recurring = yes;
+        self
+    }
+
+    /// Retry `num` times on task failure if not recurring
+    pub fn retries(mut self, num: i64) -> Self {
+        assert!(num >= -1, "max number of retries must be positive or -1");
+        self.max_retries = num;
+        self
+    }
+
+    /// Set the policy for occurrences missed while not running
+    pub fn catches_up(mut self, policy: CatchUp) -> Self {
+        self.catch_up = policy;
+        self
+    }
+
+    /// Set tick duration after creation
+    pub fn set_tick_duration(&mut self, seconds: u64) -> Result<bool> {
+        if seconds < 1 || seconds > i64::MAX as u64 {
+            Err(Error::new(
+                Kind::Invalid,
+                &format!("{ERR_INVALID_TICK_SECONDS}: {seconds}"),
+            ))
+        } else {
+            self.tick_duration = seconds as i64;
+            Ok(true)
+        }
+    }
+
+    /// Add a cron expression to the list of the ones to be checked
+    pub fn add_cron_expression(&mut self, expression: &str) -> Result<bool> {
+        if let Some(spec) = CronSpecification::parse(expression) {
+            self.cron_specifications.push(spec);
+            Ok(true)
+        } else {
+            Err(Error::new(
+                Kind::Invalid,
+                &format!("{ERR_INVALID_CRONSPEC}: `{expression}`"),
+            ))
+        }
+    }
+
+    /// Load a `CronCondition` from a [`CfgMap`](https://docs.rs/cfgmap/latest/)
+    ///
+    /// The `CronCondition` is initialized according to the values provided
+    /// in the `CfgMap` argument. If the `CfgMap` format does not comply with
+    /// the requirements of a `CronCondition` an error is raised.
+    pub fn load_cfgmap(
+        cfgmap: &CfgMap,
+        task_registry: &'static TaskRegistry,
+    ) -> Result<CronCondition> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "cron_expressions",
+            "catch_up",
+            "max_catch_up_runs",
+            "tasks",
+            "recurring",
+            "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
+            "execute_sequence",
+            "break_on_failure",
+            "break_on_success",
+            "suspended",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // type and name are both mandatory but type is only checked
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "cron"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_COND_NAME))?.unwrap();
+
+        // specific mandatory parameter retrieval
+        let cur_key = "cron_expressions";
+        let expressions = cfg_mandatory!(cfg_vec_string(cfgmap, cur_key))?.unwrap();
+        if expressions.is_empty() {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_MISSING_PARAMETER,
+            ));
+        }
+
+        // initialize the structure
+        let mut new_condition = CronCondition::new(&name);
+        new_condition.task_registry = Some(task_registry);
+
+        // by default make condition active if loaded from configuration: if
+        // the configuration changes this state the condition will not start
+        new_condition.suspended = false;
+
+        // common optional parameter initialization
+
+        // tags are always simply checked this way as no value is needed
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // retrieve task list and try to directly add each task
+        let cur_key = "tasks";
+        if let Some(v) = cfg_vec_string_check_regex(cfgmap, cur_key, &RE_TASK_NAME)? {
+            for s in v {
+                if !new_condition.add_task(&s)? {
+                    return Err(cfg_err_invalid_config(cur_key, &s, ERR_INVALID_TASK));
+                }
+            }
+        }
+
+        if let Some(v) = cfg_bool(cfgmap, "recurring")? {
+            new_condition.recurring = v;
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)? {
+            new_condition.max_retries = v;
+        }
+        new_condition.retry_policy = cfg_retry_policy(cfgmap)?;
+        if let Some(v) = cfg_bool(cfgmap, "execute_sequence")? {
+            new_condition.exec_sequence = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "break_on_failure")? {
+            new_condition.break_on_failure = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "break_on_success")? {
+            new_condition.break_on_success = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "suspended")? {
+            new_condition.suspended = v;
+        }
+
+        // specific optional parameter initialization
+        let cur_key = "cron_expressions";
+        for s in expressions {
+            if new_condition.add_cron_expression(&s).is_err() {
+                return Err(cfg_err_invalid_config(cur_key, &s, ERR_INVALID_CRONSPEC));
+            }
+        }
+
+        if let Some(v) = cfg_catch_up(cfgmap)? {
+            new_condition.catch_up = v;
+        }
+
+        // start the condition if the configuration did not suspend it
+        if !new_condition.suspended {
+            new_condition.start();
+        }
+
+        Ok(new_condition)
+    }
+
+    /// Check a configuration map and return item name if Ok
+    ///
+    /// The check is performed exactly in the same way and in the same order
+    /// as in `load_cfgmap`, the only difference is that no actual item is
+    /// created and that a name is returned, which is the name of the item that
+    /// _would_ be created via the equivalent call to `load_cfgmap`
+    pub fn check_cfgmap(cfgmap: &CfgMap, available_tasks: &Vec<&str>) -> Result<String> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "cron_expressions",
+            "catch_up",
+            "max_catch_up_runs",
+            "tasks",
+            "recurring",
+            "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
+            "execute_sequence",
+            "break_on_failure",
+            "break_on_success",
+            "suspended",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // common mandatory parameter check
+
+        // type and name are both mandatory: type is checked and name is kept
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "cron"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_COND_NAME))?.unwrap();
+
+        // specific mandatory parameter check
+        let cur_key = "cron_expressions";
+        let expressions = cfg_mandatory!(cfg_vec_string(cfgmap, cur_key))?.unwrap();
+        if expressions.is_empty() {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_MISSING_PARAMETER,
+            ));
+        }
+
+        // also for optional parameters just check and throw away the result
+
+        // tags are always simply checked this way
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // check configuration task list against the provided ones
+        let cur_key = "tasks";
+        if let Some(v) = cfg_vec_string_check_regex(cfgmap, cur_key, &RE_TASK_NAME)? {
+            for s in v {
+                if !available_tasks.contains(&s.as_str()) {
+                    return Err(cfg_err_invalid_config(cur_key, &s, ERR_INVALID_TASK));
+                }
+            }
+        }
+
+        cfg_bool(cfgmap, "recurring")?;
+        cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)?;
+        cfg_retry_policy(cfgmap)?;
+        cfg_bool(cfgmap, "execute_sequence")?;
+        cfg_bool(cfgmap, "break_on_failure")?;
+        cfg_bool(cfgmap, "break_on_success")?;
+        cfg_bool(cfgmap, "suspended")?;
+
+        // specific optional parameter check
+        let cur_key = "cron_expressions";
+        for s in expressions {
+            if CronSpecification::parse(&s).is_none() {
+                return Err(cfg_err_invalid_config(cur_key, &s, ERR_INVALID_CRONSPEC));
+            }
+        }
+
+        cfg_catch_up(cfgmap)?;
+
+        Ok(name)
+    }
+
+    // count the occurrences that fell within the provided interval (start
+    // excluded), stopping as soon as the limit set by the catch-up policy is
+    // reached: the interval is scanned one minute at a time, and seconds are
+    // only checked within the minutes that match at least one expression
+    fn missed_occurrences(&self, from: DateTime<Local>, to: DateTime<Local>) -> u64 {
+        let limit = self.catch_up.limit();
+        let from = from.naive_local();
+        let to = to.naive_local();
+        let mut missed = 0;
+        let Some(mut minute) = from.date().and_hms_opt(from.hour(), from.minute(), 0) else {
+            return 0;
+        };
+        while minute <= to && limit.is_none_or(|n| missed < n) {
+            let seconds = self
+                .cron_specifications
+                .iter()
+                .filter(|cspec| cspec.matches_minute(&minute))
+                .fold(0_u64, |mask, cspec| mask | cspec.seconds);
+            if seconds != 0 {
+                for second in 0..60 {
+                    let t = minute + TimeDelta::seconds(second);
+                    if seconds & (1 << second) != 0 && from < t && t <= to {
+                        missed += 1;
+                    }
+                }
+            }
+            minute += TimeDelta::minutes(1);
+        }
+        limit.map_or(missed, |n| missed.min(n))
+    }
+}
+
+impl Condition for CronCondition {
+    fn set_id(&mut self, id: i64) {
+        self.cond_id = id;
+    }
+    fn get_name(&self) -> String {
+        self.cond_name.clone()
+    }
+    fn get_id(&self) -> i64 {
+        self.cond_id
+    }
+    fn get_type(&self) -> &str {
+        "cron"
+    }
+
+    /// Return a hash of this item for comparison
+    fn _hash(&self) -> u64 {
+        let mut s = DefaultHasher::new();
+        self.hash(&mut s);
+        s.finish()
+    }
+
+    fn set_task_registry(&mut self, reg: &'static TaskRegistry) {
+        self.task_registry = Some(reg);
+    }
+
+    fn task_registry(&self) -> Option<&'static TaskRegistry> {
+        self.task_registry
+    }
+
+    fn suspended(&self) -> bool {
+        self.suspended
+    }
+    fn recurring(&self) -> bool {
+        self.recurring
+    }
+    fn has_succeeded(&self) -> bool {
+        self.has_succeeded
+    }
+
+    fn exec_sequence(&self) -> bool {
+        self.exec_sequence
+    }
+    fn break_on_success(&self) -> bool {
+        self.break_on_success
+    }
+    fn break_on_failure(&self) -> bool {
+        self.break_on_failure
+    }
+
+    fn last_checked(&self) -> Option<Instant> {
+        self.last_tested
+    }
+    fn last_succeeded(&self) -> Option<Instant> {
+        self.last_succeeded
+    }
+    fn startup_time(&self) -> Option<Instant> {
+        self.startup_time
+    }
+
+    fn set_checked(&mut self) {
+        self.last_tested = Some(Instant::now());
+    }
+
+    fn set_succeeded(&mut self) {
+        self.last_succeeded = self.last_tested;
+        self.has_succeeded = true;
+        self.last_fired = Some(Local::now());
+    }
+
+    fn reset_succeeded(&mut self) {
+        self.last_succeeded = None;
+        self.has_succeeded = false;
+    }
+
+    fn reset(&mut self) {
+        self.last_tested = None;
+        self.last_succeeded = None;
+        self.has_succeeded = false;
+        self.left_retries = self.max_retries + 1;
+        self.retry_wait = None;
+        self.tasks_failed = true;
+        self.catch_up_pending = 0;
+    }
+
+    fn restore_state(
+        &mut self,
+        has_succeeded: bool,
+        last_succeeded: Option<Instant>,
+        left_retries: Option<u64>,
+    ) {
+        self.has_succeeded = has_succeeded;
+        self.last_succeeded = last_succeeded;
+        if let Some(left_retries) = left_retries {
+            self.left_retries = left_retries as i64;
+        }
+    }
+
+    fn last_fired(&self) -> Option<DateTime<Local>> {
+        if self.catch_up == CatchUp::None {
+            None
+        } else {
+            self.last_fired
+        }
+    }
+
+    fn restore_last_fired(&mut self, when: DateTime<Local>) {
+        self.last_fired = Some(when);
+        self.catch_up_from = Some(when);
+    }
+
+    fn left_retries(&self) -> Option<u64> {
+        if self.max_retries < 0 {
+            None
+        } else {
+            Some(self.left_retries as u64)
+        }
+    }
+
+    fn set_retried(&mut self) {
+        if self.left_retries > 0 {
+            self.left_retries -= 1;
+        }
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    fn retry_wait(&self) -> Option<RetryWait> {
+        self.retry_wait
+    }
+
+    fn set_retry_wait(&mut self, wait: Option<RetryWait>) {
+        self.retry_wait = wait;
+    }
+
+    fn start(&mut self) {
+        self.suspended = false;
+        self.left_retries = self.max_retries + 1;
+        self.startup_time = Some(Instant::now());
+        self.catch_up_from = Some(Local::now());
+
+        // set the tasks_failed flag upon start: no task has been run now
+        // and this is equivalent to a failure; the flag was set to `false`
+        // upon creation in order to have a zero-initialization
+        self.tasks_failed = true;
+    }
+
+    fn suspend(&mut self) -> bool {
+        if self.suspended {
+            false
+        } else {
+            self.suspended = true;
+            true
+        }
+    }
+
+    fn resume(&mut self) -> bool {
+        if self.suspended {
+            // occurrences missed while suspended are not recovered
+            self.suspended = false;
+            self.catch_up_from = Some(Local::now());
+            true
+        } else {
+            false
+        }
+    }
+
+    fn task_names(&self) -> Vec<String> {
+        self.task_names.clone()
+    }
+
+    fn any_tasks_failed(&self) -> bool {
+        self.tasks_failed
+    }
+
+    fn set_tasks_failed(&mut self, failed: bool) {
+        self.tasks_failed = failed;
+    }
+
+    fn _add_task(&mut self, name: &str) -> bool {
+        let name = String::from(name);
+        if self.task_names.contains(&name) {
+            false
+        } else {
+            self.task_names.push(name);
+            true
+        }
+    }
+
+    fn _remove_task(&mut self, name: &str) -> bool {
+        let name = String::from(name);
+        if self.task_names.contains(&name) {
+            self.task_names
+                .remove(self.task_names.iter().position(|x| x == &name).unwrap());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Mandatory check function
+    ///
+    /// This function actually performs the test: every second that elapsed
+    /// within the last tick is checked against all cron expressions, and the
+    /// outcome is successful if at least one of them matches.
+    fn _check_condition(&mut self) -> Result<Option<bool>> {
+        assert!(
+            self.tick_duration > 0,
+            "tick seconds must be greater than zero",
+        );
+
+        let dt = Local::now();
+        self.log(
+            LogType::Debug,
+            LOG_WHEN_START,
+            LOG_STATUS_MSG,
+            &format!(
+                "checking cron expression based condition (at: {})",
+                dt.format("%Y-%m-%dT%H:%M:%S [%a]"),
+            ),
+        );
+
+        // the checked window spans the last tick, but it never overlaps with
+        // the previous one so that ticks that are slightly delayed do not
+        // cause the same instant to match twice
+        let end = dt.with_nanosecond(0).unwrap_or(dt);
+        let mut start = end - TimeDelta::seconds(self.tick_duration);
+        if let Some(last) = self.last_window_end
+            && last > start
+        {
+            start = last;
+        }
+        self.last_window_end = Some(end);
+
+        // look for occurrences missed before the current window, which is
+        // checked below
+        if self.catch_up != CatchUp::None {
+            if let Some(from) = self.catch_up_from
+                && from < start
+            {
+                let missed = self.missed_occurrences(from, start);
+                if missed > 0 {
+                    self.log(
+                        LogType::Info,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_MSG,
+                        &format!(
+                            "found {missed} missed occurrence(s) since {}",
+                            from.format("%Y-%m-%dT%H:%M:%S"),
+                        ),
+                    );
+                    self.catch_up_pending = self.catch_up.runs(self.catch_up_pending + missed);
+                }
+            }
+            self.catch_up_from = Some(end);
+        }
+
+        let mut outcome = false;
+        for cspec in self.cron_specifications.iter() {
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_PROC,
+                LOG_STATUS_MSG,
+                &format!(
+                    "checking cron expression `{}` against current time",
+                    cspec.as_str(),
+                ),
+            );
+            let mut t = end;
+            while t > start {
+                if cspec.matches(&t) {
+                    outcome = true;
+                    break;
+                }
+                t -= TimeDelta::seconds(1);
+            }
+            if outcome {
+                break;
+            }
+        }
+
+        // missed occurrences are only run when the current window does not
+        // contain an occurrence itself, so that they are never merged with it
+        if !outcome && self.catch_up_pending > 0 {
+            self.catch_up_pending -= 1;
+            self.log(
+                LogType::Info,
+                LOG_WHEN_PROC,
+                LOG_STATUS_MSG,
+                &format!(
+                    "catching up with a missed occurrence ({} more pending)",
+                    self.catch_up_pending,
+                ),
+            );
+            outcome = true;
+        }
+
+        Ok(Some(outcome))
+    }
+}
+
+// end.
@@ -21,6 +21,7 @@ use zbus::zvariant;
 use std::str::FromStr;
 
 use super::base::Condition;
+use super::retry::{RetryPolicy, RetryWait, cfg_retry_policy};
 use crate::common::dbusitem::*;
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::Result;
@@ -44,6 +45,7 @@ pub struct DbusMethodCondition {
     task_names: Vec<String>,
     recurring: bool,
     max_retries: i64,
+    retry_policy: RetryPolicy,
     exec_sequence: bool,
     break_on_failure: bool,
     break_on_success: bool,
@@ -56,6 +58,7 @@ pub struct DbusMethodCondition {
     startup_time: Option<Instant>,
     task_registry: Option<&'static TaskRegistry>,
     left_retries: i64,
+    retry_wait: Option<RetryWait>,
     tasks_failed: bool,
 
     // specific members
@@ -87,6 +90,9 @@ impl Hash for DbusMethodCondition {
         self.cond_name.hash(state);
         self.recurring.hash(state);
         self.max_retries.hash(state);
+        if self.retry_policy != RetryPolicy::default() {
+            self.retry_policy.hash(state);
+        }
         self.exec_sequence.hash(state);
         self.break_on_failure.hash(state);
         self.break_on_success.hash(state);
@@ -166,6 +172,7 @@ impl DbusMethodCondition {
             task_names: Vec::new(),
             recurring: false,
             max_retries: 0,
+            retry_policy: RetryPolicy::default(),
             exec_sequence: true,
             break_on_failure: false,
             break_on_success: false,
@@ -178,6 +185,7 @@ impl DbusMethodCondition {
             has_succeeded: false,
             task_registry: None,
             left_retries: 0,
+            retry_wait: None,
             tasks_failed: false,
 
             // specific members initialization
@@ -329,6 +337,10 @@ impl DbusMethodCondition {
             "tasks",
             "recurring",
             "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
             "execute_sequence",
             "break_on_failure",
             "break_on_success",
@@ -424,6 +436,7 @@ impl DbusMethodCondition {
         if let Some(v) = cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)? {
             new_condition.max_retries = v;
         }
+        new_condition.retry_policy = cfg_retry_policy(cfgmap)?;
         if let Some(v) = cfg_bool(cfgmap, "execute_sequence")? {
             new_condition.exec_sequence = v;
         }
@@ -703,6 +716,10 @@ impl DbusMethodCondition {
             "tasks",
             "recurring",
             "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
             "execute_sequence",
             "break_on_failure",
             "break_on_success",
@@ -776,6 +793,7 @@ impl DbusMethodCondition {
 
         cfg_bool(cfgmap, "recurring")?;
         cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)?;
+        cfg_retry_policy(cfgmap)?;
         cfg_bool(cfgmap, "execute_sequence")?;
         cfg_bool(cfgmap, "break_on_failure")?;
         cfg_bool(cfgmap, "break_on_success")?;
@@ -1048,9 +1066,23 @@ impl Condition for DbusMethodCondition {
         self.last_succeeded = None;
         self.has_succeeded = false;
         self.left_retries = self.max_retries + 1;
+        self.retry_wait = None;
         self.tasks_failed = true;
     }
 
+    fn restore_state(
+        &mut self,
+        has_succeeded: bool,
+        last_succeeded: Option<Instant>,
+        left_retries: Option<u64>,
+    ) {
+        self.has_succeeded = has_succeeded;
+        self.last_succeeded = last_succeeded;
+        if let Some(left_retries) = left_retries {
+            self.left_retries = left_retries as i64;
+        }
+    }
+
     fn left_retries(&self) -> Option<u64> {
         if self.max_retries < 0 {
             None
@@ -1065,6 +1097,18 @@ impl Condition for DbusMethodCondition {
         }
     }
 
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    fn retry_wait(&self) -> Option<RetryWait> {
+        self.retry_wait
+    }
+
+    fn set_retry_wait(&mut self, wait: Option<RetryWait>) {
+        self.retry_wait = wait;
+    }
+
     fn start(&mut self) {
         self.suspended = false;
         self.left_retries = self.max_retries + 1;
@@ -0,0 +1,974 @@
+//! Define a file and directory state based condition
+//!
+//! This type of condition inspects the state of a path at each check, and
+//! is verified when all the configured criteria are met. The criteria are:
+//!
+//! * existence or absence of the path
+//! * minimum and maximum size of a file
+//! * age of the path, that is, how long ago it has been last modified
+//! * number of entries in a directory whose names match a glob pattern
+//! * presence of text matching a regular expression in a file.
+//!
+//! Unlike filesystem change based events, which react to notifications,
+//! this condition actively polls the state of the path: this also works on
+//! filesystems that do not support change notifications, such as many of
+//! the network filesystems.
+
+use std::fs::{self, File};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use cfgmap::CfgMap;
+use glob::Pattern;
+use regex::Regex;
+
+use super::base::Condition;
+use super::retry::{RetryPolicy, RetryWait, cfg_retry_policy};
+use crate::task::registry::TaskRegistry;
+use crate::utility::logging::{LogType, log};
+use crate::utility::result::Result;
+use crate::{cfg_mandatory, constants::*};
+
+use crate::cfghelp::*;
+
+/// File and Directory State Based Condition
+///
+/// This condition is verified when the state of the provided path meets all
+/// the criteria given at construction time.
+pub struct FileCondition {
+    // commom members
+    // parameters
+    cond_id: i64,
+    cond_name: String,
+    task_names: Vec<String>,
+    recurring: bool,
+    max_retries: i64,
+    retry_policy: RetryPolicy,
+    exec_sequence: bool,
+    break_on_failure: bool,
+    break_on_success: bool,
+    suspended: bool,
+
+    // internal values
+    has_succeeded: bool,
+    last_tested: Option<Instant>,
+    last_succeeded: Option<Instant>,
+    startup_time: Option<Instant>,
+    task_registry: Option<&'static TaskRegistry>,
+    left_retries: i64,
+    retry_wait: Option<RetryWait>,
+    tasks_failed: bool,
+
+    // specific members
+    // parameters
+    path: PathBuf,
+    exists: bool,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    older_than: Option<Duration>,
+    newer_than: Option<Duration>,
+    file_pattern: Option<String>,
+    min_file_count: Option<u64>,
+    max_file_count: Option<u64>,
+    content_regex: Option<String>,
+    recur_after_failed_check: bool,
+    check_after: Option<Duration>,
+
+    // internal values
+    check_last: Instant,
+
+    // this is different from has_succeeded: the latter is set when the
+    // condition has actually been successful, which in this case may not
+    // be true, as a persistent success may not let the condition succeed
+    last_check_failed: bool,
+}
+
+// implement the hash protocol
+impl Hash for FileCondition {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // common part
+        self.cond_name.hash(state);
+        self.recurring.hash(state);
+        self.max_retries.hash(state);
+        if self.retry_policy != RetryPolicy::default() {
+            self.retry_policy.hash(state);
+        }
+        self.exec_sequence.hash(state);
+        self.break_on_failure.hash(state);
+        self.break_on_success.hash(state);
+        // suspended is more a status: let's not consider it yet
+        // self.suspended.hash(state);
+        // task order is significant: hash on vec is not sorted
+        self.task_names.hash(state);
+
+        // specific part
+        self.path.hash(state);
+        self.exists.hash(state);
+        self.min_size.hash(state);
+        self.max_size.hash(state);
+        self.older_than.hash(state);
+        self.newer_than.hash(state);
+        self.file_pattern.hash(state);
+        self.min_file_count.hash(state);
+        self.max_file_count.hash(state);
+        self.content_regex.hash(state);
+        self.recur_after_failed_check.hash(state);
+        self.check_after.hash(state);
+    }
+}
+
+// the criteria that can only be verified on an existing path
+const FILE_STATE_KEYS: [&str; 8] = [
+    "min_size_bytes",
+    "max_size_bytes",
+    "older_than_seconds",
+    "newer_than_seconds",
+    "file_pattern",
+    "min_file_count",
+    "max_file_count",
+    "content_regex",
+];
+
+#[allow(dead_code)]
+impl FileCondition {
+    /// Create a new file and directory state based condition
+    ///
+    /// When no other criteria are given, the condition is verified as soon
+    /// as the provided path exists.
+    pub fn new(name: &str, path: &Path) -> Self {
+        log(
+            LogType::Debug,
+            LOG_EMITTER_CONDITION_FILE,
+            LOG_ACTION_NEW,
+            Some((name, 0)),
+            LOG_WHEN_INIT,
+            LOG_STATUS_MSG,
+            &format!("CONDITION {name}: creating a new file state based condition"),
+        );
+        let t = Instant::now();
+        FileCondition {
+            // common members initialization
+            // reset ID
+            cond_id: 0,
+
+            // parameters
+            cond_name: String::from(name),
+            task_names: Vec::new(),
+            recurring: false,
+            max_retries: 0,
+            retry_policy: RetryPolicy::default(),
+            exec_sequence: true,
+            break_on_failure: false,
+            break_on_success: false,
+            suspended: true,
+
+            // internal values
+            startup_time: None,
+            last_tested: None,
+            last_succeeded: None,
+            has_succeeded: false,
+            task_registry: None,
+            left_retries: 0,
+            retry_wait: None,
+            tasks_failed: false,
+
+            // specific members initialization
+            // parameters
+            path: PathBuf::from(path),
+            exists: true,
+            min_size: None,
+            max_size: None,
+            older_than: None,
+            newer_than: None,
+            file_pattern: None,
+            min_file_count: None,
+            max_file_count: None,
+            content_regex: None,
+            recur_after_failed_check: false,
+            check_after: None,
+
+            // internal values
+            check_last: t,
+            last_check_failed: true,
+        }
+    }
+
+    // constructor modifiers
+    /// Set the command execution to sequence or parallel
+    pub fn execs_sequentially(mut self, yes: bool) -> Self {
+        self.exec_sequence = yes;
+        self
+    }
+
+    /// If true, *sequential* task execution will break on first success
+    pub fn breaks_on_success(mut self, yes: bool) -> Self {
+        self.break_on_success = yes;
+        self
+    }
+
+    /// If true, *sequential* task execution will break on first failure
+    pub fn breaks_on_failure(mut self, yes: bool) -> Self {
+        self.break_on_failure = yes;
+        self
+    }
+
+    /// If true, create a recurring condition
+    pub fn repeats(mut self, yes: bool) -> Self {
+        self.recurring = yes;
+        self
+    }
+
+    /// Retry `num` times on task failure if not recurring
+    pub fn retries(mut self, num: i64) -> Self {
+        assert!(num >= -1, "max number of retries must be positive or -1");
+        self.max_retries = num;
+        self
+    }
+
+    /// State that the first check and possible following tests are to be
+    /// performed after a certain amount of time
+    ///
+    /// Inspecting large directories or files, or paths on slow network
+    /// filesystems, might be too expensive to be performed at every tick.
+    pub fn checks_after(mut self, delta: Duration) -> Self {
+        self.check_after = Some(delta);
+        self
+    }
+
+    /// Constructor modifier to expect the path to be absent
+    ///
+    /// When set to `false` the condition is verified if the path does not
+    /// exist, and no other criteria can be checked.
+    pub fn expects_existence(mut self, yes: bool) -> Self {
+        self.exists = yes;
+        self
+    }
+
+    /// Constructor modifier to set the minimum and maximum size of a file
+    pub fn has_size_between(mut self, min: Option<u64>, max: Option<u64>) -> Self {
+        self.min_size = min;
+        self.max_size = max;
+        self
+    }
+
+    /// Constructor modifier to expect the path not to be modified since at
+    /// least the specified amount of time
+    pub fn checks_older_than(mut self, delta: Duration) -> Self {
+        self.older_than = Some(delta);
+        self
+    }
+
+    /// Constructor modifier to expect the path to be modified within the
+    /// specified amount of time
+    pub fn checks_newer_than(mut self, delta: Duration) -> Self {
+        self.newer_than = Some(delta);
+        self
+    }
+
+    /// Constructor modifier to count the directory entries matching a glob
+    ///
+    /// The number of entries whose names match the pattern must be within
+    /// the provided limits, and the path must be a directory.
+    pub fn counts_files(mut self, pattern: &str, min: Option<u64>, max: Option<u64>) -> Self {
+        self.file_pattern = Some(String::from(pattern));
+        self.min_file_count = min;
+        self.max_file_count = max;
+        self
+    }
+
+    /// Constructor modifier to expect the contents of a file to match a
+    /// regular expression
+    pub fn matches_content(mut self, regex: &str) -> Self {
+        self.content_regex = Some(String::from(regex));
+        self
+    }
+
+    /// Constructor modifier to recur only after failure
+    ///
+    /// Specifies that the condition is verified on check success only if
+    /// there has been at least one failure after the last successful test.
+    pub fn recurs_after_check_failure(mut self, yes: bool) -> Self {
+        self.recur_after_failed_check = yes;
+        self
+    }
+
+    // check the current state of the path against the criteria, and return
+    // the reason for the first failed check (if any), for logging purposes
+    fn check_state(&self) -> Option<String> {
+        let path = self.path.to_string_lossy();
+        let metadata = match fs::metadata(&self.path) {
+            Ok(m) => m,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return if self.exists {
+                    Some(format!("path `{path}` NOT found"))
+                } else {
+                    None
+                };
+            }
+            // the path may exist, but its state cannot be determined
+            Err(e) => {
+                return Some(format!("could not access path `{path}` (reason: {e})"));
+            }
+        };
+        if !self.exists {
+            return Some(format!("path `{path}` found"));
+        }
+
+        // size checks are only valid for files
+        if self.min_size.is_some() || self.max_size.is_some() {
+            if !metadata.is_file() {
+                return Some(format!(
+                    "path `{path}` is NOT a file: size cannot be checked"
+                ));
+            }
+            let size = metadata.len();
+            if let Some(min) = self.min_size
+                && size < min
+            {
+                return Some(format!("file size {size} is below the minimum of {min}"));
+            }
+            if let Some(max) = self.max_size
+                && size > max
+            {
+                return Some(format!("file size {size} is above the maximum of {max}"));
+            }
+        }
+
+        // modifications in the future are considered as just happened
+        if self.older_than.is_some() || self.newer_than.is_some() {
+            let age = match metadata.modified() {
+                Ok(t) => SystemTime::now()
+                    .duration_since(t)
+                    .unwrap_or(Duration::ZERO),
+                Err(e) => {
+                    return Some(format!(
+                        "modification time of `{path}` NOT available (reason: {e})"
+                    ));
+                }
+            };
+            if let Some(older_than) = self.older_than
+                && age < older_than
+            {
+                return Some(format!(
+                    "path modified {}s ago, less than {}s",
+                    age.as_secs(),
+                    older_than.as_secs(),
+                ));
+            }
+            if let Some(newer_than) = self.newer_than
+                && age > newer_than
+            {
+                return Some(format!(
+                    "path modified {}s ago, more than {}s",
+                    age.as_secs(),
+                    newer_than.as_secs(),
+                ));
+            }
+        }
+
+        // entries are counted only at the first level of the directory
+        if let Some(p) = &self.file_pattern {
+            if !metadata.is_dir() {
+                return Some(format!(
+                    "path `{path}` is NOT a directory: files cannot be counted"
+                ));
+            }
+            let pattern = match Pattern::new(p) {
+                Ok(pattern) => pattern,
+                Err(e) => return Some(format!("{ERR_INVALID_PATTERN}: `{p}` ({e})")),
+            };
+            let entries = match fs::read_dir(&self.path) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    return Some(format!("could not read directory `{path}` (reason: {e})"));
+                }
+            };
+            let count = entries
+                .filter_map(|e| e.ok())
+                .filter(|e| pattern.matches(&e.file_name().to_string_lossy()))
+                .count() as u64;
+            if let Some(min) = self.min_file_count
+                && count < min
+            {
+                return Some(format!(
+                    "{count} entries matching `{p}`, below the minimum of {min}"
+                ));
+            }
+            if let Some(max) = self.max_file_count
+                && count > max
+            {
+                return Some(format!(
+                    "{count} entries matching `{p}`, above the maximum of {max}"
+                ));
+            }
+        }
+
+        // the file contents are not required to be valid text, and only
+        // their first part is checked in order to limit memory usage
+        if let Some(r) = &self.content_regex {
+            if !metadata.is_file() {
+                return Some(format!(
+                    "path `{path}` is NOT a file: contents cannot be checked"
+                ));
+            }
+            let re = match Regex::new(r) {
+                Ok(re) => re,
+                Err(e) => return Some(format!("provided INVALID content regex {r:?} ({e})")),
+            };
+            let mut contents = Vec::new();
+            if let Err(e) = File::open(&self.path).and_then(|f| {
+                f.take(FILE_CONTENT_CHECK_MAX_BYTES)
+                    .read_to_end(&mut contents)
+            }) {
+                return Some(format!("could not read file `{path}` (reason: {e})"));
+            }
+            if !re.is_match(&String::from_utf8_lossy(&contents)) {
+                return Some(format!("file contents NOT matching {r:?}"));
+            }
+        }
+
+        None
+    }
+
+    /// Load a `FileCondition` from a [`CfgMap`](https://docs.rs/cfgmap/latest/)
+    ///
+    /// The `FileCondition` is initialized according to the values provided
+    /// in the `CfgMap` argument. If the `CfgMap` format does not comply with
+    /// the requirements of a `FileCondition` an error is raised.
+    pub fn load_cfgmap(
+        cfgmap: &CfgMap,
+        task_registry: &'static TaskRegistry,
+    ) -> Result<FileCondition> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "tasks",
+            "recurring",
+            "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
+            "execute_sequence",
+            "break_on_failure",
+            "break_on_success",
+            "suspended",
+            "path",
+            "exists",
+            "min_size_bytes",
+            "max_size_bytes",
+            "older_than_seconds",
+            "newer_than_seconds",
+            "file_pattern",
+            "min_file_count",
+            "max_file_count",
+            "content_regex",
+            "recur_after_failed_check",
+            "check_after",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // type and name are both mandatory but type is only checked
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "file"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_COND_NAME))?.unwrap();
+
+        // specific mandatory parameter retrieval
+        let path = PathBuf::from(cfg_mandatory!(cfg_string(cfgmap, "path"))?.unwrap());
+
+        // initialize the structure
+        let mut new_condition = FileCondition::new(&name, &path);
+        new_condition.task_registry = Some(task_registry);
+
+        // by default make condition active if loaded from configuration: if
+        // the configuration changes this state the condition will not start
+        new_condition.suspended = false;
+
+        // common optional parameter initialization
+
+        // tags are always simply checked this way as no value is needed
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // retrieve task list and try to directly add each task
+        let cur_key = "tasks";
+        if let Some(v) = cfg_vec_string_check_regex(cfgmap, cur_key, &RE_TASK_NAME)? {
+            for s in v {
+                if !new_condition.add_task(&s)? {
+                    return Err(cfg_err_invalid_config(cur_key, &s, ERR_INVALID_TASK));
+                }
+            }
+        }
+
+        if let Some(v) = cfg_bool(cfgmap, "recurring")? {
+            new_condition.recurring = v;
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)? {
+            new_condition.max_retries = v;
+        }
+        new_condition.retry_policy = cfg_retry_policy(cfgmap)?;
+        if let Some(v) = cfg_bool(cfgmap, "execute_sequence")? {
+            new_condition.exec_sequence = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "break_on_failure")? {
+            new_condition.break_on_failure = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "break_on_success")? {
+            new_condition.break_on_success = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "suspended")? {
+            new_condition.suspended = v;
+        }
+
+        // specific optional parameter initialization
+
+        // an absent path cannot be checked for anything else
+        if let Some(v) = cfg_bool(cfgmap, "exists")? {
+            new_condition.exists = v;
+        }
+        if !new_condition.exists
+            && let Some(cur_key) = FILE_STATE_KEYS.iter().find(|k| cfgmap.contains_key(k))
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "min_size_bytes", 0)? {
+            new_condition.min_size = Some(v as u64);
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "max_size_bytes", 0)? {
+            new_condition.max_size = Some(v as u64);
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "older_than_seconds", 1)? {
+            new_condition.older_than = Some(Duration::from_secs(v as u64));
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "newer_than_seconds", 1)? {
+            new_condition.newer_than = Some(Duration::from_secs(v as u64));
+        }
+
+        // when only a count is given every entry is considered, and when
+        // only a pattern is given at least one matching entry is expected
+        let cur_key = "file_pattern";
+        if let Some(v) = cfg_string(cfgmap, cur_key)? {
+            if Pattern::new(&v).is_err() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_PATTERN));
+            }
+            new_condition.file_pattern = Some(v);
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "min_file_count", 0)? {
+            new_condition.min_file_count = Some(v as u64);
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "max_file_count", 0)? {
+            new_condition.max_file_count = Some(v as u64);
+        }
+        if new_condition.min_file_count.is_some() || new_condition.max_file_count.is_some() {
+            if new_condition.file_pattern.is_none() {
+                new_condition.file_pattern = Some(String::from("*"));
+            }
+        } else if new_condition.file_pattern.is_some() {
+            new_condition.min_file_count = Some(1);
+        }
+
+        let cur_key = "content_regex";
+        if let Some(v) = cfg_string(cfgmap, cur_key)? {
+            if Regex::new(&v).is_err() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_VALUE));
+            }
+            new_condition.content_regex = Some(v);
+        }
+
+        if let Some(v) = cfg_bool(cfgmap, "recur_after_failed_check")? {
+            new_condition.recur_after_failed_check = v;
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "check_after", 1)? {
+            new_condition.check_after = Some(Duration::from_secs(v as u64));
+        }
+
+        // start the condition if the configuration did not suspend it
+        if !new_condition.suspended {
+            new_condition.start();
+        }
+
+        Ok(new_condition)
+    }
+
+    /// Check a configuration map and return item name if Ok
+    ///
+    /// The check is performed exactly in the same way and in the same order
+    /// as in `load_cfgmap`, the only difference is that no actual item is
+    /// created and that a name is returned, which is the name of the item that
+    /// _would_ be created via the equivalent call to `load_cfgmap`
+    pub fn check_cfgmap(cfgmap: &CfgMap, available_tasks: &Vec<&str>) -> Result<String> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "tasks",
+            "recurring",
+            "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
+            "execute_sequence",
+            "break_on_failure",
+            "break_on_success",
+            "suspended",
+            "path",
+            "exists",
+            "min_size_bytes",
+            "max_size_bytes",
+            "older_than_seconds",
+            "newer_than_seconds",
+            "file_pattern",
+            "min_file_count",
+            "max_file_count",
+            "content_regex",
+            "recur_after_failed_check",
+            "check_after",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // common mandatory parameter check
+
+        // type and name are both mandatory: type is checked and name is kept
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "file"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_COND_NAME))?.unwrap();
+
+        // specific mandatory parameter check
+        cfg_mandatory!(cfg_string(cfgmap, "path"))?;
+
+        // also for optional parameters just check and throw away the result
+
+        // tags are always simply checked this way
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // check configuration task list against the provided ones
+        let cur_key = "tasks";
+        if let Some(v) = cfg_vec_string_check_regex(cfgmap, cur_key, &RE_TASK_NAME)? {
+            for s in v {
+                if !available_tasks.contains(&s.as_str()) {
+                    return Err(cfg_err_invalid_config(cur_key, &s, ERR_INVALID_TASK));
+                }
+            }
+        }
+
+        cfg_bool(cfgmap, "recurring")?;
+        cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)?;
+        cfg_retry_policy(cfgmap)?;
+        cfg_bool(cfgmap, "execute_sequence")?;
+        cfg_bool(cfgmap, "break_on_failure")?;
+        cfg_bool(cfgmap, "break_on_success")?;
+        cfg_bool(cfgmap, "suspended")?;
+
+        if !cfg_bool(cfgmap, "exists")?.unwrap_or(true)
+            && let Some(cur_key) = FILE_STATE_KEYS.iter().find(|k| cfgmap.contains_key(k))
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        cfg_int_check_above_eq(cfgmap, "min_size_bytes", 0)?;
+        cfg_int_check_above_eq(cfgmap, "max_size_bytes", 0)?;
+        cfg_int_check_above_eq(cfgmap, "older_than_seconds", 1)?;
+        cfg_int_check_above_eq(cfgmap, "newer_than_seconds", 1)?;
+
+        let cur_key = "file_pattern";
+        if let Some(v) = cfg_string(cfgmap, cur_key)?
+            && Pattern::new(&v).is_err()
+        {
+            return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_PATTERN));
+        }
+        cfg_int_check_above_eq(cfgmap, "min_file_count", 0)?;
+        cfg_int_check_above_eq(cfgmap, "max_file_count", 0)?;
+
+        let cur_key = "content_regex";
+        if let Some(v) = cfg_string(cfgmap, cur_key)?
+            && Regex::new(&v).is_err()
+        {
+            return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_VALUE));
+        }
+
+        cfg_bool(cfgmap, "recur_after_failed_check")?;
+        cfg_int_check_above_eq(cfgmap, "check_after", 1)?;
+
+        Ok(name)
+    }
+}
+
+impl Condition for FileCondition {
+    fn set_id(&mut self, id: i64) {
+        self.cond_id = id;
+    }
+    fn get_name(&self) -> String {
+        self.cond_name.clone()
+    }
+    fn get_id(&self) -> i64 {
+        self.cond_id
+    }
+    fn get_type(&self) -> &str {
+        "file"
+    }
+
+    /// Return a hash of this item for comparison
+    fn _hash(&self) -> u64 {
+        let mut s = DefaultHasher::new();
+        self.hash(&mut s);
+        s.finish()
+    }
+
+    fn set_task_registry(&mut self, reg: &'static TaskRegistry) {
+        self.task_registry = Some(reg);
+    }
+
+    fn task_registry(&self) -> Option<&'static TaskRegistry> {
+        self.task_registry
+    }
+
+    fn suspended(&self) -> bool {
+        self.suspended
+    }
+    fn recurring(&self) -> bool {
+        self.recurring
+    }
+    fn has_succeeded(&self) -> bool {
+        self.has_succeeded
+    }
+
+    fn exec_sequence(&self) -> bool {
+        self.exec_sequence
+    }
+    fn break_on_success(&self) -> bool {
+        self.break_on_success
+    }
+    fn break_on_failure(&self) -> bool {
+        self.break_on_failure
+    }
+
+    fn last_checked(&self) -> Option<Instant> {
+        self.last_tested
+    }
+    fn last_succeeded(&self) -> Option<Instant> {
+        self.last_succeeded
+    }
+    fn startup_time(&self) -> Option<Instant> {
+        self.startup_time
+    }
+
+    fn set_checked(&mut self) {
+        self.last_tested = Some(Instant::now());
+    }
+
+    fn set_succeeded(&mut self) {
+        self.last_succeeded = self.last_tested;
+        self.has_succeeded = true;
+    }
+
+    fn reset_succeeded(&mut self) {
+        self.last_succeeded = None;
+        self.has_succeeded = false;
+    }
+
+    fn reset(&mut self) {
+        self.last_tested = None;
+        self.last_succeeded = None;
+        self.has_succeeded = false;
+        self.left_retries = self.max_retries + 1;
+        self.retry_wait = None;
+        self.tasks_failed = true;
+    }
+
+    fn restore_state(
+        &mut self,
+        has_succeeded: bool,
+        last_succeeded: Option<Instant>,
+        left_retries: Option<u64>,
+    ) {
+        self.has_succeeded = has_succeeded;
+        self.last_succeeded = last_succeeded;
+        if let Some(left_retries) = left_retries {
+            self.left_retries = left_retries as i64;
+        }
+    }
+
+    fn left_retries(&self) -> Option<u64> {
+        if self.max_retries < 0 {
+            None
+        } else {
+            Some(self.left_retries as u64)
+        }
+    }
+
+    fn set_retried(&mut self) {
+        if self.left_retries > 0 {
+            self.left_retries -= 1;
+        }
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    fn retry_wait(&self) -> Option<RetryWait> {
+        self.retry_wait
+    }
+
+    fn set_retry_wait(&mut self, wait: Option<RetryWait>) {
+        self.retry_wait = wait;
+    }
+
+    fn start(&mut self) {
+        self.suspended = false;
+        self.left_retries = self.max_retries + 1;
+        self.startup_time = Some(Instant::now());
+
+        // set the tasks_failed flag upon start: no task has been run now
+        // and this is equivalent to a failure; the flag was set to `false`
+        // upon creation in order to have a zero-initialization
+        self.tasks_failed = true;
+    }
+
+    fn suspend(&mut self) -> bool {
+        if self.suspended {
+            false
+        } else {
+            self.suspended = true;
+            true
+        }
+    }
+
+    fn resume(&mut self) -> bool {
+        if self.suspended {
+            self.suspended = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn task_names(&self) -> Vec<String> {
+        self.task_names.clone()
+    }
+
+    fn any_tasks_failed(&self) -> bool {
+        self.tasks_failed
+    }
+
+    fn set_tasks_failed(&mut self, failed: bool) {
+        self.tasks_failed = failed;
+    }
+
+    fn _add_task(&mut self, name: &str) -> bool {
+        let name = String::from(name);
+        if self.task_names.contains(&name) {
+            false
+        } else {
+            self.task_names.push(name);
+            true
+        }
+    }
+
+    fn _remove_task(&mut self, name: &str) -> bool {
+        let name = String::from(name);
+        if self.task_names.contains(&name) {
+            self.task_names
+                .remove(self.task_names.iter().position(|x| x == &name).unwrap());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Mandatory check function
+    ///
+    /// This function actually performs the test: the state of the path is
+    /// inspected, and the condition is verified if all criteria are met.
+    fn _check_condition(&mut self) -> Result<Option<bool>> {
+        self.log(
+            LogType::Debug,
+            LOG_WHEN_START,
+            LOG_STATUS_MSG,
+            "checking file state based condition",
+        );
+        // if the minimum interval between checks has been set, obey it
+        // last_tested has already been set by trait to Instant::now()
+        let t = self.last_tested.unwrap();
+        if let Some(e) = self.check_after
+            && e > t - self.check_last
+        {
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_START,
+                LOG_STATUS_MSG,
+                "check explicitly delayed by configuration",
+            );
+            return Ok(Some(false));
+        }
+        self.check_last = t;
+
+        // return true on success (not persistent unless allowed), false otherwise
+        if let Some(reason) = self.check_state() {
+            self.last_check_failed = true;
+            self.log(LogType::Debug, LOG_WHEN_PROC, LOG_STATUS_MSG, &reason);
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_END,
+                LOG_STATUS_FAIL,
+                "condition checked unsuccessfully",
+            );
+            Ok(Some(false))
+        } else {
+            let succeeds = self.last_check_failed || !self.recur_after_failed_check;
+            self.last_check_failed = false;
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_END,
+                LOG_STATUS_OK,
+                &format!(
+                    "condition checked successfully: path `{}` meets all criteria",
+                    self.path.to_string_lossy(),
+                ),
+            );
+            if succeeds {
+                Ok(Some(true))
+            } else {
+                self.log(
+                    LogType::Debug,
+                    LOG_WHEN_END,
+                    LOG_STATUS_MSG,
+                    "persistent success status: waiting for failure to recur",
+                );
+                Ok(Some(false))
+            }
+        }
+    }
+}
+
+// end.
@@ -0,0 +1,998 @@
+//! Define an HTTP request based condition
+//!
+//! This type of `Condition` performs an HTTP(S) request at each check, and
+//! examines the response to give either a positive or negative result. This
+//! allows to monitor the availability of a service without resorting to an
+//! external tool or to a Lua script. The same checks that are available to
+//! HTTP based tasks can be performed on the response, that is
+//!
+//! * matching the status code with a given value (by default any `2xx` code
+//!   is considered successful)
+//! * testing the body for the presence of a particular string or against a
+//!   regular expression
+//!
+//! and furthermore the condition can verify
+//!
+//! * that the response has been received within a maximum amount of time
+//! * that some headers are present in the response, with matching values
+//! * that a JSON response contains a value at a given JSON pointer, possibly
+//!   equal to an expected one.
+//!
+//! As for command based conditions, the check can be delayed in order to
+//! avoid performing a request at every tick, and the condition can be set to
+//! only succeed again after at least one failed check.
+
+#![cfg(feature = "http")]
+
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use itertools::Itertools;
+
+use cfgmap::CfgMap;
+
+use super::base::Condition;
+use super::retry::{RetryPolicy, RetryWait, cfg_retry_policy};
+use crate::common::httpitem::*;
+use crate::utility::logging::{LogType, log};
+use crate::utility::result::Result;
+use crate::task::registry::TaskRegistry;
+use crate::{cfg_mandatory, constants::*};
+
+use crate::cfghelp::*;
+
+/// HTTP Request Based Condition
+///
+/// This condition is verified when the response to the underlying request
+/// meets the criteria given at construction time.
+pub struct HttpCondition {
+    // commom members
+    // parameters
+    cond_id: i64,
+    cond_name: String,
+    task_names: Vec<String>,
+    recurring: bool,
+    max_retries: i64,
+    retry_policy: RetryPolicy,
+    exec_sequence: bool,
+    break_on_failure: bool,
+    break_on_success: bool,
+    suspended: bool,
+
+    // internal values
+    has_succeeded: bool,
+    last_tested: Option<Instant>,
+    last_succeeded: Option<Instant>,
+    startup_time: Option<Instant>,
+    task_registry: Option<&'static TaskRegistry>,
+    left_retries: i64,
+    retry_wait: Option<RetryWait>,
+    tasks_failed: bool,
+
+    // specific members
+    // parameters
+    url: String,
+    method: String,
+    headers: HashMap<String, String>,
+    body: Option<String>,
+    body_file: Option<PathBuf>,
+    checks: ResponseChecks,
+    recur_after_failed_check: bool,
+    check_after: Option<Duration>,
+    timeout: Option<Duration>,
+
+    // internal values
+    check_last: Instant,
+    _response_status: u32,
+    _response_body: String,
+    _request_failed: bool,
+    _request_duration: Duration,
+
+    // this is different from has_succeeded: the latter is set when the
+    // condition has actually been successful, which in this case may not
+    // be true, as a persistent success may not let the condition succeed
+    last_check_failed: bool,
+}
+
+// implement the hash protocol
+impl Hash for HttpCondition {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // common part
+        self.cond_name.hash(state);
+        self.recurring.hash(state);
+        self.max_retries.hash(state);
+        if self.retry_policy != RetryPolicy::default() {
+            self.retry_policy.hash(state);
+        }
+        self.exec_sequence.hash(state);
+        self.break_on_failure.hash(state);
+        self.break_on_success.hash(state);
+        // suspended is more a status: let's not consider it yet
+        // self.suspended.hash(state);
+        // task order is significant: hash on vec is not sorted
+        self.task_names.hash(state);
+
+        // specific part
+        self.url.hash(state);
+        self.method.hash(state);
+        self.body.hash(state);
+        self.body_file.hash(state);
+        self.checks.hash(state);
+        self.recur_after_failed_check.hash(state);
+        self.check_after.hash(state);
+        self.timeout.hash(state);
+
+        // the order in which headers are defined is not significant
+        for key in self.headers.keys().sorted() {
+            key.hash(state);
+            self.headers[key].hash(state);
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl HttpCondition {
+    /// Create a new HTTP request based condition
+    ///
+    /// The request uses the `GET` method by default.
+    pub fn new(name: &str, url: &str) -> Self {
+        log(
+            LogType::Debug,
+            LOG_EMITTER_CONDITION_HTTP,
+            LOG_ACTION_NEW,
+            Some((name, 0)),
+            LOG_WHEN_INIT,
+            LOG_STATUS_MSG,
+            &format!("CONDITION {name}: creating a new HTTP request based condition"),
+        );
+        let t = Instant::now();
+        HttpCondition {
+            // common members initialization
+            // reset ID
+            cond_id: 0,
+
+            // parameters
+            cond_name: String::from(name),
+            task_names: Vec::new(),
+            recurring: false,
+            max_retries: 0,
+            retry_policy: RetryPolicy::default(),
+            exec_sequence: true,
+            break_on_failure: false,
+            break_on_success: false,
+            suspended: true,
+
+            // internal values
+            startup_time: None,
+            last_tested: None,
+            last_succeeded: None,
+            has_succeeded: false,
+            task_registry: None,
+            left_retries: 0,
+            retry_wait: None,
+            tasks_failed: false,
+
+            // specific members initialization
+            // parameters
+            url: String::from(url),
+            method: String::from("GET"),
+            headers: HashMap::new(),
+            body: None,
+            body_file: None,
+            checks: ResponseChecks::default(),
+            recur_after_failed_check: false,
+            check_after: None,
+            timeout: None,
+
+            // internal values
+            check_last: t,
+            last_check_failed: true,
+            _response_status: 0,
+            _response_body: String::new(),
+            _request_failed: false,
+            _request_duration: Duration::ZERO,
+        }
+    }
+
+    // build a short description of the request, only for logging purposes
+    fn request_line(&self) -> String {
+        format!("{} {}", self.method, self.url)
+    }
+
+    // constructor modifiers
+    /// Set the command execution to sequence or parallel
+    pub fn execs_sequentially(mut self, yes: bool) -> Self {
+        self.exec_sequence = yes;
+        self
+    }
+
+    /// If true, *sequential* task execution will break on first success
+    pub fn breaks_on_success(mut self, yes: bool) -> Self {
+        self.break_on_success = yes;
+        self
+    }
+
+    /// If true, *sequential* task execution will break on first failure
+    pub fn breaks_on_failure(mut self, yes: bool) -> Self {
+        self.break_on_failure = yes;
+        self
+    }
+
+    /// If true, create a recurring condition
+    pub fn repeats(mut self, yes: bool) -> Self {
+        self.recurring = yes;
+        self
+    }
+
+    /// Retry `num` times on task failure if not recurring
+    pub fn retries(mut self, num: i64) -> Self {
+        assert!(num >= -1, "max number of retries must be positive or -1");
+        self.max_retries = num;
+        self
+    }
+
+    /// State that the first check and possible following tests are to be
+    /// performed after a certain amount of time
+    ///
+    /// Performing a request at every tick may put an unnecessary load both
+    /// on the scheduler and on the remote service.
+    pub fn checks_after(mut self, delta: Duration) -> Self {
+        self.check_after = Some(delta);
+        self
+    }
+
+    /// Set a header to be sent along with the request
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the header name
+    /// * `value` - the value assigned to the named header.
+    pub fn set_header(&mut self, name: &str, value: &str) -> Option<String> {
+        self.headers.insert(String::from(name), String::from(value))
+    }
+
+    /// Remove a header from the ones to be sent along with the request
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the header to be removed.
+    pub fn unset_header(&mut self, name: &str) -> Option<String> {
+        self.headers.remove(name)
+    }
+
+    /// Constructor modifier to use a specific method
+    ///
+    /// The method name is not case sensitive: unsupported methods are
+    /// ignored, leaving the previously set method in place.
+    pub fn with_method(mut self, method: &str) -> Self {
+        if http_method(method).is_some() {
+            self.method = method.to_ascii_uppercase();
+        }
+        self
+    }
+
+    /// Constructor modifier to send a body with the request
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.body = Some(String::from(body));
+        self.body_file = None;
+        self
+    }
+
+    /// Constructor modifier to send the contents of a file as the body
+    ///
+    /// The file is read each time the condition is checked.
+    pub fn with_body_file(mut self, path: &Path) -> Self {
+        self.body_file = Some(PathBuf::from(path));
+        self.body = None;
+        self
+    }
+
+    /// Constructor modifier to match against regular expressions
+    ///
+    /// Specifies that the values to match against the response body and
+    /// headers have to be considered as regular expressions when the argument
+    /// is set to `true`. The default behaviour is to consider them as simple
+    /// strings.
+    pub fn matches_regexp(mut self, yes: bool) -> Self {
+        self.checks.match_regexp = yes;
+        self
+    }
+
+    /// Constructor modifier exactly match against a string
+    ///
+    /// Specifies that the entire response body or header value must match
+    /// against the provided value, when the argument is set to `true`. The
+    /// default behaviour is to _partially_ match.
+    pub fn matches_exact(mut self, yes: bool) -> Self {
+        self.checks.match_exact = yes;
+        self
+    }
+
+    /// Constructor modifier for case-sensitive match
+    ///
+    /// Specifies that the matching against the response body and headers is
+    /// to be performed case-sensitively when set to `true`. The default
+    /// behaviour is to ignore case.
+    pub fn matches_case(mut self, yes: bool) -> Self {
+        self.checks.case_sensitive = yes;
+        self
+    }
+
+    /// Constructor modifier to match against the response body
+    pub fn expects_body(mut self, s: &str) -> Self {
+        self.checks.success_body = Some(s.to_string());
+        self
+    }
+
+    /// Constructor modifier to not match against the response body
+    pub fn rejects_body(mut self, s: &str) -> Self {
+        self.checks.failure_body = Some(s.to_string());
+        self
+    }
+
+    /// Constructor modifier to expect a status code
+    pub fn expects_status(mut self, c: u32) -> Self {
+        self.checks.success_status = Some(c);
+        self
+    }
+
+    /// Constructor modifier to reject a status code
+    pub fn rejects_status(mut self, c: u32) -> Self {
+        self.checks.failure_status = Some(c);
+        self
+    }
+
+    /// Constructor modifier to expect a header in the response
+    ///
+    /// The header name is not case sensitive, while the value is matched
+    /// using the same rules that apply to the response body.
+    pub fn expects_header(mut self, name: &str, value: &str) -> Self {
+        self.checks
+            .success_headers
+            .retain(|(k, _)| !k.eq_ignore_ascii_case(name));
+        self.checks
+            .success_headers
+            .push((String::from(name), String::from(value)));
+        self.checks.success_headers.sort();
+        self
+    }
+
+    /// Constructor modifier to set a maximum response time
+    ///
+    /// Responses received after the specified duration are considered failed.
+    pub fn responds_within(mut self, delta: Duration) -> Self {
+        self.checks.max_response_time = Some(delta);
+        self
+    }
+
+    /// Constructor modifier to check a value in a JSON response
+    ///
+    /// The `pointer` argument is a JSON pointer as specified in RFC 6901: if
+    /// `value` is `None` the pointed value only has to exist, otherwise it
+    /// must be equal to the provided value.
+    pub fn expects_json(mut self, pointer: &str, value: Option<serde_json::Value>) -> Self {
+        self.checks.json_pointer = Some(String::from(pointer));
+        self.checks.json_value = value.map(|v| v.to_string());
+        self
+    }
+
+    /// If set, the request times out after specified duration
+    pub fn times_out_after(mut self, delta: Duration) -> Self {
+        self.timeout = Some(delta);
+        self
+    }
+
+    /// Constructor modifier to recur only after failure
+    ///
+    /// Specifies that the condition is verified on check success only if
+    /// there has been at least one failure after the last successful test.
+    pub fn recurs_after_check_failure(mut self, yes: bool) -> Self {
+        self.recur_after_failed_check = yes;
+        self
+    }
+
+    /// Load an `HttpCondition` from a [`CfgMap`](https://docs.rs/cfgmap/latest/)
+    ///
+    /// The `HttpCondition` is initialized according to the values provided
+    /// in the `CfgMap` argument. If the `CfgMap` format does not comply with
+    /// the requirements of an `HttpCondition` an error is raised.
+    pub fn load_cfgmap(
+        cfgmap: &CfgMap,
+        task_registry: &'static TaskRegistry,
+    ) -> Result<HttpCondition> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "tasks",
+            "recurring",
+            "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
+            "execute_sequence",
+            "break_on_failure",
+            "break_on_success",
+            "suspended",
+            "url",
+            "method",
+            "headers",
+            "body",
+            "body_file",
+            "match_exact",
+            "match_regular_expression",
+            "case_sensitive",
+            "recur_after_failed_check",
+            "check_after",
+            "success_body",
+            "success_status",
+            "success_headers",
+            "failure_body",
+            "failure_status",
+            "max_response_milliseconds",
+            "json_pointer",
+            "json_value",
+            "timeout_seconds",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // type and name are both mandatory but type is only checked
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "http"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_COND_NAME))?.unwrap();
+
+        // specific mandatory parameter retrieval
+        let url = cfg_mandatory!(cfg_string_check_regex(cfgmap, "url", &RE_HTTP_URL))?.unwrap();
+
+        // initialize the structure
+        let mut new_condition = HttpCondition::new(&name, &url);
+        new_condition.task_registry = Some(task_registry);
+
+        // by default make condition active if loaded from configuration: if
+        // the configuration changes this state the condition will not start
+        new_condition.suspended = false;
+
+        // common optional parameter initialization
+
+        // tags are always simply checked this way as no value is needed
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // retrieve task list and try to directly add each task
+        let cur_key = "tasks";
+        if let Some(v) = cfg_vec_string_check_regex(cfgmap, cur_key, &RE_TASK_NAME)? {
+            for s in v {
+                if !new_condition.add_task(&s)? {
+                    return Err(cfg_err_invalid_config(cur_key, &s, ERR_INVALID_TASK));
+                }
+            }
+        }
+
+        if let Some(v) = cfg_bool(cfgmap, "recurring")? {
+            new_condition.recurring = v;
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)? {
+            new_condition.max_retries = v;
+        }
+        new_condition.retry_policy = cfg_retry_policy(cfgmap)?;
+        if let Some(v) = cfg_bool(cfgmap, "execute_sequence")? {
+            new_condition.exec_sequence = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "break_on_failure")? {
+            new_condition.break_on_failure = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "break_on_success")? {
+            new_condition.break_on_success = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "suspended")? {
+            new_condition.suspended = v;
+        }
+
+        // specific optional parameter initialization
+        let cur_key = "method";
+        if let Some(v) = cfg_string(cfgmap, cur_key)? {
+            if http_method(&v).is_none() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_VALUE));
+            }
+            new_condition.method = v.to_ascii_uppercase();
+        }
+        new_condition.headers = cfg_http_headers(cfgmap, "headers")?;
+
+        // the body can be given either inline or as a file, but not both
+        new_condition.body = cfg_string(cfgmap, "body")?;
+        let cur_key = "body_file";
+        if let Some(v) = cfg_string(cfgmap, cur_key)? {
+            if new_condition.body.is_some() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_PARAMETER));
+            }
+            let body_file = PathBuf::from(&v);
+            if !body_file.is_file() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_FILESPEC));
+            }
+            new_condition.body_file = Some(body_file);
+        }
+
+        if let Some(v) = cfg_bool(cfgmap, "recur_after_failed_check")? {
+            new_condition.recur_after_failed_check = v;
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "check_after", 1)? {
+            new_condition.check_after = Some(Duration::from_secs(v as u64));
+        }
+
+        new_condition.checks = cfg_response_extra_checks(cfgmap, cfg_response_checks(cfgmap)?)?;
+
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "timeout_seconds", 0)?
+            && v > 0
+        {
+            new_condition.timeout = Some(Duration::from_secs(v as u64));
+        }
+
+        // start the condition if the configuration did not suspend it
+        if !new_condition.suspended {
+            new_condition.start();
+        }
+
+        Ok(new_condition)
+    }
+
+    /// Check a configuration map and return item name if Ok
+    ///
+    /// The check is performed exactly in the same way and in the same order
+    /// as in `load_cfgmap`, the only difference is that no actual item is
+    /// created and that a name is returned, which is the name of the item that
+    /// _would_ be created via the equivalent call to `load_cfgmap`
+    pub fn check_cfgmap(cfgmap: &CfgMap, available_tasks: &Vec<&str>) -> Result<String> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "tasks",
+            "recurring",
+            "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
+            "execute_sequence",
+            "break_on_failure",
+            "break_on_success",
+            "suspended",
+            "url",
+            "method",
+            "headers",
+            "body",
+            "body_file",
+            "match_exact",
+            "match_regular_expression",
+            "case_sensitive",
+            "recur_after_failed_check",
+            "check_after",
+            "success_body",
+            "success_status",
+            "success_headers",
+            "failure_body",
+            "failure_status",
+            "max_response_milliseconds",
+            "json_pointer",
+            "json_value",
+            "timeout_seconds",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // common mandatory parameter check
+
+        // type and name are both mandatory: type is checked and name is kept
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "http"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_COND_NAME))?.unwrap();
+
+        // specific mandatory parameter check
+        cfg_mandatory!(cfg_string_check_regex(cfgmap, "url", &RE_HTTP_URL))?;
+
+        // also for optional parameters just check and throw away the result
+
+        // tags are always simply checked this way
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // check configuration task list against the provided ones
+        let cur_key = "tasks";
+        if let Some(v) = cfg_vec_string_check_regex(cfgmap, cur_key, &RE_TASK_NAME)? {
+            for s in v {
+                if !available_tasks.contains(&s.as_str()) {
+                    return Err(cfg_err_invalid_config(cur_key, &s, ERR_INVALID_TASK));
+                }
+            }
+        }
+
+        cfg_bool(cfgmap, "recurring")?;
+        cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)?;
+        cfg_retry_policy(cfgmap)?;
+        cfg_bool(cfgmap, "execute_sequence")?;
+        cfg_bool(cfgmap, "break_on_failure")?;
+        cfg_bool(cfgmap, "break_on_success")?;
+        cfg_bool(cfgmap, "suspended")?;
+
+        let cur_key = "method";
+        if let Some(v) = cfg_string(cfgmap, cur_key)?
+            && http_method(&v).is_none()
+        {
+            return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_VALUE));
+        }
+        cfg_http_headers(cfgmap, "headers")?;
+
+        let body = cfg_string(cfgmap, "body")?;
+        let cur_key = "body_file";
+        if let Some(v) = cfg_string(cfgmap, cur_key)? {
+            if body.is_some() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_PARAMETER));
+            }
+            if !PathBuf::from(&v).is_file() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_FILESPEC));
+            }
+        }
+
+        cfg_bool(cfgmap, "recur_after_failed_check")?;
+        cfg_int_check_above_eq(cfgmap, "check_after", 1)?;
+
+        cfg_response_extra_checks(cfgmap, cfg_response_checks(cfgmap)?)?;
+
+        cfg_int_check_above_eq(cfgmap, "timeout_seconds", 0)?;
+
+        Ok(name)
+    }
+}
+
+impl Condition for HttpCondition {
+    fn set_id(&mut self, id: i64) {
+        self.cond_id = id;
+    }
+    fn get_name(&self) -> String {
+        self.cond_name.clone()
+    }
+    fn get_id(&self) -> i64 {
+        self.cond_id
+    }
+    fn get_type(&self) -> &str {
+        "http"
+    }
+
+    /// Return a hash of this item for comparison
+    fn _hash(&self) -> u64 {
+        let mut s = DefaultHasher::new();
+        self.hash(&mut s);
+        s.finish()
+    }
+
+    fn set_task_registry(&mut self, reg: &'static TaskRegistry) {
+        self.task_registry = Some(reg);
+    }
+
+    fn task_registry(&self) -> Option<&'static TaskRegistry> {
+        self.task_registry
+    }
+
+    fn suspended(&self) -> bool {
+        self.suspended
+    }
+    fn recurring(&self) -> bool {
+        self.recurring
+    }
+    fn has_succeeded(&self) -> bool {
+        self.has_succeeded
+    }
+
+    fn exec_sequence(&self) -> bool {
+        self.exec_sequence
+    }
+    fn break_on_success(&self) -> bool {
+        self.break_on_success
+    }
+    fn break_on_failure(&self) -> bool {
+        self.break_on_failure
+    }
+
+    fn last_checked(&self) -> Option<Instant> {
+        self.last_tested
+    }
+    fn last_succeeded(&self) -> Option<Instant> {
+        self.last_succeeded
+    }
+    fn startup_time(&self) -> Option<Instant> {
+        self.startup_time
+    }
+
+    fn set_checked(&mut self) {
+        self.last_tested = Some(Instant::now());
+    }
+
+    fn set_succeeded(&mut self) {
+        self.last_succeeded = self.last_tested;
+        self.has_succeeded = true;
+    }
+
+    fn reset_succeeded(&mut self) {
+        self.last_succeeded = None;
+        self.has_succeeded = false;
+    }
+
+    fn reset(&mut self) {
+        self.last_tested = None;
+        self.last_succeeded = None;
+        self.has_succeeded = false;
+        self.left_retries = self.max_retries + 1;
+        self.retry_wait = None;
+        self.tasks_failed = true;
+    }
+
+    fn restore_state(
+        &mut self,
+        has_succeeded: bool,
+        last_succeeded: Option<Instant>,
+        left_retries: Option<u64>,
+    ) {
+        self.has_succeeded = has_succeeded;
+        self.last_succeeded = last_succeeded;
+        if let Some(left_retries) = left_retries {
+            self.left_retries = left_retries as i64;
+        }
+    }
+
+    fn left_retries(&self) -> Option<u64> {
+        if self.max_retries < 0 {
+            None
+        } else {
+            Some(self.left_retries as u64)
+        }
+    }
+
+    fn set_retried(&mut self) {
+        if self.left_retries > 0 {
+            self.left_retries -= 1;
+        }
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    fn retry_wait(&self) -> Option<RetryWait> {
+        self.retry_wait
+    }
+
+    fn set_retry_wait(&mut self, wait: Option<RetryWait>) {
+        self.retry_wait = wait;
+    }
+
+    fn start(&mut self) {
+        self.suspended = false;
+        self.left_retries = self.max_retries + 1;
+        self.startup_time = Some(Instant::now());
+
+        // set the tasks_failed flag upon start: no task has been run now
+        // and this is equivalent to a failure; the flag was set to `false`
+        // upon creation in order to have a zero-initialization
+        self.tasks_failed = true;
+    }
+
+    fn suspend(&mut self) -> bool {
+        if self.suspended {
+            false
+        } else {
+            self.suspended = true;
+            true
+        }
+    }
+
+    fn resume(&mut self) -> bool {
+        if self.suspended {
+            self.suspended = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn task_names(&self) -> Vec<String> {
+        self.task_names.clone()
+    }
+
+    fn any_tasks_failed(&self) -> bool {
+        self.tasks_failed
+    }
+
+    fn set_tasks_failed(&mut self, failed: bool) {
+        self.tasks_failed = failed;
+    }
+
+    fn _add_task(&mut self, name: &str) -> bool {
+        let name = String::from(name);
+        if self.task_names.contains(&name) {
+            false
+        } else {
+            self.task_names.push(name);
+            true
+        }
+    }
+
+    fn _remove_task(&mut self, name: &str) -> bool {
+        let name = String::from(name);
+        if self.task_names.contains(&name) {
+            self.task_names
+                .remove(self.task_names.iter().position(|x| x == &name).unwrap());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Mandatory check function
+    ///
+    /// This function actually performs the test: if the request can be
+    /// performed and the response meets the success criteria, the condition
+    /// is verified.
+    fn _check_condition(&mut self) -> Result<Option<bool>> {
+        self.log(
+            LogType::Debug,
+            LOG_WHEN_START,
+            LOG_STATUS_MSG,
+            "checking HTTP request based condition",
+        );
+        // if the minimum interval between checks has been set, obey it
+        // last_tested has already been set by trait to Instant::now()
+        let t = self.last_tested.unwrap();
+        if let Some(e) = self.check_after
+            && e > t - self.check_last
+        {
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_START,
+                LOG_STATUS_MSG,
+                "check explicitly delayed by configuration",
+            );
+            return Ok(Some(false));
+        }
+
+        self._request_failed = false;
+        self._response_status = 0;
+        self._response_body = String::new();
+        self._request_duration = Duration::ZERO;
+
+        // the body file is read each time, as its contents may change
+        let body = if let Some(body_file) = &self.body_file {
+            match fs::read(body_file) {
+                Ok(contents) => Some(contents),
+                Err(e) => {
+                    self.log(
+                        LogType::Warn,
+                        LOG_WHEN_END,
+                        LOG_STATUS_FAIL,
+                        &format!(
+                            "could not read request body from `{}` (reason: {e})",
+                            body_file.to_string_lossy(),
+                        ),
+                    );
+                    self._request_failed = true;
+                    self.last_check_failed = true;
+                    self.check_last = t;
+                    return Ok(Some(false));
+                }
+            }
+        } else {
+            self.body.as_ref().map(|b| b.as_bytes().to_vec())
+        };
+
+        self.log(
+            LogType::Debug,
+            LOG_WHEN_START,
+            LOG_STATUS_MSG,
+            &format!("performing request: `{}`", self.request_line()),
+        );
+
+        let failure_reason = match send_request(
+            &self.method,
+            &self.url,
+            &self.headers,
+            body.as_deref(),
+            self.timeout,
+        ) {
+            Ok(response) => {
+                let (ck_failure_reason, log_severity, log_when, log_status, log_message) =
+                    check_response_outcome(&response, &self.checks);
+                self._response_status = response.status;
+                self._response_body = response.body;
+                self._request_duration = response.duration;
+                self.log(log_severity, log_when, log_status, &log_message);
+                ck_failure_reason
+            }
+            // the request could not be performed thus an error is reported
+            Err(e) => {
+                self.log(
+                    LogType::Warn,
+                    LOG_WHEN_END,
+                    LOG_STATUS_FAIL,
+                    &format!(
+                        "could not perform request: `{}` (reason: {e})",
+                        self.request_line(),
+                    ),
+                );
+                FailureReason::Other
+            }
+        };
+
+        // now the time of the last check can be set to the actual time in
+        // order to allow further checks to comply with the request to be
+        // only run at certain intervals
+        self.check_last = t;
+
+        // return true on success (not persistent unless allowed), false otherwise
+        match failure_reason {
+            FailureReason::NoFailure => {
+                let succeeds = self.last_check_failed || !self.recur_after_failed_check;
+                self.last_check_failed = false;
+                self.log(
+                    LogType::Debug,
+                    LOG_WHEN_END,
+                    LOG_STATUS_OK,
+                    &format!(
+                        "condition checked successfully in {:.2}s",
+                        self._request_duration.as_secs_f64(),
+                    ),
+                );
+                if succeeds {
+                    Ok(Some(true))
+                } else {
+                    self.log(
+                        LogType::Debug,
+                        LOG_WHEN_END,
+                        LOG_STATUS_MSG,
+                        "persistent success status: waiting for failure to recur",
+                    );
+                    Ok(Some(false))
+                }
+            }
+            FailureReason::Other => {
+                self.last_check_failed = true;
+                self._request_failed = true;
+                Ok(Some(false))
+            }
+            reason => {
+                self.last_check_failed = true;
+                self._request_failed = true;
+                self.log(
+                    LogType::Debug,
+                    LOG_WHEN_END,
+                    LOG_STATUS_FAIL,
+                    &format!(
+                        "condition checked unsuccessfully ({} check) in {:.2}s",
+                        reason.check_name(),
+                        self._request_duration.as_secs_f64(),
+                    ),
+                );
+                Ok(Some(false))
+            }
+        }
+    }
+}
+
+// end.
@@ -4,13 +4,20 @@
 //! on construction has passed since the condition activation, and if set to be
 //! recurring it is verified every time that the same amount of time has passed
 //! since the last positive verification.
+//!
+//! When a catch-up policy is set, elapsed time is measured using the wall
+//! clock, so that the time spent while the computer is suspended is taken
+//! into account, and occurrences missed while not running can be recovered.
 
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::time::{Duration, Instant};
 
 use cfgmap::CfgMap;
+use chrono::{DateTime, Local};
 
 use super::base::Condition;
+use super::retry::{RetryPolicy, RetryWait, cfg_retry_policy};
+use super::catch_up::{CatchUp, cfg_catch_up};
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::Result;
 use crate::task::registry::TaskRegistry;
@@ -30,6 +37,7 @@ pub struct IntervalCondition {
     task_names: Vec<String>,
     recurring: bool,
     max_retries: i64,
+    retry_policy: RetryPolicy,
     exec_sequence: bool,
     break_on_failure: bool,
     break_on_success: bool,
@@ -42,14 +50,19 @@ pub struct IntervalCondition {
     startup_time: Option<Instant>,
     task_registry: Option<&'static TaskRegistry>,
     left_retries: i64,
+    retry_wait: Option<RetryWait>,
     tasks_failed: bool,
 
     // specific members
     // parameters
     interval: Duration,
+    catch_up: CatchUp,
 
     // internal values
     checked: Instant,
+    last_fired: Option<DateTime<Local>>,
+    catch_up_from: Option<DateTime<Local>>,
+    catch_up_pending: u64,
 }
 
 // implement the hash protocol
@@ -59,6 +72,9 @@ impl Hash for IntervalCondition {
         self.cond_name.hash(state);
         self.recurring.hash(state);
         self.max_retries.hash(state);
+        if self.retry_policy != RetryPolicy::default() {
+            self.retry_policy.hash(state);
+        }
         self.exec_sequence.hash(state);
         self.break_on_failure.hash(state);
         self.break_on_success.hash(state);
@@ -69,6 +85,12 @@ impl Hash for IntervalCondition {
 
         // specific part
         self.interval.hash(state);
+
+        // only hash the catch-up policy when set, so that conditions that do
+        // not use it keep the same hash (and thus their saved state)
+        if self.catch_up != CatchUp::None {
+            self.catch_up.hash(state);
+        }
     }
 }
 
@@ -97,6 +119,7 @@ impl IntervalCondition {
             task_names: Vec::new(),
             recurring: false,
             max_retries: 0,
+            retry_policy: RetryPolicy::default(),
             exec_sequence: true,
             break_on_failure: false,
             break_on_success: false,
@@ -109,14 +132,19 @@ impl IntervalCondition {
             has_succeeded: false,
             task_registry: None,
             left_retries: 0,
+            retry_wait: None,
             tasks_failed: false,
 
             // specific members initialization
             // parameters
             interval: *interval,
+            catch_up: CatchUp::None,
 
             // specific members initialization
             checked: t,
+            last_fired: None,
+            catch_up_from: None,
+            catch_up_pending: 0,
         }
     }
 
@@ -152,6 +180,12 @@ impl IntervalCondition {
         self
     }
 
+    /// Set the policy for occurrences missed while not running
+    pub fn catches_up(mut self, policy: CatchUp) -> Self {
+        self.catch_up = policy;
+        self
+    }
+
     /// Load an `IntervalCondition` from a [`CfgMap`](https://docs.rs/cfgmap/latest/)
     ///
     /// The `IntervalCondition` is initialized according to the values provided
@@ -166,9 +200,15 @@ impl IntervalCondition {
             "name",
             "tags",
             "interval_seconds",
+            "catch_up",
+            "max_catch_up_runs",
             "tasks",
             "recurring",
             "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
             "execute_sequence",
             "break_on_failure",
             "break_on_success",
@@ -226,6 +266,7 @@ impl IntervalCondition {
         if let Some(v) = cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)? {
             new_condition.max_retries = v;
         }
+        new_condition.retry_policy = cfg_retry_policy(cfgmap)?;
         if let Some(v) = cfg_bool(cfgmap, "execute_sequence")? {
             new_condition.exec_sequence = v;
         }
@@ -240,7 +281,9 @@ impl IntervalCondition {
         }
 
         // specific optional parameter initialization
-        // (none here)
+        if let Some(v) = cfg_catch_up(cfgmap)? {
+            new_condition.catch_up = v;
+        }
 
         // start the condition if the configuration did not suspend it
         if !new_condition.suspended {
@@ -262,9 +305,15 @@ impl IntervalCondition {
             "name",
             "tags",
             "interval_seconds",
+            "catch_up",
+            "max_catch_up_runs",
             "tasks",
             "recurring",
             "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
             "execute_sequence",
             "break_on_failure",
             "break_on_success",
@@ -307,11 +356,15 @@ impl IntervalCondition {
 
         cfg_bool(cfgmap, "recurring")?;
         cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)?;
+        cfg_retry_policy(cfgmap)?;
         cfg_bool(cfgmap, "execute_sequence")?;
         cfg_bool(cfgmap, "break_on_failure")?;
         cfg_bool(cfgmap, "break_on_success")?;
         cfg_bool(cfgmap, "suspended")?;
 
+        // specific optional parameter check
+        cfg_catch_up(cfgmap)?;
+
         Ok(name)
     }
 }
@@ -382,6 +435,7 @@ impl Condition for IntervalCondition {
     fn set_succeeded(&mut self) {
         self.last_succeeded = self.last_tested;
         self.has_succeeded = true;
+        self.last_fired = Some(Local::now());
     }
 
     fn reset_succeeded(&mut self) {
@@ -394,7 +448,35 @@ impl Condition for IntervalCondition {
         self.last_succeeded = None;
         self.has_succeeded = false;
         self.left_retries = self.max_retries + 1;
+        self.retry_wait = None;
         self.tasks_failed = true;
+        self.catch_up_pending = 0;
+    }
+
+    fn restore_state(
+        &mut self,
+        has_succeeded: bool,
+        last_succeeded: Option<Instant>,
+        left_retries: Option<u64>,
+    ) {
+        self.has_succeeded = has_succeeded;
+        self.last_succeeded = last_succeeded;
+        if let Some(left_retries) = left_retries {
+            self.left_retries = left_retries as i64;
+        }
+    }
+
+    fn last_fired(&self) -> Option<DateTime<Local>> {
+        if self.catch_up == CatchUp::None {
+            None
+        } else {
+            self.last_fired
+        }
+    }
+
+    fn restore_last_fired(&mut self, when: DateTime<Local>) {
+        self.last_fired = Some(when);
+        self.catch_up_from = Some(when);
     }
 
     fn left_retries(&self) -> Option<u64> {
@@ -411,10 +493,23 @@ impl Condition for IntervalCondition {
         }
     }
 
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    fn retry_wait(&self) -> Option<RetryWait> {
+        self.retry_wait
+    }
+
+    fn set_retry_wait(&mut self, wait: Option<RetryWait>) {
+        self.retry_wait = wait;
+    }
+
     fn start(&mut self) {
         self.suspended = false;
         self.left_retries = self.max_retries + 1;
         self.startup_time = Some(Instant::now());
+        self.catch_up_from = Some(Local::now());
 
         // set the tasks_failed flag upon start: no task has been run now
         // and this is equivalent to a failure; the flag was set to `false`
@@ -433,7 +528,9 @@ impl Condition for IntervalCondition {
 
     fn resume(&mut self) -> bool {
         if self.suspended {
+            // occurrences missed while suspended are not recovered
             self.suspended = false;
+            self.catch_up_from = Some(Local::now());
             true
         } else {
             false
@@ -487,6 +584,48 @@ impl Condition for IntervalCondition {
         );
         // last_tested has already been set by trait to Instant::now()
         let t = self.last_tested.unwrap();
+
+        // when catching up, all the occurrences that are due are considered
+        // missed, including the one that is run now, and the next ones are
+        // run at the following ticks according to the policy
+        if self.catch_up != CatchUp::None && !self.interval.is_zero() {
+            let now = Local::now();
+            let from = self.catch_up_from.unwrap_or(now);
+            let elapsed = (now - from).to_std().unwrap_or_default();
+            let due = (elapsed.as_millis() / self.interval.as_millis()) as u64;
+            if due > 0 {
+                if due > 1 {
+                    self.log(
+                        LogType::Info,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_MSG,
+                        &format!(
+                            "found {due} missed occurrence(s) since {}",
+                            from.format("%Y-%m-%dT%H:%M:%S"),
+                        ),
+                    );
+                }
+                self.catch_up_pending = self.catch_up.runs(self.catch_up_pending + due) - 1;
+                self.catch_up_from = Some(now);
+                self.checked = t;
+                return Ok(Some(true));
+            } else if self.catch_up_pending > 0 {
+                self.catch_up_pending -= 1;
+                self.log(
+                    LogType::Info,
+                    LOG_WHEN_PROC,
+                    LOG_STATUS_MSG,
+                    &format!(
+                        "catching up with a missed occurrence ({} more pending)",
+                        self.catch_up_pending,
+                    ),
+                );
+                return Ok(Some(true));
+            } else {
+                return Ok(Some(false));
+            }
+        }
+
         if self.interval <= t - self.checked {
             self.checked = t;
             Ok(Some(true))
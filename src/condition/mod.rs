@@ -2,18 +2,30 @@
 
 pub mod base; // this only defines the trait
 pub mod registry; // the main condition registry
+pub mod catch_up; // policies shared by time based conditions
+pub mod retry; // delays between task retries
 
 // specific condition types
 pub mod bucket_cond;
 pub mod command_cond;
+pub mod composite_cond;
+pub mod cron_cond;
+pub mod file_cond;
 pub mod idle_cond;
 pub mod interval_cond;
 pub mod lua_cond;
+pub mod tcp_cond;
 pub mod time_cond;
 
 #[cfg(feature = "dbus")]
 pub mod dbus_cond;
 
+#[cfg(feature = "http")]
+pub mod http_cond;
+
+#[cfg(target_os = "linux")]
+pub mod resource_cond;
+
 #[cfg(windows)]
 #[cfg(feature = "wmi")]
 pub mod wmi_cond;
@@ -6,10 +6,24 @@
 //! _active_ conditions: a `Condition` object cannot in fact be considered
 //! active until it is _registered_. A registered condition has an unique
 //! nonzero ID.
+//!
+//! The registry can also persist the state of conditions to a file, so that
+//! for instance non-recurring conditions that already succeeded are not
+//! checked again after a restart: the saved state is only restored if the
+//! condition configuration is unchanged, that is if its hash is the same.
+//!
+//! The outcome of the most recent check of each condition is also recorded,
+//! so that composite conditions can use it to evaluate their expressions.
 
 use parking_lot::{Mutex, RwLock};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Local, TimeZone};
+use serde_json::{Map, Value, json};
 
 use lazy_static::lazy_static;
 use unique_id::Generator;
@@ -35,6 +49,108 @@ fn generate_condition_id() -> i64 {
     UID_GENERATOR.next_id()
 }
 
+/// A snapshot of the internal state of a condition
+///
+/// The snapshot can only be taken when the condition is not busy, and is
+/// used to report the state of a condition to the outside.
+pub struct ConditionState {
+    pub cond_type: String,
+    pub suspended: bool,
+    pub recurring: bool,
+    pub has_succeeded: bool,
+    pub last_checked: Option<Instant>,
+    pub last_succeeded: Option<Instant>,
+    pub left_retries: Option<u64>,
+    pub task_names: Vec<String>,
+}
+
+// the persistent state of a condition, along with the hash of the condition
+// it has been taken from; in the state file instants are saved as wall clock
+// time, in milliseconds since the epoch
+#[derive(Clone)]
+struct SavedState {
+    hash: u64,
+    has_succeeded: bool,
+    last_succeeded: Option<Instant>,
+    left_retries: Option<u64>,
+    tasks_failed: bool,
+    suspended: bool,
+    last_fired: Option<DateTime<Local>>,
+}
+
+impl SavedState {
+    fn from_condition(cond: &dyn Condition) -> Self {
+        SavedState {
+            hash: cond._hash(),
+            has_succeeded: cond.has_succeeded(),
+            last_succeeded: cond.last_succeeded(),
+            left_retries: cond.left_retries(),
+            tasks_failed: cond.any_tasks_failed(),
+            suspended: cond.suspended(),
+            last_fired: cond.last_fired(),
+        }
+    }
+
+    // compare with the state of a condition without computing its hash,
+    // which is costly and only needed when the state actually changed
+    fn matches(&self, cond: &dyn Condition) -> bool {
+        self.has_succeeded == cond.has_succeeded()
+            && self.last_succeeded == cond.last_succeeded()
+            && self.left_retries == cond.left_retries()
+            && self.tasks_failed == cond.any_tasks_failed()
+            && self.suspended == cond.suspended()
+            && self.last_fired == cond.last_fired()
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        Some(SavedState {
+            hash: value.get("hash")?.as_u64()?,
+            has_succeeded: value.get("has_succeeded")?.as_bool()?,
+            last_succeeded: value
+                .get("last_succeeded")?
+                .as_u64()
+                .and_then(millis_to_instant),
+            left_retries: value.get("left_retries")?.as_u64(),
+            tasks_failed: value.get("tasks_failed")?.as_bool()?,
+            suspended: value.get("suspended")?.as_bool()?,
+            // not saved for conditions that do not catch up
+            last_fired: value
+                .get("last_fired")
+                .and_then(|v| v.as_i64())
+                .and_then(|millis| Local.timestamp_millis_opt(millis).single()),
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "hash": self.hash,
+            "has_succeeded": self.has_succeeded,
+            "last_succeeded": self.last_succeeded.map(instant_to_millis),
+            "left_retries": self.left_retries,
+            "tasks_failed": self.tasks_failed,
+            "suspended": self.suspended,
+            "last_fired": self.last_fired.map(|dt| dt.timestamp_millis()),
+        })
+    }
+}
+
+// conversions between instants and wall clock time for persistence: an
+// instant that would precede the system boot cannot be restored
+fn instant_to_millis(instant: Instant) -> u64 {
+    let elapsed = Instant::now().saturating_duration_since(instant);
+    (SystemTime::now() - elapsed)
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn millis_to_instant(millis: u64) -> Option<Instant> {
+    let elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH + Duration::from_millis(millis))
+        .unwrap_or_default();
+    Instant::now().checked_sub(elapsed)
+}
+
 /// The condition registry: there must be one and only one condition registry
 /// in each instance of the process, and should have `'static` lifetime. It may
 /// be passed around as a reference.
@@ -56,6 +172,19 @@ pub struct ConditionRegistry {
     // be removed are stored as names
     items_to_remove: Arc<Mutex<Vec<String>>>,
     items_to_add: Arc<Mutex<Vec<ConditionRef>>>,
+
+    // the optional file where the state of conditions is persisted, and
+    // the last known persistent state of each condition by name
+    state_file: RwLock<Option<PathBuf>>,
+    saved_states: Arc<Mutex<HashMap<String, SavedState>>>,
+
+    // the outcome of the most recent check of each condition, by name, along
+    // with the scheduler tick in which the check has been performed
+    last_outcomes: Arc<Mutex<HashMap<String, (bool, u64)>>>,
+    current_tick: Arc<Mutex<u64>>,
+
+    // the conditions that have no tasks, and are only evaluated as inputs
+    task_less: Arc<Mutex<HashSet<String>>>,
 }
 
 #[allow(dead_code)]
@@ -70,7 +199,146 @@ impl ConditionRegistry {
 
             items_to_remove: Arc::new(Mutex::new(Vec::new())),
             items_to_add: Arc::new(Mutex::new(Vec::new())),
+
+            state_file: RwLock::new(None),
+            saved_states: Arc::new(Mutex::new(HashMap::new())),
+
+            last_outcomes: Arc::new(Mutex::new(HashMap::new())),
+            current_tick: Arc::new(Mutex::new(0)),
+
+            task_less: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Set the file used to persist the state of conditions
+    ///
+    /// The file is read immediately if it exists, so that the saved states
+    /// can be restored when conditions are added to the registry: for this
+    /// reason this function must be called before adding any condition. If
+    /// the file cannot be read or parsed an error is returned, however the
+    /// file is still used to save the state of conditions from now on.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - the path of the state file
+    pub fn set_state_file(&self, path: &Path) -> Result<()> {
+        *self.state_file.write() = Some(PathBuf::from(path));
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let contents: Value = match serde_json::from_str(&fs::read_to_string(path)?) {
+            Ok(v) => v,
+            Err(_) => return Err(Error::new(Kind::Invalid, ERR_CONDREG_INVALID_STATE_FILE)),
+        };
+        let Some(conditions) = contents.get("conditions").and_then(|v| v.as_object()) else {
+            return Err(Error::new(Kind::Invalid, ERR_CONDREG_INVALID_STATE_FILE));
+        };
+        let mxs0 = self.saved_states.clone();
+        let mut saved_states = mxs0.lock();
+        for (name, value) in conditions {
+            if let Some(state) = SavedState::from_json(value) {
+                saved_states.insert(name.clone(), state);
+            }
+        }
+        Ok(())
+    }
+
+    // restore the saved state of a condition, only if its hash is unchanged
+    fn restore_condition_state(&self, cond: &mut ConditionRef) {
+        let name = cond.get_name();
+        let mxs0 = self.saved_states.clone();
+        let saved_states = mxs0.lock();
+        if let Some(state) = saved_states.get(&name)
+            && state.hash == cond._hash()
+        {
+            cond.restore_state(state.has_succeeded, state.last_succeeded, state.left_retries);
+            cond.set_tasks_failed(state.tasks_failed);
+            if let Some(last_fired) = state.last_fired {
+                cond.restore_last_fired(last_fired);
+            }
+            if state.suspended {
+                cond.suspend();
+            }
+            log(
+                LogType::Debug,
+                LOG_EMITTER_CONDITION_REGISTRY,
+                LOG_ACTION_RESTORE_STATE,
+                None,
+                LOG_WHEN_INIT,
+                LOG_STATUS_OK,
+                &format!("state of condition {name} restored"),
+            );
+        }
+    }
+
+    // write all the saved states of the registered conditions to the state
+    // file: the file is replaced atomically, by renaming a temporary file
+    fn write_state_file(&self, path: &Path, saved_states: &HashMap<String, SavedState>) -> Result<()> {
+        let cl0 = self.condition_list.read();
+        let mut conditions = Map::new();
+        for (name, state) in saved_states.iter() {
+            if cl0.contains_key(name) {
+                conditions.insert(name.clone(), state.to_json());
+            }
         }
+        drop(cl0);
+
+        let mut tmp_path = PathBuf::from(path).into_os_string();
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, json!({ "conditions": conditions }).to_string())?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    // update the saved state of a condition, and if it changed write the
+    // state file: errors are only logged, as there is nothing else to do
+    fn save_condition_state(&self, cond: &dyn Condition) {
+        let Some(path) = self.state_file.read().clone() else {
+            return;
+        };
+        let name = cond.get_name();
+        let mxs0 = self.saved_states.clone();
+        let mut saved_states = mxs0.lock();
+        if let Some(state) = saved_states.get(&name)
+            && state.matches(cond)
+        {
+            return;
+        }
+        saved_states.insert(name.clone(), SavedState::from_condition(cond));
+        if let Err(e) = self.write_state_file(&path, &saved_states) {
+            log(
+                LogType::Warn,
+                LOG_EMITTER_CONDITION_REGISTRY,
+                LOG_ACTION_SAVE_STATE,
+                None,
+                LOG_WHEN_PROC,
+                LOG_STATUS_FAIL,
+                &format!("could not save state of condition {name}: {e}"),
+            );
+        }
+    }
+
+    /// Save the state of all conditions to the state file, if set
+    ///
+    /// Busy conditions are not waited for, and their last saved state is
+    /// kept: this is mostly meant to be called when the application exits.
+    pub fn save_state(&self) -> Result<()> {
+        let Some(path) = self.state_file.read().clone() else {
+            return Ok(());
+        };
+        let cl0 = self.condition_list.read();
+        let conds: Vec<Arc<Mutex<ConditionRef>>> = cl0.values().cloned().collect();
+        drop(cl0);
+
+        let mxs0 = self.saved_states.clone();
+        let mut saved_states = mxs0.lock();
+        for cond in conds {
+            if let Some(cond) = cond.try_lock() {
+                saved_states.insert(cond.get_name(), SavedState::from_condition(cond.as_ref()));
+            }
+        }
+        self.write_state_file(&path, &saved_states)
     }
 
     /// Check whether or not a condition with the provided name is in the
@@ -152,6 +420,10 @@ impl ConditionRegistry {
         // only consume an ID if the condition is not discarded, otherwise the
         // released condition would be safe to run even when not registered
         cond_ref.set_id(generate_condition_id());
+        self.restore_condition_state(&mut cond_ref);
+        if !cond_ref.has_tasks() {
+            self.task_less.clone().lock().insert(name.clone());
+        }
         self.condition_list
             .write()
             .insert(name, Arc::new(Mutex::new(cond_ref)));
@@ -216,6 +488,8 @@ impl ConditionRegistry {
                     };
                     let mut condition = mxc0.into_inner();
                     condition.set_id(0);
+                    self.last_outcomes.clone().lock().remove(name);
+                    self.task_less.clone().lock().remove(name);
                     Ok(Some(condition))
                 }
                 _ => Err(Error::new(Kind::Failed, ERR_CONDREG_CANNOT_PULL_COND)),
@@ -289,6 +563,7 @@ impl ConditionRegistry {
             // here and return the operation result from the condition itself
             let mut cond = cond.lock();
             cond.reset();
+            self.save_condition_state(cond.as_ref());
             Ok(())
         }
     }
@@ -346,6 +621,7 @@ impl ConditionRegistry {
             // here and return the operation result from the condition itself
             let mut cond = cond.lock();
             cond.suspend();
+            self.save_condition_state(cond.as_ref());
             Ok(())
         }
     }
@@ -408,6 +684,7 @@ impl ConditionRegistry {
             // here and return the operation result from the condition itself
             let mut cond = cond.lock();
             cond.resume();
+            self.save_condition_state(cond.as_ref());
             Ok(())
         }
     }
@@ -451,6 +728,87 @@ impl ConditionRegistry {
         res.is_some()
     }
 
+    /// Take a snapshot of the state of a condition
+    ///
+    /// The condition is not waited for when busy: in this case, as well as
+    /// when the condition is not found in the registry, `None` is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the condition to inspect
+    pub fn condition_state(&self, name: &str) -> Option<ConditionState> {
+        let cl0 = self.condition_list.read();
+        let cond = cl0.get(name)?.clone();
+        drop(cl0);
+
+        let cond = cond.try_lock()?;
+        Some(ConditionState {
+            cond_type: cond.get_type().to_string(),
+            suspended: cond.suspended(),
+            recurring: cond.recurring(),
+            has_succeeded: cond.has_succeeded(),
+            last_checked: cond.last_checked(),
+            last_succeeded: cond.last_succeeded(),
+            left_retries: cond.left_retries(),
+            task_names: cond.task_names(),
+        })
+    }
+
+    /// Start a new scheduler tick
+    ///
+    /// Conditions without tasks are evaluated at most once per tick, however
+    /// many composite conditions refer to them: this function is called by
+    /// the scheduler at every tick, so that they are evaluated again.
+    pub fn advance_tick(&self) {
+        *self.current_tick.clone().lock() += 1;
+    }
+
+    /// Evaluate a condition on behalf of a composite condition
+    ///
+    /// Conditions that have no associated tasks are only checked when they
+    /// are evaluated by the composite conditions that refer to them: in this
+    /// case the check is performed by the first composite condition that
+    /// needs it within a scheduler tick, and the following ones receive the
+    /// same outcome, so that they all see the input in the same state.
+    /// Conditions that have associated tasks are checked independently by
+    /// the scheduler instead, and the outcome of their most recent check is
+    /// returned. Composite conditions cannot be evaluated this way.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the condition to evaluate
+    pub fn evaluate_input(&self, name: &str) -> Result<bool> {
+        let cl0 = self.condition_list.read();
+        let Some(cond) = cl0.get(name) else {
+            return Err(Error::new(Kind::Invalid, ERR_CONDREG_COND_NOT_FOUND));
+        };
+        let cond = cond.clone();
+        drop(cl0);
+
+        let mxo0 = self.last_outcomes.clone();
+        if !self.task_less.clone().lock().contains(name) {
+            return Ok(mxo0.lock().get(name).is_some_and(|(outcome, _)| *outcome));
+        }
+
+        // conditions without tasks are never busy for long, as they are not
+        // checked by the scheduler: waiting for them also guarantees that a
+        // concurrent evaluation is complete before its outcome is looked up
+        let mut cond = cond.lock();
+        if cond.get_type() == "composite" {
+            return Err(Error::new(Kind::Forbidden, ERR_CONDREG_COMPOSITE_INPUT));
+        }
+        let tick = *self.current_tick.clone().lock();
+        if let Some((outcome, checked_tick)) = mxo0.lock().get(name)
+            && *checked_tick == tick
+        {
+            return Ok(*outcome);
+        }
+        let outcome = matches!(cond.evaluate(), Ok(Some(true)));
+        mxo0.lock().insert(String::from(name), (outcome, tick));
+        self.save_condition_state(cond.as_ref());
+        Ok(outcome)
+    }
+
     /// Report the number of busy conditions
     ///
     /// Report an unsigned integer corresponding to how many conditions are
@@ -512,8 +870,18 @@ impl ConditionRegistry {
             drop(bcount);
             drop(cb0);
 
-            // the heart of all: test the condition and run tasks if verified
-            let res = match cond.test() {
+            // the heart of all: test the condition and run tasks if verified;
+            // the outcome is recorded before running tasks, as these might
+            // take a long time and composite conditions may need it anyway
+            let tested = cond.test();
+            if cond.has_tasks() {
+                let tick = *self.current_tick.clone().lock();
+                self.last_outcomes
+                    .clone()
+                    .lock()
+                    .insert(String::from(name), (matches!(tested, Ok(Some(true))), tick));
+            }
+            let res = match tested {
                 Ok(o) => {
                     if let Some(outcome) = o {
                         if outcome { cond.run_tasks() } else { Ok(None) }
@@ -605,6 +973,9 @@ impl ConditionRegistry {
             drop(queue);
             drop(mxq0);
 
+            // persist the possibly changed state of the condition
+            self.save_condition_state(cond.as_ref());
+
             // this is the right time to operate on the registry if there are
             // no busy conditions remaining, thus in this order:
             //
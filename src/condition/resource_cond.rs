@@ -0,0 +1,998 @@
+//! Define a system resource threshold based condition
+//!
+//! This type of condition reads a system metric at each check, and is
+//! verified when the metric crosses a configured threshold. The available
+//! metrics are the system load averages, memory and swap usage, and disk
+//! usage for a given mount point, as reported by `/proc/loadavg`,
+//! `/proc/meminfo` and `statvfs` respectively: therefore this condition is
+//! only available on Linux. Optionally, the threshold must have been crossed
+//! for a number of consecutive checks before the condition is verified, so
+//! that short spikes can be ignored.
+
+#![cfg(target_os = "linux")]
+
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use cfgmap::CfgMap;
+
+use super::base::Condition;
+use super::retry::{RetryPolicy, RetryWait, cfg_retry_policy};
+use crate::utility::logging::{LogType, log};
+use crate::utility::result::{Error, Kind, Result};
+use crate::task::registry::TaskRegistry;
+use crate::{cfg_mandatory, constants::*};
+
+use crate::cfghelp::*;
+
+// system files the metrics are read from
+const PROC_LOADAVG: &str = "/proc/loadavg";
+const PROC_MEMINFO: &str = "/proc/meminfo";
+
+/// The metrics that can be checked against a threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceMetric {
+    LoadAverage1,             // "load_average_1"
+    LoadAverage5,             // "load_average_5"
+    LoadAverage15,            // "load_average_15"
+    MemoryUsedPercent,        // "memory_used_percent"
+    MemoryAvailableMegabytes, // "memory_available_megabytes"
+    SwapUsedPercent,          // "swap_used_percent"
+    DiskUsedPercent,          // "disk_used_percent"
+    DiskAvailableMegabytes,   // "disk_available_megabytes"
+}
+
+impl ResourceMetric {
+    /// Convert a metric name as used in the configuration file
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "load_average_1" => Some(ResourceMetric::LoadAverage1),
+            "load_average_5" => Some(ResourceMetric::LoadAverage5),
+            "load_average_15" => Some(ResourceMetric::LoadAverage15),
+            "memory_used_percent" => Some(ResourceMetric::MemoryUsedPercent),
+            "memory_available_megabytes" => Some(ResourceMetric::MemoryAvailableMegabytes),
+            "swap_used_percent" => Some(ResourceMetric::SwapUsedPercent),
+            "disk_used_percent" => Some(ResourceMetric::DiskUsedPercent),
+            "disk_available_megabytes" => Some(ResourceMetric::DiskAvailableMegabytes),
+            _ => None,
+        }
+    }
+
+    /// Return the name of the metric, as used in the configuration file
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResourceMetric::LoadAverage1 => "load_average_1",
+            ResourceMetric::LoadAverage5 => "load_average_5",
+            ResourceMetric::LoadAverage15 => "load_average_15",
+            ResourceMetric::MemoryUsedPercent => "memory_used_percent",
+            ResourceMetric::MemoryAvailableMegabytes => "memory_available_megabytes",
+            ResourceMetric::SwapUsedPercent => "swap_used_percent",
+            ResourceMetric::DiskUsedPercent => "disk_used_percent",
+            ResourceMetric::DiskAvailableMegabytes => "disk_available_megabytes",
+        }
+    }
+
+    /// Tell whether or not the metric refers to a mount point
+    pub fn is_disk_metric(&self) -> bool {
+        matches!(
+            self,
+            ResourceMetric::DiskUsedPercent | ResourceMetric::DiskAvailableMegabytes
+        )
+    }
+}
+
+/// The comparison used to determine whether the threshold is crossed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThresholdOperator {
+    Greater,      // "gt"
+    GreaterEqual, // "ge"
+    Less,         // "lt"
+    LessEqual,    // "le"
+}
+
+impl ThresholdOperator {
+    /// Convert an operator name as used in the configuration file
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "gt" => Some(ThresholdOperator::Greater),
+            "ge" => Some(ThresholdOperator::GreaterEqual),
+            "lt" => Some(ThresholdOperator::Less),
+            "le" => Some(ThresholdOperator::LessEqual),
+            _ => None,
+        }
+    }
+
+    /// Compare a value against the threshold
+    pub fn crossed(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            ThresholdOperator::Greater => value > threshold,
+            ThresholdOperator::GreaterEqual => value >= threshold,
+            ThresholdOperator::Less => value < threshold,
+            ThresholdOperator::LessEqual => value <= threshold,
+        }
+    }
+
+    // a symbol only used in log messages
+    fn symbol(&self) -> &'static str {
+        match self {
+            ThresholdOperator::Greater => ">",
+            ThresholdOperator::GreaterEqual => ">=",
+            ThresholdOperator::Less => "<",
+            ThresholdOperator::LessEqual => "<=",
+        }
+    }
+}
+
+// read the load averages from the corresponding system file
+fn read_load_averages() -> Result<(f64, f64, f64)> {
+    let contents = fs::read_to_string(PROC_LOADAVG)?;
+    let values: Vec<f64> = contents
+        .split_whitespace()
+        .take(3)
+        .filter_map(|x| x.parse::<f64>().ok())
+        .collect();
+    if values.len() != 3 {
+        return Err(Error::new(
+            Kind::Failed,
+            &format!("{ERR_INVALID_VALUE}: `{PROC_LOADAVG}`"),
+        ));
+    }
+    Ok((values[0], values[1], values[2]))
+}
+
+// read the memory information (in kB) from the corresponding system file
+fn read_meminfo() -> Result<HashMap<String, u64>> {
+    let contents = fs::read_to_string(PROC_MEMINFO)?;
+    let mut info = HashMap::new();
+    for line in contents.lines() {
+        if let Some((key, value)) = line.split_once(':')
+            && let Some(value) = value.split_whitespace().next()
+            && let Ok(value) = value.parse::<u64>()
+        {
+            info.insert(String::from(key.trim()), value);
+        }
+    }
+    Ok(info)
+}
+
+// retrieve a single entry from the memory information
+fn meminfo_entry(info: &HashMap<String, u64>, key: &str) -> Result<u64> {
+    info.get(key).copied().ok_or(Error::new(
+        Kind::Failed,
+        &format!("{ERR_INVALID_VALUE}: `{PROC_MEMINFO}` ({key})"),
+    ))
+}
+
+// read used and available bytes on the filesystem the path belongs to, the
+// used space is computed in the same way as `df` does
+fn read_disk_usage(path: &Path) -> Result<(u64, u64)> {
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| Error::new(Kind::Invalid, &e.to_string()))?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: the path is a valid NUL terminated string and the structure
+    // is owned by this function for the entire duration of the call
+    let res = unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) };
+    if res != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let block_size = stat.f_frsize as u64;
+    let used = (stat.f_blocks as u64).saturating_sub(stat.f_bfree as u64) * block_size;
+    let available = stat.f_bavail as u64 * block_size;
+    Ok((used, available))
+}
+
+/// Read the current value of a metric
+///
+/// The `mount_point` argument is only used for disk related metrics.
+pub fn read_metric(metric: ResourceMetric, mount_point: &Path) -> Result<f64> {
+    match metric {
+        ResourceMetric::LoadAverage1 => Ok(read_load_averages()?.0),
+        ResourceMetric::LoadAverage5 => Ok(read_load_averages()?.1),
+        ResourceMetric::LoadAverage15 => Ok(read_load_averages()?.2),
+        ResourceMetric::MemoryUsedPercent => {
+            let info = read_meminfo()?;
+            let total = meminfo_entry(&info, "MemTotal")?;
+            let available = meminfo_entry(&info, "MemAvailable")?;
+            if total == 0 {
+                Ok(0.0)
+            } else {
+                Ok(total.saturating_sub(available) as f64 * 100.0 / total as f64)
+            }
+        }
+        ResourceMetric::MemoryAvailableMegabytes => {
+            let info = read_meminfo()?;
+            Ok(meminfo_entry(&info, "MemAvailable")? as f64 / 1024.0)
+        }
+        ResourceMetric::SwapUsedPercent => {
+            let info = read_meminfo()?;
+            let total = meminfo_entry(&info, "SwapTotal")?;
+            let free = meminfo_entry(&info, "SwapFree")?;
+            if total == 0 {
+                Ok(0.0)
+            } else {
+                Ok(total.saturating_sub(free) as f64 * 100.0 / total as f64)
+            }
+        }
+        ResourceMetric::DiskUsedPercent => {
+            let (used, available) = read_disk_usage(mount_point)?;
+            if used + available == 0 {
+                Ok(0.0)
+            } else {
+                Ok(used as f64 * 100.0 / (used + available) as f64)
+            }
+        }
+        ResourceMetric::DiskAvailableMegabytes => {
+            let (_, available) = read_disk_usage(mount_point)?;
+            Ok(available as f64 / (1024.0 * 1024.0))
+        }
+    }
+}
+
+// the threshold can be given either as an integer or as a float
+fn cfg_threshold(cfgmap: &CfgMap, key: &str) -> Result<Option<f64>> {
+    if let Some(item) = cfgmap.get(key) {
+        if item.is_int() {
+            Ok(Some(*item.as_int().unwrap() as f64))
+        } else if item.is_float() {
+            Ok(Some(*item.as_float().unwrap()))
+        } else {
+            Err(cfg_err_invalid_config(
+                key,
+                STR_INVALID_TYPE,
+                ERR_INVALID_PARAMETER,
+            ))
+        }
+    } else {
+        Ok(None)
+    }
+}
+
+/// System Resource Threshold Based Condition
+///
+/// This condition is verified when a system metric crosses the configured
+/// threshold for the required number of consecutive checks.
+pub struct ResourceCondition {
+    // commom members
+    // parameters
+    cond_id: i64,
+    cond_name: String,
+    task_names: Vec<String>,
+    recurring: bool,
+    max_retries: i64,
+    retry_policy: RetryPolicy,
+    exec_sequence: bool,
+    break_on_failure: bool,
+    break_on_success: bool,
+    suspended: bool,
+
+    // internal values
+    has_succeeded: bool,
+    last_tested: Option<Instant>,
+    last_succeeded: Option<Instant>,
+    startup_time: Option<Instant>,
+    task_registry: Option<&'static TaskRegistry>,
+    left_retries: i64,
+    retry_wait: Option<RetryWait>,
+    tasks_failed: bool,
+
+    // specific members
+    // parameters
+    metric: ResourceMetric,
+    operator: ThresholdOperator,
+    threshold: f64,
+    mount_point: PathBuf,
+    consecutive_checks: u64,
+    recur_after_failed_check: bool,
+    check_after: Option<Duration>,
+
+    // internal values
+    check_last: Instant,
+    crossed_count: u64,
+
+    // this is different from has_succeeded: the latter is set when the
+    // condition has actually been successful, which in this case may not
+    // be true, as a persistent success may not let the condition succeed
+    last_check_failed: bool,
+}
+
+// implement the hash protocol
+impl Hash for ResourceCondition {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // common part
+        self.cond_name.hash(state);
+        self.recurring.hash(state);
+        self.max_retries.hash(state);
+        if self.retry_policy != RetryPolicy::default() {
+            self.retry_policy.hash(state);
+        }
+        self.exec_sequence.hash(state);
+        self.break_on_failure.hash(state);
+        self.break_on_success.hash(state);
+        // suspended is more a status: let's not consider it yet
+        // self.suspended.hash(state);
+        // task order is significant: hash on vec is not sorted
+        self.task_names.hash(state);
+
+        // specific part
+        self.metric.hash(state);
+        self.operator.hash(state);
+        // f64 does not implement Hash: use its binary representation
+        self.threshold.to_bits().hash(state);
+        self.mount_point.hash(state);
+        self.consecutive_checks.hash(state);
+        self.recur_after_failed_check.hash(state);
+        self.check_after.hash(state);
+    }
+}
+
+#[allow(dead_code)]
+impl ResourceCondition {
+    /// Create a new system resource threshold based condition
+    ///
+    /// By default the condition is verified when the metric is greater than
+    /// the threshold, and disk related metrics refer to the root filesystem.
+    pub fn new(name: &str, metric: ResourceMetric, threshold: f64) -> Self {
+        log(
+            LogType::Debug,
+            LOG_EMITTER_CONDITION_RESOURCE,
+            LOG_ACTION_NEW,
+            Some((name, 0)),
+            LOG_WHEN_INIT,
+            LOG_STATUS_MSG,
+            &format!("CONDITION {name}: creating a new system resource threshold based condition"),
+        );
+        let t = Instant::now();
+        ResourceCondition {
+            // common members initialization
+            // reset ID
+            cond_id: 0,
+
+            // parameters
+            cond_name: String::from(name),
+            task_names: Vec::new(),
+            recurring: false,
+            max_retries: 0,
+            retry_policy: RetryPolicy::default(),
+            exec_sequence: true,
+            break_on_failure: false,
+            break_on_success: false,
+            suspended: true,
+
+            // internal values
+            startup_time: None,
+            last_tested: None,
+            last_succeeded: None,
+            has_succeeded: false,
+            task_registry: None,
+            left_retries: 0,
+            retry_wait: None,
+            tasks_failed: false,
+
+            // specific members initialization
+            // parameters
+            metric,
+            operator: ThresholdOperator::Greater,
+            threshold,
+            mount_point: PathBuf::from("/"),
+            consecutive_checks: 1,
+            recur_after_failed_check: false,
+            check_after: None,
+
+            // internal values
+            check_last: t,
+            crossed_count: 0,
+            last_check_failed: true,
+        }
+    }
+
+    // constructor modifiers
+    /// Set the command execution to sequence or parallel
+    pub fn execs_sequentially(mut self, yes: bool) -> Self {
+        self.exec_sequence = yes;
+        self
+    }
+
+    /// If true, *sequential* task execution will break on first success
+    pub fn breaks_on_success(mut self, yes: bool) -> Self {
+        self.break_on_success = yes;
+        self
+    }
+
+    /// If true, *sequential* task execution will break on first failure
+    pub fn breaks_on_failure(mut self, yes: bool) -> Self {
+        self.break_on_failure = yes;
+        self
+    }
+
+    /// If true, create a recurring condition
+    pub fn repeats(mut self, yes: bool) -> Self {
+        self.recurring = yes;
+        self
+    }
+
+    /// Retry `num` times on task failure if not recurring
+    pub fn retries(mut self, num: i64) -> Self {
+        assert!(num >= -1, "max number of retries must be positive or -1");
+        self.max_retries = num;
+        self
+    }
+
+    /// State that the first check and possible following tests are to be
+    /// performed after a certain amount of time
+    ///
+    /// Along with the number of consecutive checks, this determines for how
+    /// long the threshold has to be crossed before the condition succeeds.
+    pub fn checks_after(mut self, delta: Duration) -> Self {
+        self.check_after = Some(delta);
+        self
+    }
+
+    /// Constructor modifier to set the comparison with the threshold
+    pub fn compares_with(mut self, operator: ThresholdOperator) -> Self {
+        self.operator = operator;
+        self
+    }
+
+    /// Constructor modifier to set the mount point for disk related metrics
+    pub fn on_mount_point(mut self, path: &Path) -> Self {
+        self.mount_point = PathBuf::from(path);
+        self
+    }
+
+    /// Constructor modifier to require the threshold to be crossed for a
+    /// number of consecutive checks
+    pub fn holds_for(mut self, checks: u64) -> Self {
+        assert!(
+            checks >= 1,
+            "number of consecutive checks must be at least 1"
+        );
+        self.consecutive_checks = checks;
+        self
+    }
+
+    /// Constructor modifier to recur only after failure
+    ///
+    /// Specifies that the condition is verified on check success only if
+    /// there has been at least one failure after the last successful test.
+    pub fn recurs_after_check_failure(mut self, yes: bool) -> Self {
+        self.recur_after_failed_check = yes;
+        self
+    }
+
+    /// Load a `ResourceCondition` from a [`CfgMap`](https://docs.rs/cfgmap/latest/)
+    ///
+    /// The `ResourceCondition` is initialized according to the values
+    /// provided in the `CfgMap` argument. If the `CfgMap` format does not
+    /// comply with the requirements of a `ResourceCondition` an error is
+    /// raised.
+    pub fn load_cfgmap(
+        cfgmap: &CfgMap,
+        task_registry: &'static TaskRegistry,
+    ) -> Result<ResourceCondition> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "tasks",
+            "recurring",
+            "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
+            "execute_sequence",
+            "break_on_failure",
+            "break_on_success",
+            "suspended",
+            "metric",
+            "operator",
+            "threshold",
+            "mount_point",
+            "consecutive_checks",
+            "recur_after_failed_check",
+            "check_after",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // type and name are both mandatory but type is only checked
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "resource"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_COND_NAME))?.unwrap();
+
+        // specific mandatory parameter retrieval
+        let cur_key = "metric";
+        let metric = cfg_mandatory!(cfg_string(cfgmap, cur_key))?.unwrap();
+        let Some(metric) = ResourceMetric::from_name(&metric) else {
+            return Err(cfg_err_invalid_config(cur_key, &metric, ERR_INVALID_VALUE));
+        };
+        let threshold = cfg_mandatory!(cfg_threshold(cfgmap, "threshold"))?.unwrap();
+
+        // initialize the structure
+        let mut new_condition = ResourceCondition::new(&name, metric, threshold);
+        new_condition.task_registry = Some(task_registry);
+
+        // by default make condition active if loaded from configuration: if
+        // the configuration changes this state the condition will not start
+        new_condition.suspended = false;
+
+        // common optional parameter initialization
+
+        // tags are always simply checked this way as no value is needed
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // retrieve task list and try to directly add each task
+        let cur_key = "tasks";
+        if let Some(v) = cfg_vec_string_check_regex(cfgmap, cur_key, &RE_TASK_NAME)? {
+            for s in v {
+                if !new_condition.add_task(&s)? {
+                    return Err(cfg_err_invalid_config(cur_key, &s, ERR_INVALID_TASK));
+                }
+            }
+        }
+
+        if let Some(v) = cfg_bool(cfgmap, "recurring")? {
+            new_condition.recurring = v;
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)? {
+            new_condition.max_retries = v;
+        }
+        new_condition.retry_policy = cfg_retry_policy(cfgmap)?;
+        if let Some(v) = cfg_bool(cfgmap, "execute_sequence")? {
+            new_condition.exec_sequence = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "break_on_failure")? {
+            new_condition.break_on_failure = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "break_on_success")? {
+            new_condition.break_on_success = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "suspended")? {
+            new_condition.suspended = v;
+        }
+
+        // specific optional parameter initialization
+        let cur_key = "operator";
+        if let Some(v) = cfg_string(cfgmap, cur_key)? {
+            let Some(operator) = ThresholdOperator::from_name(&v) else {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_VALUE));
+            };
+            new_condition.operator = operator;
+        }
+
+        // the mount point only makes sense for disk related metrics
+        let cur_key = "mount_point";
+        if let Some(v) = cfg_string(cfgmap, cur_key)? {
+            if !metric.is_disk_metric() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_PARAMETER));
+            }
+            let mount_point = PathBuf::from(&v);
+            if !mount_point.is_dir() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_FILESPEC));
+            }
+            new_condition.mount_point = mount_point;
+        }
+
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "consecutive_checks", 1)? {
+            new_condition.consecutive_checks = v as u64;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "recur_after_failed_check")? {
+            new_condition.recur_after_failed_check = v;
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "check_after", 1)? {
+            new_condition.check_after = Some(Duration::from_secs(v as u64));
+        }
+
+        // start the condition if the configuration did not suspend it
+        if !new_condition.suspended {
+            new_condition.start();
+        }
+
+        Ok(new_condition)
+    }
+
+    /// Check a configuration map and return item name if Ok
+    ///
+    /// The check is performed exactly in the same way and in the same order
+    /// as in `load_cfgmap`, the only difference is that no actual item is
+    /// created and that a name is returned, which is the name of the item that
+    /// _would_ be created via the equivalent call to `load_cfgmap`
+    pub fn check_cfgmap(cfgmap: &CfgMap, available_tasks: &Vec<&str>) -> Result<String> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "tasks",
+            "recurring",
+            "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
+            "execute_sequence",
+            "break_on_failure",
+            "break_on_success",
+            "suspended",
+            "metric",
+            "operator",
+            "threshold",
+            "mount_point",
+            "consecutive_checks",
+            "recur_after_failed_check",
+            "check_after",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // common mandatory parameter check
+
+        // type and name are both mandatory: type is checked and name is kept
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "resource"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_COND_NAME))?.unwrap();
+
+        // specific mandatory parameter check
+        let cur_key = "metric";
+        let metric = cfg_mandatory!(cfg_string(cfgmap, cur_key))?.unwrap();
+        let Some(metric) = ResourceMetric::from_name(&metric) else {
+            return Err(cfg_err_invalid_config(cur_key, &metric, ERR_INVALID_VALUE));
+        };
+        cfg_mandatory!(cfg_threshold(cfgmap, "threshold"))?;
+
+        // also for optional parameters just check and throw away the result
+
+        // tags are always simply checked this way
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // check configuration task list against the provided ones
+        let cur_key = "tasks";
+        if let Some(v) = cfg_vec_string_check_regex(cfgmap, cur_key, &RE_TASK_NAME)? {
+            for s in v {
+                if !available_tasks.contains(&s.as_str()) {
+                    return Err(cfg_err_invalid_config(cur_key, &s, ERR_INVALID_TASK));
+                }
+            }
+        }
+
+        cfg_bool(cfgmap, "recurring")?;
+        cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)?;
+        cfg_retry_policy(cfgmap)?;
+        cfg_bool(cfgmap, "execute_sequence")?;
+        cfg_bool(cfgmap, "break_on_failure")?;
+        cfg_bool(cfgmap, "break_on_success")?;
+        cfg_bool(cfgmap, "suspended")?;
+
+        let cur_key = "operator";
+        if let Some(v) = cfg_string(cfgmap, cur_key)?
+            && ThresholdOperator::from_name(&v).is_none()
+        {
+            return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_VALUE));
+        }
+
+        let cur_key = "mount_point";
+        if let Some(v) = cfg_string(cfgmap, cur_key)? {
+            if !metric.is_disk_metric() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_PARAMETER));
+            }
+            if !PathBuf::from(&v).is_dir() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_FILESPEC));
+            }
+        }
+
+        cfg_int_check_above_eq(cfgmap, "consecutive_checks", 1)?;
+        cfg_bool(cfgmap, "recur_after_failed_check")?;
+        cfg_int_check_above_eq(cfgmap, "check_after", 1)?;
+
+        Ok(name)
+    }
+}
+
+impl Condition for ResourceCondition {
+    fn set_id(&mut self, id: i64) {
+        self.cond_id = id;
+    }
+    fn get_name(&self) -> String {
+        self.cond_name.clone()
+    }
+    fn get_id(&self) -> i64 {
+        self.cond_id
+    }
+    fn get_type(&self) -> &str {
+        "resource"
+    }
+
+    /// Return a hash of this item for comparison
+    fn _hash(&self) -> u64 {
+        let mut s = DefaultHasher::new();
+        self.hash(&mut s);
+        s.finish()
+    }
+
+    fn set_task_registry(&mut self, reg: &'static TaskRegistry) {
+        self.task_registry = Some(reg);
+    }
+
+    fn task_registry(&self) -> Option<&'static TaskRegistry> {
+        self.task_registry
+    }
+
+    fn suspended(&self) -> bool {
+        self.suspended
+    }
+    fn recurring(&self) -> bool {
+        self.recurring
+    }
+    fn has_succeeded(&self) -> bool {
+        self.has_succeeded
+    }
+
+    fn exec_sequence(&self) -> bool {
+        self.exec_sequence
+    }
+    fn break_on_success(&self) -> bool {
+        self.break_on_success
+    }
+    fn break_on_failure(&self) -> bool {
+        self.break_on_failure
+    }
+
+    fn last_checked(&self) -> Option<Instant> {
+        self.last_tested
+    }
+    fn last_succeeded(&self) -> Option<Instant> {
+        self.last_succeeded
+    }
+    fn startup_time(&self) -> Option<Instant> {
+        self.startup_time
+    }
+
+    fn set_checked(&mut self) {
+        self.last_tested = Some(Instant::now());
+    }
+
+    fn set_succeeded(&mut self) {
+        self.last_succeeded = self.last_tested;
+        self.has_succeeded = true;
+    }
+
+    fn reset_succeeded(&mut self) {
+        self.last_succeeded = None;
+        self.has_succeeded = false;
+    }
+
+    fn reset(&mut self) {
+        self.last_tested = None;
+        self.last_succeeded = None;
+        self.has_succeeded = false;
+        self.left_retries = self.max_retries + 1;
+        self.retry_wait = None;
+        self.tasks_failed = true;
+        self.crossed_count = 0;
+    }
+
+    fn restore_state(
+        &mut self,
+        has_succeeded: bool,
+        last_succeeded: Option<Instant>,
+        left_retries: Option<u64>,
+    ) {
+        self.has_succeeded = has_succeeded;
+        self.last_succeeded = last_succeeded;
+        if let Some(left_retries) = left_retries {
+            self.left_retries = left_retries as i64;
+        }
+    }
+
+    fn left_retries(&self) -> Option<u64> {
+        if self.max_retries < 0 {
+            None
+        } else {
+            Some(self.left_retries as u64)
+        }
+    }
+
+    fn set_retried(&mut self) {
+        if self.left_retries > 0 {
+            self.left_retries -= 1;
+        }
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    fn retry_wait(&self) -> Option<RetryWait> {
+        self.retry_wait
+    }
+
+    fn set_retry_wait(&mut self, wait: Option<RetryWait>) {
+        self.retry_wait = wait;
+    }
+
+    fn start(&mut self) {
+        self.suspended = false;
+        self.left_retries = self.max_retries + 1;
+        self.startup_time = Some(Instant::now());
+        self.crossed_count = 0;
+
+        // set the tasks_failed flag upon start: no task has been run now
+        // and this is equivalent to a failure; the flag was set to `false`
+        // upon creation in order to have a zero-initialization
+        self.tasks_failed = true;
+    }
+
+    fn suspend(&mut self) -> bool {
+        if self.suspended {
+            false
+        } else {
+            self.suspended = true;
+            true
+        }
+    }
+
+    fn resume(&mut self) -> bool {
+        if self.suspended {
+            self.suspended = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn task_names(&self) -> Vec<String> {
+        self.task_names.clone()
+    }
+
+    fn any_tasks_failed(&self) -> bool {
+        self.tasks_failed
+    }
+
+    fn set_tasks_failed(&mut self, failed: bool) {
+        self.tasks_failed = failed;
+    }
+
+    fn _add_task(&mut self, name: &str) -> bool {
+        let name = String::from(name);
+        if self.task_names.contains(&name) {
+            false
+        } else {
+            self.task_names.push(name);
+            true
+        }
+    }
+
+    fn _remove_task(&mut self, name: &str) -> bool {
+        let name = String::from(name);
+        if self.task_names.contains(&name) {
+            self.task_names
+                .remove(self.task_names.iter().position(|x| x == &name).unwrap());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Mandatory check function
+    ///
+    /// This function actually performs the test: the metric is read and
+    /// compared with the threshold, and the condition is verified when the
+    /// threshold has been crossed for enough consecutive checks.
+    fn _check_condition(&mut self) -> Result<Option<bool>> {
+        self.log(
+            LogType::Debug,
+            LOG_WHEN_START,
+            LOG_STATUS_MSG,
+            "checking system resource threshold based condition",
+        );
+        // if the minimum interval between checks has been set, obey it
+        // last_tested has already been set by trait to Instant::now()
+        let t = self.last_tested.unwrap();
+        if let Some(e) = self.check_after
+            && e > t - self.check_last
+        {
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_START,
+                LOG_STATUS_MSG,
+                "check explicitly delayed by configuration",
+            );
+            return Ok(Some(false));
+        }
+        self.check_last = t;
+
+        // a metric that cannot be read interrupts the sequence of checks
+        let value = match read_metric(self.metric, &self.mount_point) {
+            Ok(v) => v,
+            Err(e) => {
+                self.log(
+                    LogType::Warn,
+                    LOG_WHEN_PROC,
+                    LOG_STATUS_FAIL,
+                    &format!("could not read metric {} (reason: {e})", self.metric.name()),
+                );
+                self.crossed_count = 0;
+                self.last_check_failed = true;
+                return Ok(Some(false));
+            }
+        };
+
+        let crossed = self.operator.crossed(value, self.threshold);
+        if crossed {
+            self.crossed_count += 1;
+        } else {
+            self.crossed_count = 0;
+        }
+        self.log(
+            LogType::Debug,
+            LOG_WHEN_PROC,
+            LOG_STATUS_OK,
+            &format!(
+                "metric {} is {value:.2}: threshold ({} {}) {} ({}/{} consecutive checks)",
+                self.metric.name(),
+                self.operator.symbol(),
+                self.threshold,
+                if crossed { "CROSSED" } else { "NOT crossed" },
+                self.crossed_count.min(self.consecutive_checks),
+                self.consecutive_checks,
+            ),
+        );
+
+        // return true on success (not persistent unless allowed), false otherwise
+        if self.crossed_count >= self.consecutive_checks {
+            let succeeds = self.last_check_failed || !self.recur_after_failed_check;
+            self.last_check_failed = false;
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_END,
+                LOG_STATUS_OK,
+                "condition checked successfully",
+            );
+            if succeeds {
+                Ok(Some(true))
+            } else {
+                self.log(
+                    LogType::Debug,
+                    LOG_WHEN_END,
+                    LOG_STATUS_MSG,
+                    "persistent success status: waiting for failure to recur",
+                );
+                Ok(Some(false))
+            }
+        } else {
+            // a sequence of checks that is not complete yet is not a failure
+            if !crossed {
+                self.last_check_failed = true;
+            }
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_END,
+                LOG_STATUS_FAIL,
+                "condition checked unsuccessfully",
+            );
+            Ok(Some(false))
+        }
+    }
+}
+
+// end.
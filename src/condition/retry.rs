@@ -0,0 +1,136 @@
+//! Retry policies for conditions whose tasks failed
+//!
+//! Non recurring conditions can be configured to retry their tasks when some
+//! of them fail: by default the retry takes place at the very next tick, but
+//! a retry policy can be used to wait before retrying. The policy consists
+//! of an initial delay, a factor by which the delay is multiplied after each
+//! retry (1.0 for a fixed delay, greater for an exponential backoff), an
+//! optional maximum delay, and an optional _jitter_, that is a random amount
+//! of seconds added to each delay so that several conditions that failed at
+//! the same time do not retry all at once.
+
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use cfgmap::CfgMap;
+use rand::{Rng, rng};
+
+use crate::cfghelp::*;
+use crate::constants::*;
+use crate::utility::result::Result;
+
+/// The policy that determines the delay between task retries
+#[derive(Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    delay: u64,
+    factor: f64,
+    max_delay: Option<u64>,
+    jitter: u64,
+}
+
+/// A pending retry: the instant before which tasks should not be retried,
+/// and the delay (without jitter) that was used to compute it
+#[derive(Clone, Copy)]
+pub struct RetryWait {
+    pub until: Instant,
+    pub delay: Duration,
+}
+
+// the factor is hashed as its bit pattern, since floats are not hashable;
+// conditions only hash a policy that differs from the default one, so that
+// the hash (and thus the saved state) of existing conditions is unchanged
+impl Hash for RetryPolicy {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.delay.hash(state);
+        self.factor.to_bits().hash(state);
+        self.max_delay.hash(state);
+        self.jitter.hash(state);
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            delay: 0,
+            factor: 1.0,
+            max_delay: None,
+            jitter: 0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Return `true` if retries take place at the next tick
+    pub fn is_immediate(&self) -> bool {
+        self.delay == 0 && self.jitter == 0
+    }
+
+    /// Compute the next retry given the previous one, if any
+    ///
+    /// `None` is returned when no wait is needed before retrying. When no
+    /// maximum delay is set, a growing delay stops at `MAX_RETRY_DELAY_SECONDS`
+    /// so that it cannot overflow, however many retries are performed.
+    pub fn next(&self, previous: Option<RetryWait>) -> Option<RetryWait> {
+        if self.is_immediate() {
+            return None;
+        }
+        let max_delay = Duration::from_secs(
+            self.max_delay
+                .unwrap_or(MAX_RETRY_DELAY_SECONDS as u64)
+                .min(MAX_RETRY_DELAY_SECONDS as u64),
+        );
+        let delay = match previous {
+            Some(wait) => Duration::try_from_secs_f64(wait.delay.as_secs_f64() * self.factor)
+                .unwrap_or(max_delay),
+            None => Duration::from_secs(self.delay),
+        }
+        .min(max_delay);
+        let jitter = if self.jitter > 0 {
+            Duration::from_millis(rng().next_u64() % self.jitter.saturating_mul(1000))
+        } else {
+            Duration::ZERO
+        };
+        let now = Instant::now();
+        Some(RetryWait {
+            until: now
+                .checked_add(delay.saturating_add(jitter))
+                .unwrap_or(now + max_delay),
+            delay,
+        })
+    }
+}
+
+/// Retrieve the retry policy from a condition configuration
+///
+/// All the entries are optional, and the default policy is returned when
+/// none of them is specified.
+pub fn cfg_retry_policy(cfgmap: &CfgMap) -> Result<RetryPolicy> {
+    let mut policy = RetryPolicy::default();
+    if let Some(v) =
+        cfg_int_check_interval(cfgmap, "retry_delay_seconds", 0, MAX_RETRY_DELAY_SECONDS)?
+    {
+        policy.delay = v as u64;
+    }
+    if let Some(v) = cfg_float_check_above_eq(cfgmap, "retry_backoff_factor", 1.0)? {
+        policy.factor = v;
+    }
+    let cur_key = "retry_max_delay_seconds";
+    if let Some(v) = cfg_int_check_interval(cfgmap, cur_key, 0, MAX_RETRY_DELAY_SECONDS)? {
+        if (v as u64) < policy.delay {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                &v.to_string(),
+                ERR_INVALID_VALUE,
+            ));
+        }
+        policy.max_delay = Some(v as u64);
+    }
+    if let Some(v) =
+        cfg_int_check_interval(cfgmap, "retry_jitter_seconds", 0, MAX_RETRY_DELAY_SECONDS)?
+    {
+        policy.jitter = v as u64;
+    }
+    Ok(policy)
+}
+
+// end.
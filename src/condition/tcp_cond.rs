@@ -0,0 +1,737 @@
+//! Define a TCP port reachability based condition
+//!
+//! This type of `Condition` tries to open a TCP connection to a given port
+//! on a host at each check, and is verified when the connection succeeds,
+//! that is, when the port is open. The outcome can be inverted, so that the
+//! condition is verified when the port cannot be reached instead: this helps
+//! to react both to a service becoming available and to a service going down
+//! without spawning an external tool for each check. The connection is
+//! closed as soon as it has been established, and no data is exchanged.
+//!
+//! As for command based conditions, the check can be delayed in order to
+//! avoid probing the port at every tick, and the condition can be set to
+//! only succeed again after at least one failed check.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use cfgmap::CfgMap;
+
+use super::base::Condition;
+use super::retry::{RetryPolicy, RetryWait, cfg_retry_policy};
+use crate::utility::logging::{LogType, log};
+use crate::utility::result::Result;
+use crate::task::registry::TaskRegistry;
+use crate::{cfg_mandatory, constants::*};
+
+use crate::cfghelp::*;
+
+/// TCP Port Reachability Based Condition
+///
+/// This condition is verified when a TCP connection to the given host and
+/// port can be established or, if requested, when it cannot.
+pub struct TcpCondition {
+    // commom members
+    // parameters
+    cond_id: i64,
+    cond_name: String,
+    task_names: Vec<String>,
+    recurring: bool,
+    max_retries: i64,
+    retry_policy: RetryPolicy,
+    exec_sequence: bool,
+    break_on_failure: bool,
+    break_on_success: bool,
+    suspended: bool,
+
+    // internal values
+    has_succeeded: bool,
+    last_tested: Option<Instant>,
+    last_succeeded: Option<Instant>,
+    startup_time: Option<Instant>,
+    task_registry: Option<&'static TaskRegistry>,
+    left_retries: i64,
+    retry_wait: Option<RetryWait>,
+    tasks_failed: bool,
+
+    // specific members
+    // parameters
+    host: String,
+    port: u16,
+    expect_closed: bool,
+    recur_after_failed_check: bool,
+    check_after: Option<Duration>,
+    timeout: Duration,
+
+    // internal values
+    check_last: Instant,
+
+    // this is different from has_succeeded: the latter is set when the
+    // condition has actually been successful, which in this case may not
+    // be true, as a persistent success may not let the condition succeed
+    last_check_failed: bool,
+}
+
+// implement the hash protocol
+impl Hash for TcpCondition {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // common part
+        self.cond_name.hash(state);
+        self.recurring.hash(state);
+        self.max_retries.hash(state);
+        if self.retry_policy != RetryPolicy::default() {
+            self.retry_policy.hash(state);
+        }
+        self.exec_sequence.hash(state);
+        self.break_on_failure.hash(state);
+        self.break_on_success.hash(state);
+        // suspended is more a status: let's not consider it yet
+        // self.suspended.hash(state);
+        // task order is significant: hash on vec is not sorted
+        self.task_names.hash(state);
+
+        // specific part
+        self.host.hash(state);
+        self.port.hash(state);
+        self.expect_closed.hash(state);
+        self.recur_after_failed_check.hash(state);
+        self.check_after.hash(state);
+        self.timeout.hash(state);
+    }
+}
+
+#[allow(dead_code)]
+impl TcpCondition {
+    /// Create a new TCP port reachability based condition
+    ///
+    /// The `host` argument can either be a host name or an IP address.
+    pub fn new(name: &str, host: &str, port: u16) -> Self {
+        log(
+            LogType::Debug,
+            LOG_EMITTER_CONDITION_TCP,
+            LOG_ACTION_NEW,
+            Some((name, 0)),
+            LOG_WHEN_INIT,
+            LOG_STATUS_MSG,
+            &format!("CONDITION {name}: creating a new TCP port reachability based condition"),
+        );
+        let t = Instant::now();
+        TcpCondition {
+            // common members initialization
+            // reset ID
+            cond_id: 0,
+
+            // parameters
+            cond_name: String::from(name),
+            task_names: Vec::new(),
+            recurring: false,
+            max_retries: 0,
+            retry_policy: RetryPolicy::default(),
+            exec_sequence: true,
+            break_on_failure: false,
+            break_on_success: false,
+            suspended: true,
+
+            // internal values
+            startup_time: None,
+            last_tested: None,
+            last_succeeded: None,
+            has_succeeded: false,
+            task_registry: None,
+            left_retries: 0,
+            retry_wait: None,
+            tasks_failed: false,
+
+            // specific members initialization
+            // parameters
+            host: String::from(host),
+            port,
+            expect_closed: false,
+            recur_after_failed_check: false,
+            check_after: None,
+            timeout: Duration::from_secs(DEFAULT_TCP_CONNECT_TIMEOUT_SECONDS),
+
+            // internal values
+            check_last: t,
+            last_check_failed: true,
+        }
+    }
+
+    // build a short description of the probed endpoint, for logging purposes
+    fn endpoint(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+
+    // constructor modifiers
+    /// Set the command execution to sequence or parallel
+    pub fn execs_sequentially(mut self, yes: bool) -> Self {
+        self.exec_sequence = yes;
+        self
+    }
+
+    /// If true, *sequential* task execution will break on first success
+    pub fn breaks_on_success(mut self, yes: bool) -> Self {
+        self.break_on_success = yes;
+        self
+    }
+
+    /// If true, *sequential* task execution will break on first failure
+    pub fn breaks_on_failure(mut self, yes: bool) -> Self {
+        self.break_on_failure = yes;
+        self
+    }
+
+    /// If true, create a recurring condition
+    pub fn repeats(mut self, yes: bool) -> Self {
+        self.recurring = yes;
+        self
+    }
+
+    /// Retry `num` times on task failure if not recurring
+    pub fn retries(mut self, num: i64) -> Self {
+        assert!(num >= -1, "max number of retries must be positive or -1");
+        self.max_retries = num;
+        self
+    }
+
+    /// State that the first check and possible following tests are to be
+    /// performed after a certain amount of time
+    ///
+    /// A probe that waits for an unreachable host may last as long as the
+    /// connection timeout, thus it can be useful to perform it less often.
+    pub fn checks_after(mut self, delta: Duration) -> Self {
+        self.check_after = Some(delta);
+        self
+    }
+
+    /// Constructor modifier to succeed when the port is closed
+    ///
+    /// When set to `true` the condition is verified if no connection can be
+    /// established, instead of when the connection succeeds.
+    pub fn expects_closed(mut self, yes: bool) -> Self {
+        self.expect_closed = yes;
+        self
+    }
+
+    /// Set the maximum amount of time to wait for the connection
+    ///
+    /// Connections that are not established within this time are considered
+    /// failed, that is, the port is considered closed.
+    pub fn times_out_after(mut self, delta: Duration) -> Self {
+        self.timeout = delta;
+        self
+    }
+
+    /// Constructor modifier to recur only after failure
+    ///
+    /// Specifies that the condition is verified on check success only if
+    /// there has been at least one failure after the last successful test.
+    pub fn recurs_after_check_failure(mut self, yes: bool) -> Self {
+        self.recur_after_failed_check = yes;
+        self
+    }
+
+    /// Load a `TcpCondition` from a [`CfgMap`](https://docs.rs/cfgmap/latest/)
+    ///
+    /// The `TcpCondition` is initialized according to the values provided
+    /// in the `CfgMap` argument. If the `CfgMap` format does not comply with
+    /// the requirements of a `TcpCondition` an error is raised.
+    pub fn load_cfgmap(
+        cfgmap: &CfgMap,
+        task_registry: &'static TaskRegistry,
+    ) -> Result<TcpCondition> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "tasks",
+            "recurring",
+            "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
+            "execute_sequence",
+            "break_on_failure",
+            "break_on_success",
+            "suspended",
+            "host",
+            "port",
+            "expect_closed",
+            "recur_after_failed_check",
+            "check_after",
+            "timeout_seconds",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // type and name are both mandatory but type is only checked
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "tcp"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_COND_NAME))?.unwrap();
+
+        // specific mandatory parameter retrieval
+        let host =
+            cfg_mandatory!(cfg_string_check_regex(cfgmap, "host", &RE_HOST_ADDRESS))?.unwrap();
+        let port =
+            cfg_mandatory!(cfg_int_check_interval(cfgmap, "port", 1, u16::MAX as i64))?.unwrap();
+
+        // initialize the structure
+        let mut new_condition = TcpCondition::new(&name, &host, port as u16);
+        new_condition.task_registry = Some(task_registry);
+
+        // by default make condition active if loaded from configuration: if
+        // the configuration changes this state the condition will not start
+        new_condition.suspended = false;
+
+        // common optional parameter initialization
+
+        // tags are always simply checked this way as no value is needed
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // retrieve task list and try to directly add each task
+        let cur_key = "tasks";
+        if let Some(v) = cfg_vec_string_check_regex(cfgmap, cur_key, &RE_TASK_NAME)? {
+            for s in v {
+                if !new_condition.add_task(&s)? {
+                    return Err(cfg_err_invalid_config(cur_key, &s, ERR_INVALID_TASK));
+                }
+            }
+        }
+
+        if let Some(v) = cfg_bool(cfgmap, "recurring")? {
+            new_condition.recurring = v;
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)? {
+            new_condition.max_retries = v;
+        }
+        new_condition.retry_policy = cfg_retry_policy(cfgmap)?;
+        if let Some(v) = cfg_bool(cfgmap, "execute_sequence")? {
+            new_condition.exec_sequence = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "break_on_failure")? {
+            new_condition.break_on_failure = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "break_on_success")? {
+            new_condition.break_on_success = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "suspended")? {
+            new_condition.suspended = v;
+        }
+
+        // specific optional parameter initialization
+        if let Some(v) = cfg_bool(cfgmap, "expect_closed")? {
+            new_condition.expect_closed = v;
+        }
+        if let Some(v) = cfg_bool(cfgmap, "recur_after_failed_check")? {
+            new_condition.recur_after_failed_check = v;
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "check_after", 1)? {
+            new_condition.check_after = Some(Duration::from_secs(v as u64));
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "timeout_seconds", 1)? {
+            new_condition.timeout = Duration::from_secs(v as u64);
+        }
+
+        // start the condition if the configuration did not suspend it
+        if !new_condition.suspended {
+            new_condition.start();
+        }
+
+        Ok(new_condition)
+    }
+
+    /// Check a configuration map and return item name if Ok
+    ///
+    /// The check is performed exactly in the same way and in the same order
+    /// as in `load_cfgmap`, the only difference is that no actual item is
+    /// created and that a name is returned, which is the name of the item that
+    /// _would_ be created via the equivalent call to `load_cfgmap`
+    pub fn check_cfgmap(cfgmap: &CfgMap, available_tasks: &Vec<&str>) -> Result<String> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "tasks",
+            "recurring",
+            "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
+            "execute_sequence",
+            "break_on_failure",
+            "break_on_success",
+            "suspended",
+            "host",
+            "port",
+            "expect_closed",
+            "recur_after_failed_check",
+            "check_after",
+            "timeout_seconds",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // common mandatory parameter check
+
+        // type and name are both mandatory: type is checked and name is kept
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "tcp"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_COND_NAME))?.unwrap();
+
+        // specific mandatory parameter check
+        cfg_mandatory!(cfg_string_check_regex(cfgmap, "host", &RE_HOST_ADDRESS))?;
+        cfg_mandatory!(cfg_int_check_interval(cfgmap, "port", 1, u16::MAX as i64))?;
+
+        // also for optional parameters just check and throw away the result
+
+        // tags are always simply checked this way
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // check configuration task list against the provided ones
+        let cur_key = "tasks";
+        if let Some(v) = cfg_vec_string_check_regex(cfgmap, cur_key, &RE_TASK_NAME)? {
+            for s in v {
+                if !available_tasks.contains(&s.as_str()) {
+                    return Err(cfg_err_invalid_config(cur_key, &s, ERR_INVALID_TASK));
+                }
+            }
+        }
+
+        cfg_bool(cfgmap, "recurring")?;
+        cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)?;
+        cfg_retry_policy(cfgmap)?;
+        cfg_bool(cfgmap, "execute_sequence")?;
+        cfg_bool(cfgmap, "break_on_failure")?;
+        cfg_bool(cfgmap, "break_on_success")?;
+        cfg_bool(cfgmap, "suspended")?;
+
+        cfg_bool(cfgmap, "expect_closed")?;
+        cfg_bool(cfgmap, "recur_after_failed_check")?;
+        cfg_int_check_above_eq(cfgmap, "check_after", 1)?;
+        cfg_int_check_above_eq(cfgmap, "timeout_seconds", 1)?;
+
+        Ok(name)
+    }
+}
+
+impl Condition for TcpCondition {
+    fn set_id(&mut self, id: i64) {
+        self.cond_id = id;
+    }
+    fn get_name(&self) -> String {
+        self.cond_name.clone()
+    }
+    fn get_id(&self) -> i64 {
+        self.cond_id
+    }
+    fn get_type(&self) -> &str {
+        "tcp"
+    }
+
+    /// Return a hash of this item for comparison
+    fn _hash(&self) -> u64 {
+        let mut s = DefaultHasher::new();
+        self.hash(&mut s);
+        s.finish()
+    }
+
+    fn set_task_registry(&mut self, reg: &'static TaskRegistry) {
+        self.task_registry = Some(reg);
+    }
+
+    fn task_registry(&self) -> Option<&'static TaskRegistry> {
+        self.task_registry
+    }
+
+    fn suspended(&self) -> bool {
+        self.suspended
+    }
+    fn recurring(&self) -> bool {
+        self.recurring
+    }
+    fn has_succeeded(&self) -> bool {
+        self.has_succeeded
+    }
+
+    fn exec_sequence(&self) -> bool {
+        self.exec_sequence
+    }
+    fn break_on_success(&self) -> bool {
+        self.break_on_success
+    }
+    fn break_on_failure(&self) -> bool {
+        self.break_on_failure
+    }
+
+    fn last_checked(&self) -> Option<Instant> {
+        self.last_tested
+    }
+    fn last_succeeded(&self) -> Option<Instant> {
+        self.last_succeeded
+    }
+    fn startup_time(&self) -> Option<Instant> {
+        self.startup_time
+    }
+
+    fn set_checked(&mut self) {
+        self.last_tested = Some(Instant::now());
+    }
+
+    fn set_succeeded(&mut self) {
+        self.last_succeeded = self.last_tested;
+        self.has_succeeded = true;
+    }
+
+    fn reset_succeeded(&mut self) {
+        self.last_succeeded = None;
+        self.has_succeeded = false;
+    }
+
+    fn reset(&mut self) {
+        self.last_tested = None;
+        self.last_succeeded = None;
+        self.has_succeeded = false;
+        self.left_retries = self.max_retries + 1;
+        self.retry_wait = None;
+        self.tasks_failed = true;
+    }
+
+    fn restore_state(
+        &mut self,
+        has_succeeded: bool,
+        last_succeeded: Option<Instant>,
+        left_retries: Option<u64>,
+    ) {
+        self.has_succeeded = has_succeeded;
+        self.last_succeeded = last_succeeded;
+        if let Some(left_retries) = left_retries {
+            self.left_retries = left_retries as i64;
+        }
+    }
+
+    fn left_retries(&self) -> Option<u64> {
+        if self.max_retries < 0 {
+            None
+        } else {
+            Some(self.left_retries as u64)
+        }
+    }
+
+    fn set_retried(&mut self) {
+        if self.left_retries > 0 {
+            self.left_retries -= 1;
+        }
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    fn retry_wait(&self) -> Option<RetryWait> {
+        self.retry_wait
+    }
+
+    fn set_retry_wait(&mut self, wait: Option<RetryWait>) {
+        self.retry_wait = wait;
+    }
+
+    fn start(&mut self) {
+        self.suspended = false;
+        self.left_retries = self.max_retries + 1;
+        self.startup_time = Some(Instant::now());
+
+        // set the tasks_failed flag upon start: no task has been run now
+        // and this is equivalent to a failure; the flag was set to `false`
+        // upon creation in order to have a zero-initialization
+        self.tasks_failed = true;
+    }
+
+    fn suspend(&mut self) -> bool {
+        if self.suspended {
+            false
+        } else {
+            self.suspended = true;
+            true
+        }
+    }
+
+    fn resume(&mut self) -> bool {
+        if self.suspended {
+            self.suspended = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn task_names(&self) -> Vec<String> {
+        self.task_names.clone()
+    }
+
+    fn any_tasks_failed(&self) -> bool {
+        self.tasks_failed
+    }
+
+    fn set_tasks_failed(&mut self, failed: bool) {
+        self.tasks_failed = failed;
+    }
+
+    fn _add_task(&mut self, name: &str) -> bool {
+        let name = String::from(name);
+        if self.task_names.contains(&name) {
+            false
+        } else {
+            self.task_names.push(name);
+            true
+        }
+    }
+
+    fn _remove_task(&mut self, name: &str) -> bool {
+        let name = String::from(name);
+        if self.task_names.contains(&name) {
+            self.task_names
+                .remove(self.task_names.iter().position(|x| x == &name).unwrap());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Mandatory check function
+    ///
+    /// This function actually performs the test: a connection is attempted
+    /// to each of the addresses the host resolves to, and the port is
+    /// considered open as soon as one of the attempts succeeds.
+    fn _check_condition(&mut self) -> Result<Option<bool>> {
+        self.log(
+            LogType::Debug,
+            LOG_WHEN_START,
+            LOG_STATUS_MSG,
+            "checking TCP port reachability based condition",
+        );
+        // if the minimum interval between checks has been set, obey it
+        // last_tested has already been set by trait to Instant::now()
+        let t = self.last_tested.unwrap();
+        if let Some(e) = self.check_after
+            && e > t - self.check_last
+        {
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_START,
+                LOG_STATUS_MSG,
+                "check explicitly delayed by configuration",
+            );
+            return Ok(Some(false));
+        }
+
+        // now the time of the last check can be set to the actual time in
+        // order to allow further checks to comply with the request to be
+        // only run at certain intervals
+        self.check_last = t;
+
+        // a host name that cannot be resolved is an error and not a closed
+        // port, thus the check fails regardless of the expected outcome
+        let addresses: Vec<_> = match (self.host.as_str(), self.port).to_socket_addrs() {
+            Ok(a) => a.collect(),
+            Err(e) => {
+                self.log(
+                    LogType::Warn,
+                    LOG_WHEN_PROC,
+                    LOG_STATUS_FAIL,
+                    &format!("could not resolve host {} (reason: {e})", self.host),
+                );
+                self.last_check_failed = true;
+                return Ok(Some(false));
+            }
+        };
+
+        let startup_time = Instant::now();
+        let mut port_open = false;
+        for address in addresses.iter() {
+            match TcpStream::connect_timeout(address, self.timeout) {
+                Ok(_) => {
+                    port_open = true;
+                    break;
+                }
+                Err(e) => {
+                    self.log(
+                        LogType::Trace,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_MSG,
+                        &format!("could not connect to {address} (reason: {e})"),
+                    );
+                }
+            }
+        }
+        let duration = startup_time.elapsed();
+
+        self.log(
+            LogType::Debug,
+            LOG_WHEN_PROC,
+            LOG_STATUS_OK,
+            &format!(
+                "port {} found {} in {:.2}s",
+                self.endpoint(),
+                if port_open { "OPEN" } else { "CLOSED" },
+                duration.as_secs_f64(),
+            ),
+        );
+
+        // return true on success (not persistent unless allowed), false otherwise
+        if port_open != self.expect_closed {
+            let succeeds = self.last_check_failed || !self.recur_after_failed_check;
+            self.last_check_failed = false;
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_END,
+                LOG_STATUS_OK,
+                "condition checked successfully",
+            );
+            if succeeds {
+                Ok(Some(true))
+            } else {
+                self.log(
+                    LogType::Debug,
+                    LOG_WHEN_END,
+                    LOG_STATUS_MSG,
+                    "persistent success status: waiting for failure to recur",
+                );
+                Ok(Some(false))
+            }
+        } else {
+            self.last_check_failed = true;
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_END,
+                LOG_STATUS_FAIL,
+                "condition checked unsuccessfully",
+            );
+            Ok(Some(false))
+        }
+    }
+}
+
+// end.
@@ -17,14 +17,20 @@
 //! All values should be provided. If minute and second are not provided, they
 //! are both considered the beginning of the hour. All other values, if not
 //! provided, are considered to be always verified.
+//!
+//! Occurrences that have been missed, because the computer was suspended or
+//! the scheduler was not running, can be recovered using a catch-up policy.
 
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::time::Instant;
 
 use cfgmap::CfgMap;
+use chrono::TimeDelta;
 use chrono::prelude::*;
 
 use super::base::Condition;
+use super::retry::{RetryPolicy, RetryWait, cfg_retry_policy};
+use super::catch_up::{CatchUp, cfg_catch_up};
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::{Error, Kind, Result};
 use crate::task::registry::TaskRegistry;
@@ -162,6 +168,20 @@ impl TimeSpecification {
         Ok(dt.unwrap())
     }
 
+    /// Tell whether or not the provided local time is an exact occurrence
+    /// of this specification
+    pub fn matches(&self, t: &NaiveDateTime) -> bool {
+        self.year.is_none_or(|v| v == t.year())
+            && self.month.is_none_or(|v| v == t.month())
+            && self.day.is_none_or(|v| v == t.day())
+            && self.hour.is_none_or(|v| v == t.hour())
+            && self.minute.unwrap_or(0) == t.minute()
+            && self.second.unwrap_or(0) == t.second()
+            && self
+                .dow
+                .is_none_or(|v| v == t.weekday().number_from_sunday())
+    }
+
     pub fn as_str(&self) -> String {
         format!(
             "{}-{}-{}T{}:{}:{} [{}]",
@@ -239,6 +259,7 @@ pub struct TimeCondition {
     task_names: Vec<String>,
     recurring: bool,
     max_retries: i64,
+    retry_policy: RetryPolicy,
     exec_sequence: bool,
     break_on_failure: bool,
     break_on_success: bool,
@@ -251,12 +272,19 @@ pub struct TimeCondition {
     startup_time: Option<Instant>,
     task_registry: Option<&'static TaskRegistry>,
     left_retries: i64,
+    retry_wait: Option<RetryWait>,
     tasks_failed: bool,
 
     // specific members
     // parameters
     time_specifications: Vec<TimeSpecification>,
     tick_duration: i64,
+    catch_up: CatchUp,
+
+    // internal values
+    last_fired: Option<DateTime<Local>>,
+    catch_up_from: Option<DateTime<Local>>,
+    catch_up_pending: u64,
 }
 
 // implement the hash protocol
@@ -266,6 +294,9 @@ impl Hash for TimeCondition {
         self.cond_name.hash(state);
         self.recurring.hash(state);
         self.max_retries.hash(state);
+        if self.retry_policy != RetryPolicy::default() {
+            self.retry_policy.hash(state);
+        }
         self.exec_sequence.hash(state);
         self.break_on_failure.hash(state);
         self.break_on_success.hash(state);
@@ -279,6 +310,12 @@ impl Hash for TimeCondition {
 
         // time specifications support the hash protocol
         self.time_specifications.hash(state);
+
+        // only hash the catch-up policy when set, so that conditions that do
+        // not use it keep the same hash (and thus their saved state)
+        if self.catch_up != CatchUp::None {
+            self.catch_up.hash(state);
+        }
     }
 }
 
@@ -305,6 +342,7 @@ impl TimeCondition {
             task_names: Vec::new(),
             recurring: false,
             max_retries: 0,
+            retry_policy: RetryPolicy::default(),
             exec_sequence: true,
             break_on_failure: false,
             break_on_success: false,
@@ -317,12 +355,19 @@ impl TimeCondition {
             has_succeeded: false,
             task_registry: None,
             left_retries: 0,
+            retry_wait: None,
             tasks_failed: false,
 
             // specific members initialization
             // parameters
             time_specifications: Vec::new(),
             tick_duration: 0,
+            catch_up: CatchUp::None,
+
+            // internal values
+            last_fired: None,
+            catch_up_from: None,
+            catch_up_pending: 0,
         }
     }
 
@@ -358,6 +403,12 @@ impl TimeCondition {
         self
     }
 
+    /// Set the policy for occurrences missed while not running
+    pub fn catches_up(mut self, policy: CatchUp) -> Self {
+        self.catch_up = policy;
+        self
+    }
+
     /// Set tick duration after creation
     pub fn set_tick_duration(&mut self, seconds: u64) -> Result<bool> {
         if seconds < 1 || seconds > i64::MAX as u64 {
@@ -458,9 +509,15 @@ impl TimeCondition {
             "name",
             "tags",
             "time_specifications",
+            "catch_up",
+            "max_catch_up_runs",
             "tasks",
             "recurring",
             "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
             "execute_sequence",
             "break_on_failure",
             "break_on_success",
@@ -514,6 +571,7 @@ impl TimeCondition {
         if let Some(v) = cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)? {
             new_condition.max_retries = v;
         }
+        new_condition.retry_policy = cfg_retry_policy(cfgmap)?;
         if let Some(v) = cfg_bool(cfgmap, "execute_sequence")? {
             new_condition.exec_sequence = v;
         }
@@ -684,6 +742,10 @@ impl TimeCondition {
             ));
         }
 
+        if let Some(v) = cfg_catch_up(cfgmap)? {
+            new_condition.catch_up = v;
+        }
+
         // start the condition if the configuration did not suspend it
         if !new_condition.suspended {
             new_condition.start();
@@ -704,9 +766,15 @@ impl TimeCondition {
             "name",
             "tags",
             "time_specifications",
+            "catch_up",
+            "max_catch_up_runs",
             "tasks",
             "recurring",
             "max_tasks_retries",
+            "retry_delay_seconds",
+            "retry_backoff_factor",
+            "retry_max_delay_seconds",
+            "retry_jitter_seconds",
             "execute_sequence",
             "break_on_failure",
             "break_on_success",
@@ -747,6 +815,7 @@ impl TimeCondition {
 
         cfg_bool(cfgmap, "recurring")?;
         cfg_int_check_above_eq(cfgmap, "max_tasks_retries", -1)?;
+        cfg_retry_policy(cfgmap)?;
         cfg_bool(cfgmap, "execute_sequence")?;
         cfg_bool(cfgmap, "break_on_failure")?;
         cfg_bool(cfgmap, "break_on_success")?;
@@ -874,8 +943,41 @@ impl TimeCondition {
             ));
         }
 
+        cfg_catch_up(cfgmap)?;
+
         Ok(name)
     }
+
+    // count the occurrences that fell within the provided interval (start
+    // excluded), stopping as soon as the limit set by the catch-up policy is
+    // reached: all specifications have a fixed minute and second, therefore
+    // it is sufficient to check a single candidate per hour for each of them
+    fn missed_occurrences(&self, from: DateTime<Local>, to: DateTime<Local>) -> u64 {
+        let limit = self.catch_up.limit();
+        let from = from.naive_local();
+        let to = to.naive_local();
+        let mut missed = 0;
+        let Some(mut hour) = from.date().and_hms_opt(from.hour(), 0, 0) else {
+            return 0;
+        };
+        while hour <= to && limit.is_none_or(|n| missed < n) {
+            let mut found: Vec<NaiveDateTime> = self
+                .time_specifications
+                .iter()
+                .filter_map(|tspec| {
+                    let t = hour
+                        + TimeDelta::minutes(tspec.minute.unwrap_or(0) as i64)
+                        + TimeDelta::seconds(tspec.second.unwrap_or(0) as i64);
+                    (from < t && t <= to && tspec.matches(&t)).then_some(t)
+                })
+                .collect();
+            found.sort();
+            found.dedup();
+            missed += found.len() as u64;
+            hour += TimeDelta::hours(1);
+        }
+        limit.map_or(missed, |n| missed.min(n))
+    }
 }
 
 impl Condition for TimeCondition {
@@ -944,6 +1046,7 @@ impl Condition for TimeCondition {
     fn set_succeeded(&mut self) {
         self.last_succeeded = self.last_tested;
         self.has_succeeded = true;
+        self.last_fired = Some(Local::now());
     }
 
     fn reset_succeeded(&mut self) {
@@ -956,7 +1059,35 @@ impl Condition for TimeCondition {
         self.last_succeeded = None;
         self.has_succeeded = false;
         self.left_retries = self.max_retries + 1;
+        self.retry_wait = None;
         self.tasks_failed = true;
+        self.catch_up_pending = 0;
+    }
+
+    fn restore_state(
+        &mut self,
+        has_succeeded: bool,
+        last_succeeded: Option<Instant>,
+        left_retries: Option<u64>,
+    ) {
+        self.has_succeeded = has_succeeded;
+        self.last_succeeded = last_succeeded;
+        if let Some(left_retries) = left_retries {
+            self.left_retries = left_retries as i64;
+        }
+    }
+
+    fn last_fired(&self) -> Option<DateTime<Local>> {
+        if self.catch_up == CatchUp::None {
+            None
+        } else {
+            self.last_fired
+        }
+    }
+
+    fn restore_last_fired(&mut self, when: DateTime<Local>) {
+        self.last_fired = Some(when);
+        self.catch_up_from = Some(when);
     }
 
     fn left_retries(&self) -> Option<u64> {
@@ -973,10 +1104,23 @@ impl Condition for TimeCondition {
         }
     }
 
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    fn retry_wait(&self) -> Option<RetryWait> {
+        self.retry_wait
+    }
+
+    fn set_retry_wait(&mut self, wait: Option<RetryWait>) {
+        self.retry_wait = wait;
+    }
+
     fn start(&mut self) {
         self.suspended = false;
         self.left_retries = self.max_retries + 1;
         self.startup_time = Some(Instant::now());
+        self.catch_up_from = Some(Local::now());
 
         // set the tasks_failed flag upon start: no task has been run now
         // and this is equivalent to a failure; the flag was set to `false`
@@ -995,7 +1139,9 @@ impl Condition for TimeCondition {
 
     fn resume(&mut self) -> bool {
         if self.suspended {
+            // occurrences missed while suspended are not recovered
             self.suspended = false;
+            self.catch_up_from = Some(Local::now());
             true
         } else {
             false
@@ -1057,6 +1203,31 @@ impl Condition for TimeCondition {
             ),
         );
 
+        // first look for occurrences missed since the last check, excluding
+        // the ones that belong to the current tick, which is checked below
+        if self.catch_up != CatchUp::None {
+            let window_start = dt - TimeDelta::seconds(self.tick_duration);
+            if let Some(from) = self.catch_up_from
+                && from < window_start
+            {
+                let missed = self.missed_occurrences(from, window_start);
+                if missed > 0 {
+                    self.log(
+                        LogType::Info,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_MSG,
+                        &format!(
+                            "found {missed} missed occurrence(s) since {}",
+                            from.format("%Y-%m-%dT%H:%M:%S"),
+                        ),
+                    );
+                    self.catch_up_pending = self.catch_up.runs(self.catch_up_pending + missed);
+                }
+            }
+            self.catch_up_from = Some(dt);
+        }
+
+        let mut outcome = false;
         for tspec in self.time_specifications.iter() {
             let test_tspec = tspec.as_datetime(dt)?;
             self.log(
@@ -1074,17 +1245,31 @@ impl Condition for TimeCondition {
             let span = (dt - test_tspec).num_microseconds().unwrap();
             if span >= 0 && span < self.tick_duration * 1_000_000 {
                 if let Some(dow) = tspec.dow {
-                    if dow == dt.weekday().number_from_sunday() {
-                        return Ok(Some(true));
-                    } else {
-                        return Ok(Some(false));
-                    }
+                    outcome = dow == dt.weekday().number_from_sunday();
                 } else {
-                    return Ok(Some(true));
+                    outcome = true;
                 }
+                break;
             }
         }
-        Ok(Some(false))
+
+        // missed occurrences are only run when the current tick is not an
+        // occurrence itself, so that they are never merged with it
+        if !outcome && self.catch_up_pending > 0 {
+            self.catch_up_pending -= 1;
+            self.log(
+                LogType::Info,
+                LOG_WHEN_PROC,
+                LOG_STATUS_MSG,
+                &format!(
+                    "catching up with a missed occurrence ({} more pending)",
+                    self.catch_up_pending,
+                ),
+            );
+            outcome = true;
+        }
+
+        Ok(Some(outcome))
     }
 }
 
@@ -3,7 +3,9 @@
 //! module to load the application configuration from a TOML file
 
 use cfgmap::{CfgMap, CfgValue};
+use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
 
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::{Error, Kind, Result};
@@ -24,14 +26,500 @@ use crate::task::base::Task;
 
 use crate::cfghelp::*;
 
-/// Check the configuration from a string
-pub fn check_configuration(config_file: &str) -> Result<()> {
-    let config_map: CfgMap = match toml::from_str(fs::read_to_string(config_file)?.as_str()) {
-        Ok(toml_text) => CfgMap::from_toml(toml_text),
-        _ => {
-            return Err(Error::new(Kind::Invalid, ERR_INVALID_CONFIG_FILE));
+// the sections that can be split across the main and the included files
+const MERGEABLE_SECTIONS: [&str; 3] = ["task", "condition", "event"];
+
+// parse a single TOML file into a configuration map
+fn read_config_file(config_file: &Path) -> Result<CfgMap> {
+    match toml::from_str(fs::read_to_string(config_file)?.as_str()) {
+        Ok(toml_text) => Ok(CfgMap::from_toml(toml_text)),
+        _ => Err(Error::new(
+            Kind::Invalid,
+            &format!("{ERR_INVALID_CONFIG_FILE}: `{}`", config_file.display()),
+        )),
+    }
+}
+
+// retrieve a list of strings that can also be given as a single string
+fn cfg_string_or_list(cfgmap: &CfgMap, key: &str) -> Result<Vec<String>> {
+    let mut res = Vec::new();
+    if let Some(item) = cfgmap.get(key) {
+        if let Some(s) = item.as_str() {
+            res.push(s.clone());
+        } else if let Some(list) = item.as_list() {
+            for v in list {
+                let Some(s) = v.as_str() else {
+                    return Err(cfg_err_invalid_config(
+                        key,
+                        STR_INVALID_VALUE,
+                        ERR_INVALID_PARAMETER,
+                    ));
+                };
+                res.push(s.clone());
+            }
+        } else {
+            return Err(cfg_err_invalid_config(
+                key,
+                STR_INVALID_TYPE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+    }
+    Ok(res)
+}
+
+// build the list of files to be included, in the order in which they have to
+// be merged: first the ones given in `include`, where each glob pattern is
+// expanded in alphabetical order, then the `*.toml` files in the directories
+// given in `include_dir`, again in alphabetical order; relative paths are
+// relative to the directory of the main file
+fn included_files(cfgmap: &CfgMap, base_dir: &Path) -> Result<Vec<PathBuf>> {
+    fn _expand(pattern: &Path) -> Result<Vec<PathBuf>> {
+        let pattern = pattern.to_string_lossy();
+        let Ok(paths) = glob::glob(&pattern) else {
+            return Err(cfg_err_invalid_config(
+                "include",
+                &pattern,
+                ERR_INVALID_PARAMETER,
+            ));
+        };
+        let mut res: Vec<PathBuf> = paths.filter_map(|p| p.ok()).collect();
+        res.sort();
+        Ok(res)
+    }
+
+    let mut res = Vec::new();
+    for pattern in cfg_string_or_list(cfgmap, "include")? {
+        let pattern = base_dir.join(pattern);
+        let found = _expand(&pattern)?;
+        // a plain file name must exist, while a pattern may match nothing
+        if found.is_empty() && !pattern.to_string_lossy().contains(['*', '?', '[']) {
+            return Err(Error::new(
+                Kind::Invalid,
+                &format!("{ERR_INCLUDE_NOT_FOUND}: `{}`", pattern.display()),
+            ));
+        }
+        res.extend(found);
+    }
+    for dir in cfg_string_or_list(cfgmap, "include_dir")? {
+        let dir = base_dir.join(dir);
+        if !dir.is_dir() {
+            return Err(Error::new(
+                Kind::Invalid,
+                &format!("{ERR_INCLUDE_NOT_FOUND}: `{}`", dir.display()),
+            ));
+        }
+        res.extend(_expand(&dir.join("*.toml"))?);
+    }
+    Ok(res)
+}
+
+// record the names of the items of a section found in a file, failing if any
+// of them has already been found, possibly in another file
+fn register_item_names(
+    items: &[CfgValue],
+    section: &str,
+    config_file: &Path,
+    names: &mut HashMap<String, PathBuf>,
+) -> Result<()> {
+    for item in items {
+        if let Some(name) = item
+            .as_map()
+            .and_then(|x| x.get("name"))
+            .and_then(|x| x.as_str())
+        {
+            let key = format!("{section}/{name}");
+            if let Some(other) = names.get(&key) {
+                return Err(Error::new(
+                    Kind::Invalid,
+                    &format!(
+                        "{ERR_DUPLICATE_ITEM_NAME}: {section} `{name}` in `{}` (already defined in `{}`)",
+                        config_file.display(),
+                        other.display(),
+                    ),
+                ));
+            }
+            names.insert(key, config_file.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+// collect the templates defined in a file, failing if any of them has
+// already been defined, possibly in another file: templates are tables whose
+// entries are copied into the items that extend them, and must not specify
+// a name, which is always required to be specific to the item
+fn collect_templates(
+    cfgmap: &mut CfgMap,
+    config_file: &Path,
+    templates: &mut HashMap<String, (CfgMap, PathBuf)>,
+) -> Result<()> {
+    let Some(section) = cfgmap.remove("template") else {
+        return Ok(());
+    };
+    let CfgValue::Map(section) = section else {
+        return Err(cfg_err_invalid_config(
+            "template",
+            STR_INVALID_TYPE,
+            ERR_INVALID_TEMPLATE,
+        ));
+    };
+    for (name, template) in section.iter() {
+        let Some(template) = template.as_map() else {
+            return Err(cfg_err_invalid_config(
+                "template",
+                name,
+                ERR_INVALID_TEMPLATE,
+            ));
+        };
+        if template.contains_key("name") {
+            return Err(cfg_err_invalid_config(
+                "template",
+                name,
+                &format!("{ERR_INVALID_TEMPLATE} ({ERR_INVALID_CFG_ENTRY}: name)"),
+            ));
+        }
+        if let Some((_, other)) = templates.get(name) {
+            return Err(Error::new(
+                Kind::Invalid,
+                &format!(
+                    "{ERR_DUPLICATE_ITEM_NAME}: template `{name}` in `{}` (already defined in `{}`)",
+                    config_file.display(),
+                    other.display(),
+                ),
+            ));
+        }
+        templates.insert(name.clone(), (template.clone(), config_file.to_path_buf()));
+    }
+    Ok(())
+}
+
+// build the full set of entries provided by a template, including the ones
+// inherited from the templates it extends in turn: the names of templates
+// being resolved are kept in `chain` to detect circular references
+fn resolve_template(
+    name: &str,
+    templates: &HashMap<String, (CfgMap, PathBuf)>,
+    chain: &mut Vec<String>,
+) -> Result<CfgMap> {
+    if chain.iter().any(|x| x == name) {
+        return Err(cfg_err_invalid_config(
+            "extends",
+            name,
+            ERR_TEMPLATE_CIRCULAR,
+        ));
+    }
+    let Some((template, _)) = templates.get(name) else {
+        return Err(cfg_err_invalid_config(
+            "extends",
+            name,
+            ERR_TEMPLATE_NOT_FOUND,
+        ));
+    };
+
+    chain.push(String::from(name));
+    let mut res = match template.get("extends") {
+        Some(CfgValue::Str(parent)) => resolve_template(parent, templates, chain)?,
+        Some(_) => {
+            return Err(cfg_err_invalid_config(
+                "extends",
+                STR_INVALID_TYPE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+        None => CfgMap::new(),
+    };
+    chain.pop();
+
+    for (key, value) in template.iter() {
+        if key != "extends" {
+            res.insert(key.clone(), value.clone());
+        }
+    }
+    Ok(res)
+}
+
+// replace the `extends` entry of each item with the entries of the template,
+// except for the ones that are explicitly specified by the item itself
+fn apply_templates(
+    cfgmap: &mut CfgMap,
+    templates: &HashMap<String, (CfgMap, PathBuf)>,
+) -> Result<()> {
+    for section in MERGEABLE_SECTIONS {
+        let Some(items) = cfgmap.get_mut(section).and_then(|x| x.as_list_mut()) else {
+            continue;
+        };
+        for item in items.iter_mut() {
+            let Some(item) = item.as_map_mut() else {
+                continue;
+            };
+            let Some(extends) = item.remove("extends") else {
+                continue;
+            };
+            let CfgValue::Str(name) = extends else {
+                return Err(cfg_err_invalid_config(
+                    "extends",
+                    STR_INVALID_TYPE,
+                    ERR_INVALID_PARAMETER,
+                ));
+            };
+            let template = resolve_template(&name, templates, &mut Vec::new())?;
+            for (key, value) in template.iter() {
+                if !item.contains_key(key) {
+                    item.insert(key.clone(), value.clone());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// replace the references in a string with the value of an environment
+// variable (`${env:NAME}`), the contents of a file without the trailing line
+// terminator (`${file:PATH}`) or the value of a configuration variable
+// (`${var:NAME}`): relative paths are relative to the directory of the main
+// configuration file, and `$${` is used to leave a literal `${` in place
+fn interpolate_str(
+    value: &str,
+    variables: &HashMap<String, String>,
+    base_dir: &Path,
+) -> Result<String> {
+    let mut res = String::new();
+    let mut last = 0;
+    for caps in RE_CFG_REFERENCE.captures_iter(value) {
+        let whole = caps.get(0).unwrap();
+        res.push_str(&value[last..whole.start()]);
+        last = whole.end();
+        if whole.as_str().starts_with("$$") {
+            res.push_str(&whole.as_str()[1..]);
+            continue;
+        }
+        let name = &caps[2];
+        match &caps[1] {
+            "env" => match std::env::var(name) {
+                Ok(v) => res.push_str(&v),
+                Err(_) => {
+                    return Err(Error::new(
+                        Kind::Invalid,
+                        &format!("{ERR_UNDEFINED_ENVVAR}: `{name}`"),
+                    ));
+                }
+            },
+            "file" => match fs::read_to_string(base_dir.join(name)) {
+                Ok(v) => res.push_str(v.trim_end_matches(['\n', '\r'])),
+                Err(e) => {
+                    return Err(Error::new(
+                        Kind::Invalid,
+                        &format!("{ERR_UNREADABLE_FILE}: `{name}` ({e})"),
+                    ));
+                }
+            },
+            _ => match variables.get(name) {
+                Some(v) => res.push_str(v),
+                None => {
+                    return Err(Error::new(
+                        Kind::Invalid,
+                        &format!("{ERR_UNDEFINED_VARIABLE}: `{name}`"),
+                    ));
+                }
+            },
+        }
+    }
+    res.push_str(&value[last..]);
+    Ok(res)
+}
+
+// apply interpolation to all the strings found in a value, recursively: the
+// `tags` tables are left alone, as they are reserved to wrappers
+fn interpolate_value(
+    value: &mut CfgValue,
+    variables: &HashMap<String, String>,
+    base_dir: &Path,
+) -> Result<()> {
+    match value {
+        CfgValue::Str(s) => *s = interpolate_str(s, variables, base_dir)?,
+        CfgValue::List(items) => {
+            for item in items.iter_mut() {
+                interpolate_value(item, variables, base_dir)?;
+            }
+        }
+        CfgValue::Map(map) => {
+            for (key, item) in map.iter_mut() {
+                if key != "tags" {
+                    interpolate_value(item, variables, base_dir)?;
+                }
+            }
         }
+        _ => (),
+    }
+    Ok(())
+}
+
+// extract the configuration variables: they must be strings, which can in
+// turn refer to environment variables and files but not to other variables
+fn collect_variables(cfgmap: &mut CfgMap, base_dir: &Path) -> Result<HashMap<String, String>> {
+    let mut res = HashMap::new();
+    let Some(section) = cfgmap.remove("variables") else {
+        return Ok(res);
     };
+    let CfgValue::Map(section) = section else {
+        return Err(cfg_err_invalid_config(
+            "variables",
+            STR_INVALID_TYPE,
+            ERR_INVALID_VARIABLES,
+        ));
+    };
+    for (name, value) in section.iter() {
+        let Some(value) = value.as_str() else {
+            return Err(cfg_err_invalid_config(
+                "variables",
+                name,
+                ERR_INVALID_VARIABLES,
+            ));
+        };
+        if !RE_VAR_NAME.is_match(name) {
+            return Err(cfg_err_invalid_config(
+                "variables",
+                name,
+                ERR_INVALID_VARIABLES,
+            ));
+        }
+        let value = interpolate_str(value, &HashMap::new(), base_dir)?;
+        res.insert(name.clone(), value);
+    }
+    Ok(res)
+}
+
+// read the configuration file and merge the included files into it: these
+// can be specified using file names or glob patterns in `include`, and using
+// directories in `include_dir` (where only `*.toml` files are considered);
+// included files can only define tasks, conditions and events, which are
+// appended to the ones found in the main file, and an item with the same
+// type and name as one that has already been found causes an error; finally
+// the templates, that can be defined in any of the files, are applied
+fn merge_configuration(config_file: &str) -> Result<CfgMap> {
+    let config_file = Path::new(config_file);
+    let mut config_map = read_config_file(config_file)?;
+    let base_dir = config_file.parent().unwrap_or(Path::new(""));
+    let includes = included_files(&config_map, base_dir)?;
+    config_map.remove("include");
+    config_map.remove("include_dir");
+
+    let mut templates: HashMap<String, (CfgMap, PathBuf)> = HashMap::new();
+    collect_templates(&mut config_map, config_file, &mut templates)?;
+
+    let mut names: HashMap<String, PathBuf> = HashMap::new();
+    for section in MERGEABLE_SECTIONS {
+        if let Some(items) = config_map.get(section).and_then(|x| x.as_list()) {
+            register_item_names(items, section, config_file, &mut names)?;
+        }
+    }
+
+    for include in includes {
+        let mut included_map = read_config_file(&include)?;
+        collect_templates(&mut included_map, &include, &mut templates)?;
+        for key in included_map.keys() {
+            if !MERGEABLE_SECTIONS.contains(&key.as_str()) {
+                return Err(Error::new(
+                    Kind::Invalid,
+                    &format!(
+                        "{ERR_INVALID_INCLUDE}: `{}` ({ERR_INVALID_CFG_ENTRY}: {key})",
+                        include.display(),
+                    ),
+                ));
+            }
+        }
+        for section in MERGEABLE_SECTIONS {
+            let Some(items) = included_map.remove(section) else {
+                continue;
+            };
+            let CfgValue::List(items) = items else {
+                return Err(Error::new(
+                    Kind::Invalid,
+                    &format!(
+                        "{ERR_INVALID_INCLUDE}: `{}` ({ERR_INVALID_CFG_ENTRY}: {section})",
+                        include.display(),
+                    ),
+                ));
+            };
+            register_item_names(&items, section, &include, &mut names)?;
+            if let Some(existing) = config_map.get_mut(section).and_then(|x| x.as_list_mut()) {
+                existing.extend(items);
+            } else if config_map.get(section).is_none() {
+                let _ = config_map.add(section, CfgValue::List(items));
+            }
+        }
+    }
+
+    apply_templates(&mut config_map, &templates)?;
+
+    Ok(config_map)
+}
+
+// read the configuration as above, and replace the references in string
+// values, see `interpolate_str()`
+fn read_configuration(config_file: &str) -> Result<CfgMap> {
+    let mut config_map = merge_configuration(config_file)?;
+    let base_dir = Path::new(config_file).parent().unwrap_or(Path::new(""));
+    let variables = collect_variables(&mut config_map, base_dir)?;
+    for (key, value) in config_map.iter_mut() {
+        if key != "tags" {
+            interpolate_value(value, &variables, base_dir)?;
+        }
+    }
+
+    Ok(config_map)
+}
+
+/// Read the configuration for introspection purposes
+///
+/// Included files and templates are handled as when configuring items, but
+/// the references in string values are left as they are, so that the values
+/// they refer to (which may be secrets) are never reported.
+pub fn read_uninterpolated_configuration(config_file: &str) -> Result<CfgMap> {
+    let mut config_map = merge_configuration(config_file)?;
+    config_map.remove("variables");
+    Ok(config_map)
+}
+
+/// Return the files that make up a configuration and the directories where
+/// they are looked for
+///
+/// The files are the main configuration file followed by the included ones,
+/// resolved exactly as `read_configuration()` does, while the directories
+/// are the ones where included files may appear, including the directory of
+/// the main configuration file itself.
+pub fn configuration_files(config_file: &str) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let config_file = Path::new(config_file);
+    let config_map = read_config_file(config_file)?;
+    let base_dir = config_file.parent().unwrap_or(Path::new(""));
+
+    let mut files = vec![config_file.to_path_buf()];
+    files.extend(included_files(&config_map, base_dir)?);
+
+    let mut dirs = vec![base_dir.to_path_buf()];
+    for pattern in cfg_string_or_list(&config_map, "include")? {
+        if let Some(dir) = base_dir.join(pattern).parent() {
+            dirs.push(dir.to_path_buf());
+        }
+    }
+    for dir in cfg_string_or_list(&config_map, "include_dir")? {
+        dirs.push(base_dir.join(dir));
+    }
+    for dir in dirs.iter_mut() {
+        if dir.as_os_str().is_empty() {
+            *dir = PathBuf::from(".");
+        }
+    }
+    dirs.sort();
+    dirs.dedup();
+
+    Ok((files, dirs))
+}
+
+/// Check the configuration from a string
+pub fn check_configuration(config_file: &str) -> Result<()> {
+    let config_map = read_configuration(config_file)?;
 
     // check that the first level keys are only the admitted ones
     let check = vec![
@@ -40,6 +528,7 @@ pub fn check_configuration(config_file: &str) -> Result<()> {
         "event",
         "scheduler_tick_seconds",
         "randomize_checks_within_ticks",
+        "state_file",
         "tags",
     ];
     cfg_check_keys(&config_map, &check)?;
@@ -60,9 +549,11 @@ pub fn check_configuration(config_file: &str) -> Result<()> {
     // globals
     cfg_int_check_above_eq(&config_map, "scheduler_tick_seconds", 1)?;
     cfg_bool(&config_map, "randomize_checks_within_ticks")?;
+    cfg_string(&config_map, "state_file")?;
 
     // check tasks and build a list of names to check conditions against
     let mut task_list: Vec<String> = Vec::new();
+    let mut task_graph: HashMap<String, Vec<String>> = HashMap::new();
     if let Some(item_map) = config_map.get("task") {
         if !item_map.is_list() {
             return Err(Error::new(Kind::Invalid, ERR_INVALID_TASK_CONFIG));
@@ -80,11 +571,15 @@ pub fn check_configuration(config_file: &str) -> Result<()> {
                         "internal" => task::internal_task::InternalTask::check_cfgmap(
                             entry.as_map().unwrap(),
                         )?,
+                        #[cfg(feature = "http")]
+                        "http" => task::http_task::HttpTask::check_cfgmap(entry.as_map().unwrap())?,
                         // ...
                         _ => {
                             return Err(Error::new(Kind::Invalid, ERR_INVALID_TASK_TYPE));
                         }
                     };
+                    let deps = task::graph::cfg_task_dependencies(entry.as_map().unwrap())?;
+                    task_graph.insert(name.clone(), deps.upstream());
                     task_list.push(name);
                 } else {
                     return Err(Error::new(Kind::Invalid, ERR_INVALID_TASK_CONFIG));
@@ -93,6 +588,29 @@ pub fn check_configuration(config_file: &str) -> Result<()> {
         }
     }
 
+    // tasks may depend on tasks that are defined later, so the dependency
+    // graph can only be verified after all of them have been checked
+    task::graph::check_task_graph(&task_graph)?;
+
+    // composite conditions can refer to conditions that are defined later in
+    // the configuration, thus the names of possible inputs are collected first
+    let mut input_list: Vec<String> = Vec::new();
+    if let Some(item_map) = config_map.get("condition")
+        && item_map.is_list()
+    {
+        for entry in item_map.as_list().unwrap() {
+            if let Some(entry) = entry.as_map()
+                && let Some(name) = entry.get("name").and_then(|x| x.as_str())
+                && entry
+                    .get("type")
+                    .and_then(|x| x.as_str())
+                    .is_some_and(|x| x != "composite")
+            {
+                input_list.push(name.clone());
+            }
+        }
+    }
+
     // check conditions and build a list of names to check events against
     let mut condition_list: Vec<String> = Vec::new();
     if let Some(task_map) = config_map.get("condition") {
@@ -118,6 +636,10 @@ pub fn check_configuration(config_file: &str) -> Result<()> {
                             entry.as_map().unwrap(),
                             &task_list,
                         )?,
+                        "cron" => condition::cron_cond::CronCondition::check_cfgmap(
+                            entry.as_map().unwrap(),
+                            &task_list,
+                        )?,
                         "command" => condition::command_cond::CommandCondition::check_cfgmap(
                             entry.as_map().unwrap(),
                             &task_list,
@@ -126,11 +648,36 @@ pub fn check_configuration(config_file: &str) -> Result<()> {
                             entry.as_map().unwrap(),
                             &task_list,
                         )?,
+                        "tcp" => condition::tcp_cond::TcpCondition::check_cfgmap(
+                            entry.as_map().unwrap(),
+                            &task_list,
+                        )?,
+                        "file" => condition::file_cond::FileCondition::check_cfgmap(
+                            entry.as_map().unwrap(),
+                            &task_list,
+                        )?,
+                        #[cfg(target_os = "linux")]
+                        "resource" => condition::resource_cond::ResourceCondition::check_cfgmap(
+                            entry.as_map().unwrap(),
+                            &task_list,
+                        )?,
+                        "composite" => {
+                            condition::composite_cond::CompositeCondition::check_cfgmap(
+                                entry.as_map().unwrap(),
+                                &task_list,
+                                &input_list.iter().map(|x| x.as_str()).collect(),
+                            )?
+                        }
                         #[cfg(feature = "dbus")]
                         "dbus" => condition::dbus_cond::DbusMethodCondition::check_cfgmap(
                             entry.as_map().unwrap(),
                             &task_list,
                         )?,
+                        #[cfg(feature = "http")]
+                        "http" => condition::http_cond::HttpCondition::check_cfgmap(
+                            entry.as_map().unwrap(),
+                            &task_list,
+                        )?,
                         #[cfg(windows)]
                         #[cfg(feature = "wmi")]
                         "wmi" => condition::wmi_cond::WmiQueryCondition::check_cfgmap(
@@ -173,6 +720,15 @@ pub fn check_configuration(config_file: &str) -> Result<()> {
                             entry.as_map().unwrap(),
                             &condition_list,
                         )?,
+                        "logtail" => event::logtail_event::LogTailEvent::check_cfgmap(
+                            entry.as_map().unwrap(),
+                            &condition_list,
+                        )?,
+                        #[cfg(unix)]
+                        "signal" => event::signal_event::SignalEvent::check_cfgmap(
+                            entry.as_map().unwrap(),
+                            &condition_list,
+                        )?,
                         #[cfg(feature = "dbus")]
                         "dbus" => event::dbus_event::DbusMessageEvent::check_cfgmap(
                             entry.as_map().unwrap(),
@@ -206,16 +762,7 @@ pub fn check_configuration(config_file: &str) -> Result<()> {
 
 /// Read the configuration from a string and retrieve globals
 pub fn configure_globals(config_file: &str) -> Result<CfgMap> {
-    let mut config_map: CfgMap; // to be initialized below
-
-    match toml::from_str(fs::read_to_string(config_file)?.as_str()) {
-        Ok(toml_text) => {
-            config_map = CfgMap::from_toml(toml_text);
-        }
-        _ => {
-            return Err(Error::new(Kind::Invalid, ERR_INVALID_CONFIG_FILE));
-        }
-    }
+    let mut config_map = read_configuration(config_file)?;
 
     // check that the first level keys are only the admitted ones
     let check = vec![
@@ -224,6 +771,7 @@ pub fn configure_globals(config_file: &str) -> Result<CfgMap> {
         "event",
         "scheduler_tick_seconds",
         "randomize_checks_within_ticks",
+        "state_file",
         "tags",
     ];
     cfg_check_keys(&config_map, &check)?;
@@ -271,6 +819,16 @@ pub fn configure_globals(config_file: &str) -> Result<CfgMap> {
         randomize_checks_within_ticks = *item.as_bool().unwrap();
     }
 
+    let cur_key = "state_file";
+    if let Some(item) = config_map.get(cur_key)
+        && !item.is_str()
+    {
+        return Err(Error::new(
+            Kind::Invalid,
+            &format!("{ERR_INVALID_CONFIG_FILE}: entry `{cur_key}`"),
+        ));
+    }
+
     // ...
 
     // complete the global configuration map if any values were not present
@@ -288,16 +846,7 @@ pub fn configure_globals(config_file: &str) -> Result<CfgMap> {
 
 /// Read the configuration from a string and retrieve globals
 pub fn reconfigure_globals(config_file: &str) -> Result<CfgMap> {
-    let mut config_map: CfgMap; // to be initialized below
-
-    match toml::from_str(fs::read_to_string(config_file)?.as_str()) {
-        Ok(toml_text) => {
-            config_map = CfgMap::from_toml(toml_text);
-        }
-        _ => {
-            return Err(Error::new(Kind::Invalid, ERR_INVALID_CONFIG_FILE));
-        }
-    }
+    let mut config_map = read_configuration(config_file)?;
 
     let cur_key = "scheduler_tick_seconds";
     let mut scheduler_tick_seconds = DEFAULT_SCHEDULER_TICK_SECONDS;
@@ -379,6 +928,14 @@ fn configure_tasks(cfgmap: &CfgMap, task_registry: &'static TaskRegistry) -> Res
                                 return Err(Error::new(Kind::Invalid, ERR_TASKREG_TASK_NOT_ADDED));
                             }
                         }
+                        #[cfg(feature = "http")]
+                        "http" => {
+                            let task =
+                                task::http_task::HttpTask::load_cfgmap(entry.as_map().unwrap())?;
+                            if !task_registry.add_task(Box::new(task)) {
+                                return Err(Error::new(Kind::Invalid, ERR_TASKREG_TASK_NOT_ADDED));
+                            }
+                        }
                         // ...
                         _ => {
                             return Err(Error::new(Kind::Invalid, ERR_INVALID_TASK_TYPE));
@@ -390,6 +947,9 @@ fn configure_tasks(cfgmap: &CfgMap, task_registry: &'static TaskRegistry) -> Res
             }
         }
     }
+
+    // reject dependencies on inexistent tasks and circular dependencies
+    task_registry.check_dependencies()?;
     Ok(())
 }
 
@@ -535,6 +1095,48 @@ fn reconfigure_tasks(cfgmap: &CfgMap, task_registry: &'static TaskRegistry) -> R
                                 );
                             }
                         }
+                        #[cfg(feature = "http")]
+                        "http" => {
+                            let task =
+                                task::http_task::HttpTask::load_cfgmap(entry.as_map().unwrap())?;
+                            let task_name = task.get_name();
+                            if !task_registry.has_task(&task_name)
+                                || !task_registry.has_task_eq(&task)
+                            {
+                                if !task_registry.dynamic_add_or_replace_task(Box::new(task))? {
+                                    return Err(Error::new(
+                                        Kind::Invalid,
+                                        ERR_TASKREG_TASK_NOT_ADDED,
+                                    ));
+                                }
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_OK,
+                                    &format!("task {task_name} has been reconfigured"),
+                                );
+                            } else {
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_MSG,
+                                    &format!(
+                                        "not reconfiguring task {task_name}: no change detected",
+                                    ),
+                                );
+                            }
+                            if to_remove.contains(&task_name) {
+                                to_remove.swap_remove(
+                                    to_remove.iter().position(|x| task_name == *x).unwrap(),
+                                );
+                            }
+                        }
                         // ...
                         _ => {
                             return Err(Error::new(Kind::Invalid, ERR_INVALID_TASK_TYPE));
@@ -612,6 +1214,17 @@ fn configure_conditions(
                                 return Err(Error::new(Kind::Invalid, ERR_CONDREG_COND_NOT_ADDED));
                             }
                         }
+                        "cron" => {
+                            // this is peculiar because it requires extra initialization after loading from map
+                            let mut condition = condition::cron_cond::CronCondition::load_cfgmap(
+                                entry.as_map().unwrap(),
+                                task_registry,
+                            )?;
+                            let _ = condition.set_tick_duration(tick_secs)?;
+                            if !cond_registry.add_condition(Box::new(condition)) {
+                                return Err(Error::new(Kind::Invalid, ERR_CONDREG_COND_NOT_ADDED));
+                            }
+                        }
                         "command" => {
                             let condition = condition::command_cond::CommandCondition::load_cfgmap(
                                 entry.as_map().unwrap(),
@@ -630,9 +1243,8 @@ fn configure_conditions(
                                 return Err(Error::new(Kind::Invalid, ERR_CONDREG_COND_NOT_ADDED));
                             }
                         }
-                        #[cfg(feature = "dbus")]
-                        "dbus" => {
-                            let condition = condition::dbus_cond::DbusMethodCondition::load_cfgmap(
+                        "tcp" => {
+                            let condition = condition::tcp_cond::TcpCondition::load_cfgmap(
                                 entry.as_map().unwrap(),
                                 task_registry,
                             )?;
@@ -640,10 +1252,8 @@ fn configure_conditions(
                                 return Err(Error::new(Kind::Invalid, ERR_CONDREG_COND_NOT_ADDED));
                             }
                         }
-                        #[cfg(windows)]
-                        #[cfg(feature = "wmi")]
-                        "wmi" => {
-                            let condition = condition::wmi_cond::WmiQueryCondition::load_cfgmap(
+                        "file" => {
+                            let condition = condition::file_cond::FileCondition::load_cfgmap(
                                 entry.as_map().unwrap(),
                                 task_registry,
                             )?;
@@ -651,24 +1261,77 @@ fn configure_conditions(
                                 return Err(Error::new(Kind::Invalid, ERR_CONDREG_COND_NOT_ADDED));
                             }
                         }
-                        "bucket" | "event" => {
-                            // this is peculiar because it requires extra initialization after loading from map
-                            let mut condition =
-                                condition::bucket_cond::BucketCondition::load_cfgmap(
+                        #[cfg(target_os = "linux")]
+                        "resource" => {
+                            let condition =
+                                condition::resource_cond::ResourceCondition::load_cfgmap(
                                     entry.as_map().unwrap(),
                                     task_registry,
                                 )?;
-                            let _ = condition.set_execution_bucket(bucket)?;
                             if !cond_registry.add_condition(Box::new(condition)) {
                                 return Err(Error::new(Kind::Invalid, ERR_CONDREG_COND_NOT_ADDED));
                             }
                         }
-                        // ...
-                        _ => {
-                            return Err(Error::new(Kind::Invalid, ERR_INVALID_COND_TYPE));
-                        }
-                    }
-                } else {
+                        "composite" => {
+                            let condition =
+                                condition::composite_cond::CompositeCondition::load_cfgmap(
+                                    entry.as_map().unwrap(),
+                                    cond_registry,
+                                    task_registry,
+                                )?;
+                            if !cond_registry.add_condition(Box::new(condition)) {
+                                return Err(Error::new(Kind::Invalid, ERR_CONDREG_COND_NOT_ADDED));
+                            }
+                        }
+                        #[cfg(feature = "dbus")]
+                        "dbus" => {
+                            let condition = condition::dbus_cond::DbusMethodCondition::load_cfgmap(
+                                entry.as_map().unwrap(),
+                                task_registry,
+                            )?;
+                            if !cond_registry.add_condition(Box::new(condition)) {
+                                return Err(Error::new(Kind::Invalid, ERR_CONDREG_COND_NOT_ADDED));
+                            }
+                        }
+                        #[cfg(feature = "http")]
+                        "http" => {
+                            let condition = condition::http_cond::HttpCondition::load_cfgmap(
+                                entry.as_map().unwrap(),
+                                task_registry,
+                            )?;
+                            if !cond_registry.add_condition(Box::new(condition)) {
+                                return Err(Error::new(Kind::Invalid, ERR_CONDREG_COND_NOT_ADDED));
+                            }
+                        }
+                        #[cfg(windows)]
+                        #[cfg(feature = "wmi")]
+                        "wmi" => {
+                            let condition = condition::wmi_cond::WmiQueryCondition::load_cfgmap(
+                                entry.as_map().unwrap(),
+                                task_registry,
+                            )?;
+                            if !cond_registry.add_condition(Box::new(condition)) {
+                                return Err(Error::new(Kind::Invalid, ERR_CONDREG_COND_NOT_ADDED));
+                            }
+                        }
+                        "bucket" | "event" => {
+                            // this is peculiar because it requires extra initialization after loading from map
+                            let mut condition =
+                                condition::bucket_cond::BucketCondition::load_cfgmap(
+                                    entry.as_map().unwrap(),
+                                    task_registry,
+                                )?;
+                            let _ = condition.set_execution_bucket(bucket)?;
+                            if !cond_registry.add_condition(Box::new(condition)) {
+                                return Err(Error::new(Kind::Invalid, ERR_CONDREG_COND_NOT_ADDED));
+                            }
+                        }
+                        // ...
+                        _ => {
+                            return Err(Error::new(Kind::Invalid, ERR_INVALID_COND_TYPE));
+                        }
+                    }
+                } else {
                     return Err(Error::new(Kind::Invalid, ERR_INVALID_COND_CONFIG));
                 }
             }
@@ -838,6 +1501,53 @@ fn reconfigure_conditions(
                                 );
                             }
                         }
+                        "cron" => {
+                            // this is peculiar because it requires extra initialization after loading from map
+                            let mut condition = condition::cron_cond::CronCondition::load_cfgmap(
+                                entry.as_map().unwrap(),
+                                task_registry,
+                            )?;
+                            let _ = condition.set_tick_duration(tick_secs)?;
+                            let cond_name = condition.get_name();
+                            if !cond_registry.has_condition(&cond_name)
+                                || !cond_registry.has_condition_eq(&condition)
+                            {
+                                if !cond_registry
+                                    .dynamic_add_or_replace_condition(Box::new(condition))?
+                                {
+                                    return Err(Error::new(
+                                        Kind::Invalid,
+                                        ERR_CONDREG_COND_NOT_ADDED,
+                                    ));
+                                }
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_OK,
+                                    &format!("condition {cond_name} has been reconfigured"),
+                                );
+                            } else {
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_MSG,
+                                    &format!(
+                                        "not reconfiguring condition {cond_name}: no change detected",
+                                    ),
+                                );
+                            }
+                            if to_remove.contains(&cond_name) {
+                                to_remove.swap_remove(
+                                    to_remove.iter().position(|x| cond_name == *x).unwrap(),
+                                );
+                            }
+                        }
                         "command" => {
                             let condition = condition::command_cond::CommandCondition::load_cfgmap(
                                 entry.as_map().unwrap(),
@@ -928,6 +1638,190 @@ fn reconfigure_conditions(
                                 );
                             }
                         }
+                        "tcp" => {
+                            let condition = condition::tcp_cond::TcpCondition::load_cfgmap(
+                                entry.as_map().unwrap(),
+                                task_registry,
+                            )?;
+                            let cond_name = condition.get_name();
+                            if !cond_registry.has_condition(&cond_name)
+                                || !cond_registry.has_condition_eq(&condition)
+                            {
+                                if !cond_registry
+                                    .dynamic_add_or_replace_condition(Box::new(condition))?
+                                {
+                                    return Err(Error::new(
+                                        Kind::Invalid,
+                                        ERR_CONDREG_COND_NOT_ADDED,
+                                    ));
+                                }
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_OK,
+                                    &format!("condition {cond_name} has been reconfigured"),
+                                );
+                            } else {
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_MSG,
+                                    &format!(
+                                        "not reconfiguring condition {cond_name}: no change detected",
+                                    ),
+                                )
+                            }
+                            if to_remove.contains(&cond_name) {
+                                to_remove.swap_remove(
+                                    to_remove.iter().position(|x| cond_name == *x).unwrap(),
+                                );
+                            }
+                        }
+                        "file" => {
+                            let condition = condition::file_cond::FileCondition::load_cfgmap(
+                                entry.as_map().unwrap(),
+                                task_registry,
+                            )?;
+                            let cond_name = condition.get_name();
+                            if !cond_registry.has_condition(&cond_name)
+                                || !cond_registry.has_condition_eq(&condition)
+                            {
+                                if !cond_registry
+                                    .dynamic_add_or_replace_condition(Box::new(condition))?
+                                {
+                                    return Err(Error::new(
+                                        Kind::Invalid,
+                                        ERR_CONDREG_COND_NOT_ADDED,
+                                    ));
+                                }
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_OK,
+                                    &format!("condition {cond_name} has been reconfigured"),
+                                );
+                            } else {
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_MSG,
+                                    &format!(
+                                        "not reconfiguring condition {cond_name}: no change detected",
+                                    ),
+                                )
+                            }
+                            if to_remove.contains(&cond_name) {
+                                to_remove.swap_remove(
+                                    to_remove.iter().position(|x| cond_name == *x).unwrap(),
+                                );
+                            }
+                        }
+                        #[cfg(target_os = "linux")]
+                        "resource" => {
+                            let condition =
+                                condition::resource_cond::ResourceCondition::load_cfgmap(
+                                    entry.as_map().unwrap(),
+                                    task_registry,
+                                )?;
+                            let cond_name = condition.get_name();
+                            if !cond_registry.has_condition(&cond_name)
+                                || !cond_registry.has_condition_eq(&condition)
+                            {
+                                if !cond_registry
+                                    .dynamic_add_or_replace_condition(Box::new(condition))?
+                                {
+                                    return Err(Error::new(
+                                        Kind::Invalid,
+                                        ERR_CONDREG_COND_NOT_ADDED,
+                                    ));
+                                }
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_OK,
+                                    &format!("condition {cond_name} has been reconfigured"),
+                                );
+                            } else {
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_MSG,
+                                    &format!(
+                                        "not reconfiguring condition {cond_name}: no change detected",
+                                    ),
+                                )
+                            }
+                            if to_remove.contains(&cond_name) {
+                                to_remove.swap_remove(
+                                    to_remove.iter().position(|x| cond_name == *x).unwrap(),
+                                );
+                            }
+                        }
+                        "composite" => {
+                            let condition =
+                                condition::composite_cond::CompositeCondition::load_cfgmap(
+                                    entry.as_map().unwrap(),
+                                    cond_registry,
+                                    task_registry,
+                                )?;
+                            let cond_name = condition.get_name();
+                            if !cond_registry.has_condition(&cond_name)
+                                || !cond_registry.has_condition_eq(&condition)
+                            {
+                                if !cond_registry
+                                    .dynamic_add_or_replace_condition(Box::new(condition))?
+                                {
+                                    return Err(Error::new(
+                                        Kind::Invalid,
+                                        ERR_CONDREG_COND_NOT_ADDED,
+                                    ));
+                                }
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_OK,
+                                    &format!("condition {cond_name} has been reconfigured"),
+                                );
+                            } else {
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_MSG,
+                                    &format!(
+                                        "not reconfiguring condition {cond_name}: no change detected",
+                                    ),
+                                )
+                            }
+                            if to_remove.contains(&cond_name) {
+                                to_remove.swap_remove(
+                                    to_remove.iter().position(|x| cond_name == *x).unwrap(),
+                                );
+                            }
+                        }
                         #[cfg(feature = "dbus")]
                         "dbus" => {
                             let condition = condition::dbus_cond::DbusMethodCondition::load_cfgmap(
@@ -974,6 +1868,52 @@ fn reconfigure_conditions(
                                 );
                             }
                         }
+                        #[cfg(feature = "http")]
+                        "http" => {
+                            let condition = condition::http_cond::HttpCondition::load_cfgmap(
+                                entry.as_map().unwrap(),
+                                task_registry,
+                            )?;
+                            let cond_name = condition.get_name();
+                            if !cond_registry.has_condition(&cond_name)
+                                || !cond_registry.has_condition_eq(&condition)
+                            {
+                                if !cond_registry
+                                    .dynamic_add_or_replace_condition(Box::new(condition))?
+                                {
+                                    return Err(Error::new(
+                                        Kind::Invalid,
+                                        ERR_CONDREG_COND_NOT_ADDED,
+                                    ));
+                                }
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_OK,
+                                    &format!("condition {cond_name} has been reconfigured"),
+                                );
+                            } else {
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_MSG,
+                                    &format!(
+                                        "not reconfiguring condition {cond_name}: no change detected",
+                                    ),
+                                )
+                            }
+                            if to_remove.contains(&cond_name) {
+                                to_remove.swap_remove(
+                                    to_remove.iter().position(|x| cond_name == *x).unwrap(),
+                                );
+                            }
+                        }
                         #[cfg(windows)]
                         #[cfg(feature = "wmi")]
                         "wmi" => {
@@ -1128,6 +2068,33 @@ fn configure_events(
                                 ));
                             }
                         }
+                        "logtail" => {
+                            let event = event::logtail_event::LogTailEvent::load_cfgmap(
+                                entry.as_map().unwrap(),
+                                cond_registry,
+                                bucket,
+                            )?;
+                            if !event_registry.add_event(Box::new(event)) {
+                                return Err(Error::new(
+                                    Kind::Invalid,
+                                    ERR_EVENTREG_EVENT_NOT_ADDED,
+                                ));
+                            }
+                        }
+                        #[cfg(unix)]
+                        "signal" => {
+                            let event = event::signal_event::SignalEvent::load_cfgmap(
+                                entry.as_map().unwrap(),
+                                cond_registry,
+                                bucket,
+                            )?;
+                            if !event_registry.add_event(Box::new(event)) {
+                                return Err(Error::new(
+                                    Kind::Invalid,
+                                    ERR_EVENTREG_EVENT_NOT_ADDED,
+                                ));
+                            }
+                        }
                         #[cfg(feature = "dbus")]
                         "dbus" => {
                             let event = event::dbus_event::DbusMessageEvent::load_cfgmap(
@@ -1266,6 +2233,125 @@ fn reconfigure_events(
                                 );
                             }
                         }
+                        "logtail" => {
+                            let event = event::logtail_event::LogTailEvent::load_cfgmap(
+                                entry.as_map().unwrap(),
+                                cond_registry,
+                                bucket,
+                            )?;
+                            let event_name = event.get_name();
+                            if !event_registry.has_event(&event_name)
+                                || !event_registry.has_event_eq(&event)
+                            {
+                                // the following call to remove_event puts the
+                                // received event out of scope after the block
+                                if event_registry.has_event(&event_name)
+                                    && event_registry.remove_event(&event_name).is_err()
+                                {
+                                    log(
+                                        LogType::Trace,
+                                        LOG_EMITTER_CONFIGURATION,
+                                        LOG_ACTION_MAIN_LISTENER,
+                                        None,
+                                        LOG_WHEN_PROC,
+                                        LOG_STATUS_FAIL,
+                                        &format!("cannot remove reconfigured event {event_name}"),
+                                    );
+                                }
+                                if !event_registry.add_event(Box::new(event)) {
+                                    return Err(Error::new(
+                                        Kind::Invalid,
+                                        ERR_EVENTREG_EVENT_NOT_ADDED,
+                                    ));
+                                }
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_OK,
+                                    &format!("event {event_name} has been reconfigured"),
+                                );
+                            } else {
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_MSG,
+                                    &format!(
+                                        "not reconfiguring event {event_name}: no change detected",
+                                    ),
+                                );
+                            }
+                            if to_remove.contains(&event_name) {
+                                to_remove.swap_remove(
+                                    to_remove.iter().position(|x| event_name == *x).unwrap(),
+                                );
+                            }
+                        }
+                        #[cfg(unix)]
+                        "signal" => {
+                            let event = event::signal_event::SignalEvent::load_cfgmap(
+                                entry.as_map().unwrap(),
+                                cond_registry,
+                                bucket,
+                            )?;
+                            let event_name = event.get_name();
+                            if !event_registry.has_event(&event_name)
+                                || !event_registry.has_event_eq(&event)
+                            {
+                                // the following call to remove_event puts the
+                                // received event out of scope after the block
+                                if event_registry.has_event(&event_name)
+                                    && event_registry.remove_event(&event_name).is_err()
+                                {
+                                    log(
+                                        LogType::Trace,
+                                        LOG_EMITTER_CONFIGURATION,
+                                        LOG_ACTION_MAIN_LISTENER,
+                                        None,
+                                        LOG_WHEN_PROC,
+                                        LOG_STATUS_FAIL,
+                                        &format!("cannot remove reconfigured event {event_name}"),
+                                    );
+                                }
+                                if !event_registry.add_event(Box::new(event)) {
+                                    return Err(Error::new(
+                                        Kind::Invalid,
+                                        ERR_EVENTREG_EVENT_NOT_ADDED,
+                                    ));
+                                }
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_OK,
+                                    &format!("event {event_name} has been reconfigured"),
+                                );
+                            } else {
+                                log(
+                                    LogType::Debug,
+                                    LOG_EMITTER_CONFIGURATION,
+                                    LOG_ACTION_RECONFIGURE,
+                                    None,
+                                    LOG_WHEN_PROC,
+                                    LOG_STATUS_MSG,
+                                    &format!(
+                                        "not reconfiguring event {event_name}: no change detected",
+                                    ),
+                                );
+                            }
+                            if to_remove.contains(&event_name) {
+                                to_remove.swap_remove(
+                                    to_remove.iter().position(|x| event_name == *x).unwrap(),
+                                );
+                            }
+                        }
                         #[cfg(feature = "dbus")]
                         "dbus" => {
                             let event = event::dbus_event::DbusMessageEvent::load_cfgmap(
@@ -35,6 +35,16 @@ pub const ERR_INVALID_TASK_CONFIG: &str = "invalid task configuration";
 pub const ERR_INVALID_COND_CONFIG: &str = "invalid condition configuration";
 pub const ERR_INVALID_EVENT_CONFIG: &str = "invalid event configuration";
 pub const ERR_INVALID_CONFIG: &str = "invalid configuration";
+pub const ERR_INVALID_INCLUDE: &str = "invalid included configuration file";
+pub const ERR_INCLUDE_NOT_FOUND: &str = "included configuration file not found";
+pub const ERR_DUPLICATE_ITEM_NAME: &str = "duplicate item name";
+pub const ERR_INVALID_TEMPLATE: &str = "invalid template";
+pub const ERR_TEMPLATE_NOT_FOUND: &str = "template not found";
+pub const ERR_TEMPLATE_CIRCULAR: &str = "circular template reference";
+pub const ERR_INVALID_VARIABLES: &str = "invalid configuration variables";
+pub const ERR_UNDEFINED_ENVVAR: &str = "undefined environment variable";
+pub const ERR_UNDEFINED_VARIABLE: &str = "undefined configuration variable";
+pub const ERR_UNREADABLE_FILE: &str = "cannot read referenced file";
 pub const ERR_TASKREG_TASK_NOT_ADDED: &str = "could not add task to the registry";
 pub const ERR_TASKREG_CANNOT_PULL_TASK: &str = "could not pull task out from the registry";
 pub const ERR_CONDREG_COND_NOT_ADDED: &str = "could not add condition to the registry";
@@ -42,7 +52,10 @@ pub const ERR_CONDREG_CANNOT_PULL_COND: &str = "could not pull condition out fro
 pub const ERR_CONDREG_COND_RESET_BUSY: &str = "attempt to reset condition while busy";
 pub const ERR_CONDREG_COND_SUSPEND_BUSY: &str = "attempt to suspend condition while busy";
 pub const ERR_CONDREG_COND_RESUME_BUSY: &str = "attempt to resume condition while busy";
+pub const ERR_CONDREG_INVALID_STATE_FILE: &str = "invalid condition state file";
 pub const ERR_CONDREG_COND_TICK_NOEXIST: &str = "attempt to test non existent condition skipped";
+pub const ERR_CONDREG_COND_NOT_FOUND: &str = "condition not found in the registry";
+pub const ERR_CONDREG_COMPOSITE_INPUT: &str = "composite conditions cannot be used as inputs";
 pub const ERR_EVENTREG_EVENT_NOT_ADDED: &str = "could not add event to the registry";
 pub const ERR_EVENTREG_CANNOT_REMOVE_EVENT: &str = "could not remove event from the registry";
 pub const ERR_EVENTREG_CANNOT_STOP_LISTENER: &str = "could not shut down the event listener";
@@ -56,23 +69,45 @@ pub const ERR_UNKNOWN_EXITSTATUS: &str = "unknown exit status";
 pub const ERR_ALREADY_RUNNING: &str = "another instance of the scheduler is already running";
 pub const ERR_LOGGER_NOT_INITIALIZED: &str = "could not initialize logger";
 
+pub const ERR_PROTOCOL_INVALID_REQUEST: &str = "invalid request";
+pub const ERR_PROTOCOL_MISSING_COMMAND: &str = "command missing in request";
+pub const ERR_PROTOCOL_INVALID_ARGS: &str =
+    "request arguments must be a list of non-empty strings without whitespace";
+
+#[cfg(unix)]
+pub const ERR_CONTROL_PATH_NOT_SOCKET: &str = "control socket path exists and is not a socket";
+#[cfg(unix)]
+pub const ERR_CONTROL_SOCKET_IN_USE: &str = "control socket in use by another process";
+#[cfg(unix)]
+pub const ERR_CONTROL_NO_REPLY: &str = "no reply received from the running instance";
+
 pub const ERR_INVALID_CFG_ENTRY: &str = "invalid configuration entry";
 pub const ERR_MISSING_PARAMETER: &str = "missing parameter";
 pub const ERR_INVALID_PARAMETER: &str = "invalid parameter";
 pub const ERR_INVALID_PARAMETER_LIST: &str = "invalid list or list element";
 pub const ERR_INVALID_FILESPEC: &str = "invalid file specification";
+pub const ERR_INVALID_PATTERN: &str = "invalid glob pattern";
 pub const ERR_INVALID_COND_TYPE: &str = "condition type invalid or mismatched";
 pub const ERR_INVALID_TASK: &str = "invalid task specification or inexistent task";
+pub const ERR_TASK_UNKNOWN_DEPENDENCY: &str = "task depends on an inexistent task";
+pub const ERR_TASK_DEPENDENCY_CYCLE: &str = "circular task dependency involving task";
 
 pub const ERR_INVALID_STARTUP_PATH: &str = "invalid startup path";
 pub const ERR_INVALID_ENVVAR_NAME: &str = "invalid name for environment variable";
 pub const ERR_INVALID_ENVVAR_VALUE: &str = "invalid value for environment variable";
+#[cfg(feature = "http")]
+pub const ERR_INVALID_HEADER_NAME: &str = "invalid name for HTTP header";
+#[cfg(feature = "http")]
+pub const ERR_INVALID_HEADER_VALUE: &str = "invalid value for HTTP header";
 
 pub const ERR_INVALID_VAR_NAME: &str = "invalid variable name";
 pub const ERR_INVALID_VAR_VALUE: &str = "invalid variable value";
 pub const ERR_INVALID_VALUE: &str = "invalid value";
 
 pub const ERR_INVALID_TIMESPEC: &str = "invalid specification for date or time";
+pub const ERR_INVALID_CRONSPEC: &str = "invalid cron expression";
+pub const ERR_INVALID_COMPOSITE_EXPR: &str = "invalid composite condition expression";
+pub const ERR_INVALID_COMPOSITE_INPUT: &str = "inexistent or composite condition in expression";
 pub const ERR_INVALID_TICK_SECONDS: &str = "invalid number of seconds for tick";
 pub const ERR_INVALID_VALUE_FOR: &str = "invalid value for";
 pub const ERR_INVALID_VALUE_FOR_ENTRY: &str = "invalid value for entry";
@@ -118,13 +153,20 @@ pub const LOG_EMITTER_EVENT: &str = "EVENT";
 pub const LOG_EMITTER_EVENT_REGISTRY: &str = "EVENT_REGISTRY";
 pub const LOG_EMITTER_CONFIGURATION: &str = "CONFIGURATION";
 pub const LOG_EMITTER_MAIN: &str = "MAIN";
+#[cfg(unix)]
+pub const LOG_EMITTER_CONTROL: &str = "CONTROL";
 
 pub const LOG_EMITTER_TASK_COMMAND: &str = "COMMAND_TASK";
 pub const LOG_EMITTER_TASK_LUA: &str = "LUA_TASK";
 pub const LOG_EMITTER_TASK_INTERNAL: &str = "INTERNAL_TASK";
+#[cfg(feature = "http")]
+pub const LOG_EMITTER_TASK_HTTP: &str = "HTTP_TASK";
 
 pub const LOG_EMITTER_EVENT_FSCHANGE: &str = "FSCHANGE_EVENT";
+pub const LOG_EMITTER_EVENT_LOGTAIL: &str = "LOGTAIL_EVENT";
 pub const LOG_EMITTER_EVENT_MANUAL: &str = "CMD_EVENT";
+#[cfg(unix)]
+pub const LOG_EMITTER_EVENT_SIGNAL: &str = "SIGNAL_EVENT";
 #[cfg(feature = "dbus")]
 pub const LOG_EMITTER_EVENT_DBUS: &str = "DBUS_EVENT";
 #[cfg(windows)]
@@ -132,12 +174,20 @@ pub const LOG_EMITTER_EVENT_DBUS: &str = "DBUS_EVENT";
 pub const LOG_EMITTER_EVENT_WMI: &str = "WMI_EVENT";
 
 pub const LOG_EMITTER_CONDITION_INTERVAL: &str = "INTERVAL_CONDITION";
+pub const LOG_EMITTER_CONDITION_CRON: &str = "CRON_CONDITION";
 pub const LOG_EMITTER_CONDITION_BUCKET: &str = "BUCKET_CONDITION";
 pub const LOG_EMITTER_CONDITION_COMMAND: &str = "COMMAND_CONDITION";
 pub const LOG_EMITTER_CONDITION_IDLE: &str = "IDLE_CONDITION";
 pub const LOG_EMITTER_CONDITION_LUA: &str = "LUA_CONDITION";
+pub const LOG_EMITTER_CONDITION_COMPOSITE: &str = "COMPOSITE_CONDITION";
+pub const LOG_EMITTER_CONDITION_FILE: &str = "FILE_CONDITION";
+pub const LOG_EMITTER_CONDITION_TCP: &str = "TCP_CONDITION";
+#[cfg(target_os = "linux")]
+pub const LOG_EMITTER_CONDITION_RESOURCE: &str = "RESOURCE_CONDITION";
 #[cfg(feature = "dbus")]
 pub const LOG_EMITTER_CONDITION_DBUS: &str = "DBUS_CONDITION";
+#[cfg(feature = "http")]
+pub const LOG_EMITTER_CONDITION_HTTP: &str = "HTTP_CONDITION";
 #[cfg(windows)]
 #[cfg(feature = "wmi")]
 pub const LOG_EMITTER_CONDITION_WMI: &str = "WMI_CONDITION";
@@ -155,6 +205,8 @@ pub const LOG_ACTION_RESET_CONDITIONS: &str = "reset_conditions";
 pub const LOG_ACTION_SUSPEND_CONDITION: &str = "suspend_condition";
 pub const LOG_ACTION_RESUME_CONDITION: &str = "resume_condition";
 pub const LOG_ACTION_CONDITION_STATE: &str = "condition_state";
+pub const LOG_ACTION_RESTORE_STATE: &str = "restore_state";
+pub const LOG_ACTION_SAVE_STATE: &str = "save_state";
 pub const LOG_ACTION_EVENT_TRIGGER: &str = "event_trigger";
 pub const LOG_ACTION_RUN_COMMAND: &str = "command";
 pub const LOG_ACTION_MAIN_LISTENER: &str = "listener";
@@ -162,18 +214,48 @@ pub const LOG_ACTION_MAIN_START: &str = "starting";
 pub const LOG_ACTION_MAIN_EXIT: &str = "exit";
 pub const LOG_ACTION_RUN_TASKS_SEQ: &str = "run_seq";
 pub const LOG_ACTION_RUN_TASKS_PAR: &str = "run_par";
+pub const LOG_ACTION_RUN_TASKS_GRAPH: &str = "run_graph";
 
 // other string pub constants
 pub const STR_UNKNOWN_VALUE: &str = "<unknown>";
 pub const STR_INVALID_TYPE: &str = "<invalid_type>";
 pub const STR_INVALID_VALUE: &str = "<invalid_value>";
 
+// status values for replies to structured (JSON) requests
+pub const PROTOCOL_STATUS_OK: &str = "ok";
+pub const PROTOCOL_STATUS_FAIL: &str = "fail";
+pub const PROTOCOL_STATUS_ERR: &str = "error";
+
+// control socket replies and file name suffix (as for the GUID, a debug
+// instance uses a different socket to be able to run beside a release one)
+#[cfg(unix)]
+pub const CONTROL_REPLY_OK: &str = "OK";
+#[cfg(unix)]
+pub const CONTROL_REPLY_FAIL: &str = "FAIL";
+#[cfg(unix)]
+pub const CONTROL_REPLY_ERR: &str = "ERR";
+
+#[cfg(all(unix, debug_assertions))]
+pub const CONTROL_SOCKET_SUFFIX: &str = "_DEBUG";
+#[cfg(all(unix, not(debug_assertions)))]
+pub const CONTROL_SOCKET_SUFFIX: &str = "";
+
 // default values
 pub const DEFAULT_SCHEDULER_TICK_SECONDS: i64 = 5;
 pub const DEFAULT_RANDOMIZE_CHECKS_WITHIN_TICKS: bool = false;
+pub const DEFAULT_TCP_CONNECT_TIMEOUT_SECONDS: u64 = 5;
+
+// retry delays never exceed a week, in order to avoid overflows
+pub const MAX_RETRY_DELAY_SECONDS: i64 = 7 * 24 * 3600;
 
 // operational values
 pub const MAIN_STDIN_READ_WAIT_MILLISECONDS: u64 = 100; // default: 100
+pub const CONFIG_WATCH_STABLE_MILLISECONDS: u64 = 2000; // default: 2000
+pub const CONFIG_WATCH_POLL_MILLISECONDS: u64 = 500; // default: 500
+pub const FILE_CONTENT_CHECK_MAX_BYTES: u64 = 1024 * 1024; // default: 1MiB
+
+// prefix for command arguments that select items by tag
+pub const TAG_SELECTOR_PREFIX: &str = "tag:";
 
 // channel sizes
 pub const EVENT_QUIT_CHANNEL_SIZE: usize = 10; // default: 10
@@ -195,10 +277,12 @@ lazy_static! {
     // environment variables set by the command based items
     pub static ref ENVVAR_NAME_TASK: String = format!("{}_TASK", APP_NAME.to_ascii_uppercase());
     pub static ref ENVVAR_NAME_COND: String = format!("{}_CONDITION", APP_NAME.to_ascii_uppercase());
+    pub static ref ENVVAR_NAME_EVENT: String = format!("{}_EVENT", APP_NAME.to_ascii_uppercase());
 
     // Lua variables set by the Lua based items
     pub static ref LUAVAR_NAME_TASK: String = format!("{}_task", APP_NAME.to_ascii_lowercase());
     pub static ref LUAVAR_NAME_COND: String = format!("{}_condition", APP_NAME.to_ascii_lowercase());
+    pub static ref LUAVAR_NAME_EVENT: String = format!("{}_event", APP_NAME.to_ascii_lowercase());
 
     // item names
     pub static ref RE_TASK_NAME: Regex = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$").unwrap();
@@ -209,6 +293,12 @@ lazy_static! {
     pub static ref RE_VAR_NAME: Regex = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$").unwrap();
     pub static ref RE_ENVVAR_NAME: Regex = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$").unwrap();
 
+    // host names or addresses for network based items
+    pub static ref RE_HOST_ADDRESS: Regex = Regex::new(r"^[a-zA-Z0-9._:%-]+$").unwrap();
+
+    // references in configuration values, a leading `$$` escapes them
+    pub static ref RE_CFG_REFERENCE: Regex = Regex::new(r"\$?\$\{(env|file|var):([^}]*)\}").unwrap();
+
     // interval for polling spawned commands for stdout/stderr contents
     pub static ref DUR_SPAWNED_POLL_INTERVAL: Duration = Duration::from_millis(100);
 
@@ -244,6 +334,14 @@ lazy_static! {
     pub static ref RE_WMI_NAMESPACE: Regex = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*([/\\][a-zA-Z_][a-zA-Z0-9_]*)+$").unwrap();
 }
 
+#[cfg(feature = "http")]
+lazy_static! {
+    // only absolute HTTP(S) URLs are accepted by HTTP based items, while
+    // header names must be tokens as defined in RFC 9110
+    pub static ref RE_HTTP_URL: Regex = Regex::new(r"(?i)^https?://[^\s/?#]+\S*$").unwrap();
+    pub static ref RE_HTTP_HEADER_NAME: Regex = Regex::new(r"^[a-zA-Z0-9!#$%&'*+.^_`|~-]+$").unwrap();
+}
+
 #[cfg(feature = "lua_sync")]
 lazy_static! {
     // accepted names for mutex objects and state tables to use in Lua scrripts
@@ -12,9 +12,14 @@
 //!
 //! and so on. The purpose of the trait is to provide a common interface that
 //! is independent from the particular implementation of the waiting service.
+//!
+//! Firing the condition is also handled here, so that the firing policy (see
+//! the `firing` module) applies in the same way to all types of event.
 
 use async_trait::async_trait;
 
+use super::firing::FiringPolicy;
+use super::payload::EventPayload;
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::{Error, Kind, Result};
 use crate::condition::bucket_cond::ExecutionBucket;
@@ -60,6 +65,9 @@ pub trait Event: Send + Sync {
     /// Condition bucket getter
     fn condition_bucket(&self) -> Option<&'static ExecutionBucket>;
 
+    /// Return the policy that determines how often the condition is fired
+    fn firing_policy(&self) -> FiringPolicy;
+
     /// Tell whether or not another `Event` is equal to this
     fn eq(&self, other: &dyn Event) -> bool {
         self._hash() == other._hash()
@@ -112,6 +120,18 @@ pub trait Event: Send + Sync {
     /// has not been set: each would indicate an error in the program flow.
     /// Also panics if the event has not been registered.
     fn fire_condition(&self) -> bool {
+        self.fire_condition_with_payload(EventPayload::new(&self.get_name()))
+    }
+
+    /// Fire the assigned condition attaching a payload
+    ///
+    /// This is the same as `fire_condition`, but the provided payload is
+    /// passed on to the tasks run by the condition.
+    ///
+    /// # Panics
+    ///
+    /// In the same cases as `fire_condition`.
+    fn fire_condition_with_payload(&self, payload: EventPayload) -> bool {
         assert!(
             self.get_id() != 0,
             "event {} not registered",
@@ -125,13 +145,48 @@ pub trait Event: Send + Sync {
 
         let cond_name = self.get_condition().unwrap();
         let bucket = self.condition_bucket().unwrap();
-        self.log(
-            LogType::Debug,
-            LOG_WHEN_PROC,
-            LOG_STATUS_OK,
-            &format!("condition {cond_name} firing"),
-        );
-        bucket.insert_condition(&cond_name)
+        let policy = self.firing_policy();
+        if let Some(quiet) = policy.debounce() {
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_PROC,
+                LOG_STATUS_OK,
+                &format!(
+                    "condition {cond_name} firing after {}s without further triggers",
+                    quiet.as_secs(),
+                ),
+            );
+            bucket.defer_condition(&cond_name, quiet, policy.throttle(), payload)
+        } else if let Some(window) = policy.throttle() {
+            let res = bucket.insert_condition_throttled(&cond_name, window, payload);
+            if res {
+                self.log(
+                    LogType::Debug,
+                    LOG_WHEN_PROC,
+                    LOG_STATUS_OK,
+                    &format!("condition {cond_name} firing"),
+                );
+            } else {
+                self.log(
+                    LogType::Debug,
+                    LOG_WHEN_PROC,
+                    LOG_STATUS_MSG,
+                    &format!(
+                        "condition {cond_name} already fired in the last {}s",
+                        window.as_secs()
+                    ),
+                );
+            }
+            res
+        } else {
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_PROC,
+                LOG_STATUS_OK,
+                &format!("condition {cond_name} firing"),
+            );
+            bucket.insert_condition(&cond_name, payload)
+        }
     }
 
     /// Log a message in the specific `Event` format
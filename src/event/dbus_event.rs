@@ -21,6 +21,8 @@ use async_trait::async_trait;
 use zbus;
 
 use super::base::Event;
+use super::firing::{FiringPolicy, cfg_firing_policy};
+use super::payload::EventPayload;
 use crate::common::dbusitem::*;
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::Result;
@@ -33,6 +35,36 @@ use crate::cfghelp::*;
 // see the DBus specification
 const DBUS_MAX_NUMBER_OF_ARGUMENTS: i64 = 63;
 
+// build the payload for a received message: the header fields identifying
+// the signal, and the arguments as `arg0`, `arg1` and so on, where strings
+// are passed verbatim and other values using the GVariant text format
+fn message_payload(event_name: &str, message: &zbus::Message) -> EventPayload {
+    let mut payload = EventPayload::new(event_name);
+    let header = message.header();
+    if let Some(v) = header.sender() {
+        payload.set_text("sender", v.as_str());
+    }
+    if let Some(v) = header.path() {
+        payload.set_text("path", v.as_str());
+    }
+    if let Some(v) = header.interface() {
+        payload.set_text("interface", v.as_str());
+    }
+    if let Some(v) = header.member() {
+        payload.set_text("member", v.as_str());
+    }
+    if let Ok(body) = message.body().deserialize::<zbus::zvariant::Structure>() {
+        for (index, field) in body.fields().iter().enumerate() {
+            let value = match field {
+                zbus::zvariant::Value::Str(x) => x.to_string(),
+                x => x.to_string(),
+            };
+            payload.set_text(&format!("arg{index}"), &value);
+        }
+    }
+    payload
+}
+
 /// DBus Based Event
 ///
 /// Implements an event based upon DBus suscription to certain events, using
@@ -48,6 +80,7 @@ pub struct DbusMessageEvent {
     event_id: i64,
     event_name: String,
     condition_name: Option<String>,
+    firing_policy: FiringPolicy,
 
     // internal values
     condition_registry: Option<&'static ConditionRegistry>,
@@ -72,6 +105,7 @@ impl Hash for DbusMessageEvent {
         if let Some(s) = &self.condition_name {
             s.hash(state);
         }
+        self.firing_policy.hash(state);
 
         // specific part
         // 0 is hashed on the else branch in order to avoid that adjacent
@@ -105,6 +139,7 @@ impl Clone for DbusMessageEvent {
             // parameters
             event_name: self.event_name.clone(),
             condition_name: self.condition_name.clone(),
+            firing_policy: self.firing_policy,
 
             // internal values
             condition_registry: None,
@@ -153,6 +188,7 @@ impl DbusMessageEvent {
             // parameters
             event_name: String::from(name),
             condition_name: None,
+            firing_policy: FiringPolicy::default(),
 
             // internal values
             condition_registry: None,
@@ -227,6 +263,8 @@ impl DbusMessageEvent {
             "name",
             "tags",
             "condition",
+            "debounce_seconds",
+            "throttle_seconds",
             "bus",
             "rule",
             "parameter_check",
@@ -284,6 +322,8 @@ impl DbusMessageEvent {
             new_event.assign_condition(&v)?;
         }
 
+        new_event.firing_policy = cfg_firing_policy(cfgmap)?;
+
         // specific optional parameter initialization
 
         // this is tricky: we build a list of elements constituted by:
@@ -491,6 +531,8 @@ impl DbusMessageEvent {
             "name",
             "tags",
             "condition",
+            "debounce_seconds",
+            "throttle_seconds",
             "bus",
             "rule",
             "parameter_check",
@@ -531,6 +573,8 @@ impl DbusMessageEvent {
             ));
         }
 
+        cfg_firing_policy(cfgmap)?;
+
         // specific optional parameter check
 
         let check = ["index", "operator", "value"];
@@ -718,6 +762,10 @@ impl Event for DbusMessageEvent {
         self.condition_bucket
     }
 
+    fn firing_policy(&self) -> FiringPolicy {
+        self.firing_policy
+    }
+
     fn _assign_condition(&mut self, cond_name: &str) {
         // correctness has already been checked by the caller
         self.condition_name = Some(String::from(cond_name));
@@ -798,7 +846,7 @@ impl Event for DbusMessageEvent {
             }
 
             if verified {
-                if self.fire_condition() {
+                if self.fire_condition_with_payload(message_payload(&name, &message)) {
                     self.log(
                         LogType::Debug,
                         LOG_WHEN_PROC,
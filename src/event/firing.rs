@@ -0,0 +1,67 @@
+//! Firing policies for events that trigger in bursts
+//!
+//! The execution bucket only merges the firings that occur before the next
+//! tick, so an event that keeps triggering over several ticks (for instance
+//! while a large file is being written, or when a DBus service emits many
+//! signals in a row) would cause its condition to fire repeatedly. A firing
+//! policy can be used to reduce the number of firings:
+//!
+//! * `debounce` - the condition is fired only once the event has stopped
+//!   triggering for the specified amount of seconds
+//! * `throttle` - the condition is fired at most once within a window of
+//!   the specified amount of seconds, further triggers being discarded.
+//!
+//! When both are specified, the condition fires when the event has been
+//! quiet for the debounce period, unless it already fired within the
+//! throttle window.
+
+use std::time::Duration;
+
+use cfgmap::CfgMap;
+
+use crate::cfghelp::*;
+use crate::utility::result::Result;
+
+/// The policy that determines how often an event fires its condition
+#[derive(Clone, Copy, Default, Hash, PartialEq)]
+pub struct FiringPolicy {
+    debounce: u64,
+    throttle: u64,
+}
+
+impl FiringPolicy {
+    /// The quiet period required before firing, if any
+    pub fn debounce(&self) -> Option<Duration> {
+        if self.debounce > 0 {
+            Some(Duration::from_secs(self.debounce))
+        } else {
+            None
+        }
+    }
+
+    /// The minimum interval between two firings, if any
+    pub fn throttle(&self) -> Option<Duration> {
+        if self.throttle > 0 {
+            Some(Duration::from_secs(self.throttle))
+        } else {
+            None
+        }
+    }
+}
+
+/// Retrieve the firing policy from an event configuration
+///
+/// Both entries are optional, and the default policy (that fires at each
+/// trigger) is returned when none of them is specified.
+pub fn cfg_firing_policy(cfgmap: &CfgMap) -> Result<FiringPolicy> {
+    let mut policy = FiringPolicy::default();
+    if let Some(v) = cfg_int_check_above_eq(cfgmap, "debounce_seconds", 0)? {
+        policy.debounce = v as u64;
+    }
+    if let Some(v) = cfg_int_check_above_eq(cfgmap, "throttle_seconds", 0)? {
+        policy.throttle = v as u64;
+    }
+    Ok(policy)
+}
+
+// end.
@@ -4,9 +4,14 @@
 //! a notification everytime that a change occurs in the watched items. This
 //! event puts an associated condition into the execution bucket each time
 //! it happens.
+//!
+//! Notifications can be filtered, so that only the changes that are relevant
+//! fire the condition: _include_ and _exclude_ glob patterns are checked
+//! against the changed paths, and the kinds of change can be restricted to
+//! creation, modification, removal and renaming.
 
 use std::hash::{DefaultHasher, Hash, Hasher};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use async_trait::async_trait;
@@ -17,11 +22,14 @@ use futures::{
 
 use cfgmap::CfgMap;
 
-use notify::{self, Watcher};
+use glob::Pattern;
+use notify::{self, Watcher, event::ModifyKind};
 
 use super::base::Event;
+use super::payload::EventPayload;
+use super::firing::{FiringPolicy, cfg_firing_policy};
 use crate::utility::logging::{LogType, log};
-use crate::utility::result::Result;
+use crate::utility::result::{Error, Kind, Result};
 use crate::condition::bucket_cond::ExecutionBucket;
 use crate::condition::registry::ConditionRegistry;
 use crate::{cfg_mandatory, constants::*};
@@ -31,6 +39,50 @@ use crate::cfghelp::*;
 // default seconds to wayt between active polls: generally ignored
 const DEFAULT_FSCHANGE_POLL_SECONDS: u64 = 2;
 
+// the kinds of change that can be used to filter notifications
+const FSCHANGE_KINDS: [&str; 4] = ["create", "modify", "remove", "rename"];
+
+// compile a list of glob patterns, failing on the first invalid one
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Pattern>> {
+    let mut res = Vec::new();
+    for p in patterns {
+        match Pattern::new(p) {
+            Ok(pattern) => res.push(pattern),
+            Err(e) => {
+                return Err(Error::new(
+                    Kind::Invalid,
+                    &format!("{ERR_INVALID_PATTERN}: `{p}` ({e})"),
+                ));
+            }
+        }
+    }
+    Ok(res)
+}
+
+// the kind of change as used in configuration and in the payload, where
+// `other` stands for kinds that cannot be selected
+fn event_kind(evt: &notify::Event) -> &'static str {
+    match evt.kind {
+        notify::EventKind::Create(_) => "create",
+        notify::EventKind::Modify(ModifyKind::Name(_)) => "rename",
+        notify::EventKind::Modify(_) => "modify",
+        notify::EventKind::Remove(_) => "remove",
+        _ => "other",
+    }
+}
+
+// patterns containing a path separator are matched against the full path,
+// while simple patterns (such as `*.txt`) are matched against the file name
+fn pattern_matches(pattern: &Pattern, path: &Path) -> bool {
+    let s = pattern.as_str();
+    if s.contains('/') || s.contains(std::path::MAIN_SEPARATOR) {
+        pattern.matches_path(path)
+    } else {
+        path.file_name()
+            .is_some_and(|name| pattern.matches(&name.to_string_lossy()))
+    }
+}
+
 /// Filesystem Change Based Event
 ///
 /// Implements an event based upon filesystem change notification: it uses the
@@ -44,6 +96,7 @@ pub struct FilesystemChangeEvent {
     event_id: i64,
     event_name: String,
     condition_name: Option<String>,
+    firing_policy: FiringPolicy,
 
     // internal values
     condition_registry: Option<&'static ConditionRegistry>,
@@ -54,8 +107,13 @@ pub struct FilesystemChangeEvent {
     watched_locations: Option<Vec<PathBuf>>,
     poll_seconds: u64,
     recursive: bool,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    kinds: Vec<String>,
 
     // internal values
+    include_patterns: Vec<Pattern>,
+    exclude_patterns: Vec<Pattern>,
     event_rx: Option<Receiver<notify::Result<notify::Event>>>,
     event_watcher: Option<notify::RecommendedWatcher>,
 }
@@ -68,6 +126,7 @@ impl Hash for FilesystemChangeEvent {
         if let Some(s) = &self.condition_name {
             s.hash(state);
         }
+        self.firing_policy.hash(state);
 
         // specific part
         if let Some(x) = &self.watched_locations {
@@ -79,6 +138,9 @@ impl Hash for FilesystemChangeEvent {
         }
         self.poll_seconds.hash(state);
         self.recursive.hash(state);
+        self.include.hash(state);
+        self.exclude.hash(state);
+        self.kinds.hash(state);
     }
 }
 
@@ -92,6 +154,7 @@ impl Clone for FilesystemChangeEvent {
             // parameters
             event_name: self.event_name.clone(),
             condition_name: self.condition_name.clone(),
+            firing_policy: self.firing_policy,
 
             // internal values
             condition_registry: None,
@@ -102,8 +165,13 @@ impl Clone for FilesystemChangeEvent {
             watched_locations: self.watched_locations.clone(),
             poll_seconds: self.poll_seconds,
             recursive: self.recursive,
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+            kinds: self.kinds.clone(),
 
             // internal values
+            include_patterns: self.include_patterns.clone(),
+            exclude_patterns: self.exclude_patterns.clone(),
             event_rx: None,
             event_watcher: None,
         }
@@ -129,6 +197,7 @@ impl FilesystemChangeEvent {
             // parameters
             event_name: String::from(name),
             condition_name: None,
+            firing_policy: FiringPolicy::default(),
 
             // internal values
             condition_registry: None,
@@ -139,8 +208,13 @@ impl FilesystemChangeEvent {
             watched_locations: None,
             poll_seconds: DEFAULT_FSCHANGE_POLL_SECONDS,
             recursive: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            kinds: Vec::new(),
 
             // internal values
+            include_patterns: Vec::new(),
+            exclude_patterns: Vec::new(),
             event_rx: None,
             event_watcher: None,
         }
@@ -189,6 +263,45 @@ impl FilesystemChangeEvent {
         self.recursive
     }
 
+    /// Only accept changes to paths matching one of the provided patterns
+    pub fn set_include(&mut self, patterns: &[String]) -> Result<bool> {
+        self.include_patterns = compile_patterns(patterns)?;
+        self.include = patterns.to_vec();
+        Ok(true)
+    }
+
+    /// Ignore changes to paths matching any of the provided patterns
+    pub fn set_exclude(&mut self, patterns: &[String]) -> Result<bool> {
+        self.exclude_patterns = compile_patterns(patterns)?;
+        self.exclude = patterns.to_vec();
+        Ok(true)
+    }
+
+    /// Only accept the provided kinds of change
+    pub fn set_kinds(&mut self, kinds: &[String]) {
+        self.kinds = kinds.to_vec();
+    }
+
+    // tell whether a notification passes both the kind and the path filters:
+    // for events involving more than one path (such as renames) it suffices
+    // that one of the paths is accepted
+    fn accepts(&self, evt: &notify::Event) -> bool {
+        if !self.kinds.is_empty() {
+            let kind = event_kind(evt);
+            if !self.kinds.iter().any(|k| k == kind) {
+                return false;
+            }
+        }
+        if self.include_patterns.is_empty() && self.exclude_patterns.is_empty() {
+            return true;
+        }
+        evt.paths.iter().any(|p| {
+            (self.include_patterns.is_empty()
+                || self.include_patterns.iter().any(|x| pattern_matches(x, p)))
+                && !self.exclude_patterns.iter().any(|x| pattern_matches(x, p))
+        })
+    }
+
     /// Load a `FilesystemChangeEvent` from a [`CfgMap`](https://docs.rs/cfgmap/latest/)
     ///
     /// The `FilesystemChangeEvent` is initialized according to the values
@@ -200,7 +313,20 @@ impl FilesystemChangeEvent {
         cond_registry: &'static ConditionRegistry,
         bucket: &'static ExecutionBucket,
     ) -> Result<FilesystemChangeEvent> {
-        let check = vec!["type", "name", "tags", "condition", "watch", "recursive"];
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "condition",
+            "debounce_seconds",
+            "throttle_seconds",
+            "watch",
+            "recursive",
+            "poll_seconds",
+            "include",
+            "exclude",
+            "kinds",
+        ];
         cfg_check_keys(cfgmap, &check)?;
 
         // common mandatory parameter retrieval
@@ -246,6 +372,8 @@ impl FilesystemChangeEvent {
             new_event.assign_condition(&v)?;
         }
 
+        new_event.firing_policy = cfg_firing_policy(cfgmap)?;
+
         // specific optional parameter initialization
         if let Some(v) = cfg_vec_string(cfgmap, "watch")? {
             for s in v {
@@ -268,6 +396,31 @@ impl FilesystemChangeEvent {
             new_event.poll_seconds = v as u64;
         }
 
+        // filters: patterns are compiled here, and refused if invalid
+        let cur_key = "include";
+        if let Some(v) = cfg_vec_string(cfgmap, cur_key)?
+            && new_event.set_include(&v).is_err()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PATTERN,
+            ));
+        }
+        let cur_key = "exclude";
+        if let Some(v) = cfg_vec_string(cfgmap, cur_key)?
+            && new_event.set_exclude(&v).is_err()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PATTERN,
+            ));
+        }
+        if let Some(v) = cfg_vec_string_check_within(cfgmap, "kinds", &FSCHANGE_KINDS.to_vec())? {
+            new_event.set_kinds(&v);
+        }
+
         Ok(new_event)
     }
 
@@ -278,7 +431,20 @@ impl FilesystemChangeEvent {
     /// created and that a name is returned, which is the name of the item that
     /// _would_ be created via the equivalent call to `load_cfgmap`
     pub fn check_cfgmap(cfgmap: &CfgMap, available_conditions: &Vec<&str>) -> Result<String> {
-        let check = vec!["type", "name", "tags", "condition", "watch", "recursive"];
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "condition",
+            "debounce_seconds",
+            "throttle_seconds",
+            "watch",
+            "recursive",
+            "poll_seconds",
+            "include",
+            "exclude",
+            "kinds",
+        ];
         cfg_check_keys(cfgmap, &check)?;
 
         // common mandatory parameter retrieval
@@ -314,11 +480,26 @@ impl FilesystemChangeEvent {
             ));
         }
 
+        cfg_firing_policy(cfgmap)?;
+
         // specific optional parameter check
         cfg_vec_string(cfgmap, "watch")?; // see above: we do not check for correctness
         cfg_bool(cfgmap, "recursive")?;
         cfg_int_check_above_eq(cfgmap, "poll_seconds", 0)?;
 
+        for cur_key in ["include", "exclude"] {
+            if let Some(v) = cfg_vec_string(cfgmap, cur_key)?
+                && compile_patterns(&v).is_err()
+            {
+                return Err(cfg_err_invalid_config(
+                    cur_key,
+                    STR_UNKNOWN_VALUE,
+                    ERR_INVALID_PATTERN,
+                ));
+            }
+        }
+        cfg_vec_string_check_within(cfgmap, "kinds", &FSCHANGE_KINDS.to_vec())?;
+
         Ok(name)
     }
 }
@@ -362,6 +543,10 @@ impl Event for FilesystemChangeEvent {
         self.condition_bucket
     }
 
+    fn firing_policy(&self) -> FiringPolicy {
+        self.firing_policy
+    }
+
     fn _assign_condition(&mut self, cond_name: &str) {
         // correctness has already been checked by the caller
         self.condition_name = Some(String::from(cond_name));
@@ -396,13 +581,31 @@ impl Event for FilesystemChangeEvent {
                 } else {
                     "UNKNOWN"
                 };
+                if !self.accepts(&evt) {
+                    self.log(
+                        LogType::Debug,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_MSG,
+                        &format!("event notification filtered out: {evt_s}"),
+                    );
+                    return Ok(None);
+                }
                 self.log(
                     LogType::Debug,
                     LOG_WHEN_PROC,
                     LOG_STATUS_OK,
                     &format!("event notification caught: {evt_s}"),
                 );
-                if self.fire_condition() {
+                let mut payload = EventPayload::new(&name);
+                payload.set_list("kinds", vec![String::from(event_kind(&evt))]);
+                payload.set_list(
+                    "paths",
+                    evt.paths
+                        .iter()
+                        .map(|p| p.to_string_lossy().to_string())
+                        .collect(),
+                );
+                if self.fire_condition_with_payload(payload) {
                     self.log(
                         LogType::Debug,
                         LOG_WHEN_PROC,
@@ -0,0 +1,698 @@
+//! Define event based on lines appended to a text file
+//!
+//! The user states a file to follow, as `tail -F` would do, and a regular
+//! expression: each time that lines are appended to the file, the ones that
+//! match the expression fire the associated condition. The directory that
+//! contains the file is watched rather than the file itself, so that the
+//! event keeps working when the file is rotated (that is, renamed or removed
+//! and then created again) or truncated: in both cases the new contents are
+//! read from the beginning. The followed file is kept open, so that on
+//! rotation the lines still unread in the old file are not lost.
+
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::{
+    SinkExt, StreamExt,
+    channel::mpsc::{Receiver, channel},
+};
+
+use cfgmap::CfgMap;
+
+use notify::{self, Watcher};
+use regex::{Regex, RegexBuilder};
+
+use super::base::Event;
+use super::firing::{FiringPolicy, cfg_firing_policy};
+use super::payload::EventPayload;
+use crate::utility::logging::{LogType, log};
+use crate::utility::result::{Error, Kind, Result};
+use crate::condition::bucket_cond::ExecutionBucket;
+use crate::condition::registry::ConditionRegistry;
+use crate::{cfg_mandatory, constants::*};
+
+use crate::cfghelp::*;
+
+// default seconds to wayt between active polls: generally ignored
+const DEFAULT_LOGTAIL_POLL_SECONDS: u64 = 2;
+
+// something that identifies a file beyond its name, used to detect that the
+// followed file has been replaced by a new one with the same name
+#[cfg(unix)]
+fn file_identity(meta: &fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(meta.ino())
+}
+
+#[cfg(not(unix))]
+fn file_identity(meta: &fs::Metadata) -> Option<u64> {
+    meta.created()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_nanos() as u64)
+}
+
+// build the matcher for appended lines
+fn build_matcher(pattern: &str, case_sensitive: bool) -> std::result::Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+}
+
+/// Log File Tail Based Event
+///
+/// Implements an event that follows a text file and fires its condition when
+/// appended lines match a regular expression. It uses the same notification
+/// service as the filesystem change based event, watching the directory that
+/// contains the file in order to survive rotations.
+#[allow(dead_code)]
+pub struct LogTailEvent {
+    // common members
+    // parameters
+    event_id: i64,
+    event_name: String,
+    condition_name: Option<String>,
+    firing_policy: FiringPolicy,
+
+    // internal values
+    condition_registry: Option<&'static ConditionRegistry>,
+    condition_bucket: Option<&'static ExecutionBucket>,
+
+    // specific members
+    // parameters
+    file: Option<PathBuf>,
+    pattern: Option<String>,
+    case_sensitive: bool,
+    poll_seconds: u64,
+
+    // internal values
+    matcher: Option<Regex>,
+    position: u64,
+    identity: Option<u64>,
+    handle: Option<fs::File>,
+    partial_line: Vec<u8>,
+    event_rx: Option<Receiver<notify::Result<notify::Event>>>,
+    event_watcher: Option<notify::RecommendedWatcher>,
+}
+
+// implement the hash protocol
+impl Hash for LogTailEvent {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // common part
+        self.event_name.hash(state);
+        if let Some(s) = &self.condition_name {
+            s.hash(state);
+        }
+        self.firing_policy.hash(state);
+
+        // specific part
+        self.file.hash(state);
+        self.pattern.hash(state);
+        self.case_sensitive.hash(state);
+        self.poll_seconds.hash(state);
+    }
+}
+
+// implement cloning
+impl Clone for LogTailEvent {
+    fn clone(&self) -> Self {
+        LogTailEvent {
+            // reset ID
+            event_id: 0,
+
+            // parameters
+            event_name: self.event_name.clone(),
+            condition_name: self.condition_name.clone(),
+            firing_policy: self.firing_policy,
+
+            // internal values
+            condition_registry: None,
+            condition_bucket: None,
+
+            // specific members initialization
+            // parameters
+            file: self.file.clone(),
+            pattern: self.pattern.clone(),
+            case_sensitive: self.case_sensitive,
+            poll_seconds: self.poll_seconds,
+
+            // internal values
+            matcher: self.matcher.clone(),
+            position: 0,
+            identity: None,
+            handle: None,
+            partial_line: Vec::new(),
+            event_rx: None,
+            event_watcher: None,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl LogTailEvent {
+    pub fn new(name: &str) -> Self {
+        log(
+            LogType::Debug,
+            LOG_EMITTER_EVENT_LOGTAIL,
+            LOG_ACTION_NEW,
+            Some((name, 0)),
+            LOG_WHEN_INIT,
+            LOG_STATUS_MSG,
+            &format!("EVENT {name}: creating a new log file tail based event"),
+        );
+        LogTailEvent {
+            // reset ID
+            event_id: 0,
+
+            // parameters
+            event_name: String::from(name),
+            condition_name: None,
+            firing_policy: FiringPolicy::default(),
+
+            // internal values
+            condition_registry: None,
+            condition_bucket: None,
+
+            // specific members initialization
+            // parameters
+            file: None,
+            pattern: None,
+            case_sensitive: false,
+            poll_seconds: DEFAULT_LOGTAIL_POLL_SECONDS,
+
+            // internal values
+            matcher: None,
+            position: 0,
+            identity: None,
+            handle: None,
+            partial_line: Vec::new(),
+            event_rx: None,
+            event_watcher: None,
+        }
+    }
+
+    /// Set the file to be followed
+    pub fn set_file(&mut self, path: &str) {
+        self.file = Some(PathBuf::from(path));
+    }
+
+    /// Set the regular expression that lines must match
+    pub fn set_pattern(&mut self, pattern: &str, case_sensitive: bool) -> Result<bool> {
+        match build_matcher(pattern, case_sensitive) {
+            Ok(matcher) => self.matcher = Some(matcher),
+            Err(e) => {
+                return Err(Error::new(
+                    Kind::Invalid,
+                    &format!("{ERR_INVALID_VALUE}: `{pattern}` ({e})"),
+                ));
+            }
+        }
+        self.pattern = Some(String::from(pattern));
+        self.case_sensitive = case_sensitive;
+        Ok(true)
+    }
+
+    // forget everything about the followed file, so that its contents are
+    // read from the beginning as soon as it appears again
+    fn restart(&mut self, identity: Option<u64>) {
+        self.identity = identity;
+        self.position = 0;
+        self.partial_line.clear();
+    }
+
+    // read what has been appended to the open file since the last read: the
+    // handle still refers to the old file after it is renamed or removed
+    fn drain_open_file(&mut self) -> Result<()> {
+        if let Some(f) = self.handle.as_mut() {
+            f.seek(SeekFrom::Start(self.position))?;
+            let mut buffer: Vec<u8> = Vec::new();
+            let read = f.read_to_end(&mut buffer)?;
+            self.position += read as u64;
+            self.partial_line.extend(buffer);
+        }
+        Ok(())
+    }
+
+    // move the complete lines that match out of the buffer of read data
+    fn take_matching_lines(&mut self, res: &mut Vec<String>) {
+        let matcher = self.matcher.as_ref().unwrap();
+        while let Some(eol) = self.partial_line.iter().position(|c| *c == b'\n') {
+            let line: Vec<u8> = self.partial_line.drain(..=eol).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+            if matcher.is_match(line) {
+                res.push(String::from(line));
+            }
+        }
+    }
+
+    // read the lines appended to the file since the last read, handling
+    // rotation and truncation, and return the ones that match: incomplete
+    // lines are kept until they are terminated, unless the file is rotated
+    fn matching_lines(&mut self) -> Result<Vec<String>> {
+        let path = self.file.clone().unwrap();
+        let meta = fs::metadata(&path).ok();
+        let identity = meta.as_ref().and_then(file_identity);
+        let mut res = Vec::new();
+
+        if meta.is_none() || identity != self.identity || self.handle.is_none() {
+            // as `tail -F` does, finish reading the old file before leaving
+            // it: its last line is considered complete as nothing follows it
+            if self.handle.is_some() {
+                self.drain_open_file()?;
+                if !self.partial_line.is_empty() {
+                    self.partial_line.push(b'\n');
+                }
+                self.take_matching_lines(&mut res);
+                self.handle = None;
+            }
+            let Some(_) = meta else {
+                // the file has been removed and not created yet
+                if self.identity.is_some() {
+                    self.log(
+                        LogType::Debug,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_MSG,
+                        "followed file disappeared: waiting for it to be created again",
+                    );
+                }
+                self.restart(None);
+                return Ok(res);
+            };
+            if identity != self.identity {
+                self.log(
+                    LogType::Debug,
+                    LOG_WHEN_PROC,
+                    LOG_STATUS_MSG,
+                    "followed file has been replaced: reading it from the beginning",
+                );
+                self.restart(identity);
+            }
+            self.handle = Some(fs::File::open(&path)?);
+        } else if meta.is_some_and(|m| m.len() < self.position) {
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_PROC,
+                LOG_STATUS_MSG,
+                "followed file has been truncated: reading it from the beginning",
+            );
+            self.restart(identity);
+        }
+
+        self.drain_open_file()?;
+        self.take_matching_lines(&mut res);
+        Ok(res)
+    }
+
+    /// Load a `LogTailEvent` from a [`CfgMap`](https://docs.rs/cfgmap/latest/)
+    ///
+    /// The `LogTailEvent` is initialized according to the values provided in
+    /// the `CfgMap` argument. If the `CfgMap` format does not comply with the
+    /// requirements of a `LogTailEvent` an error is raised.
+    pub fn load_cfgmap(
+        cfgmap: &CfgMap,
+        cond_registry: &'static ConditionRegistry,
+        bucket: &'static ExecutionBucket,
+    ) -> Result<LogTailEvent> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "condition",
+            "debounce_seconds",
+            "throttle_seconds",
+            "file",
+            "pattern",
+            "case_sensitive",
+            "poll_seconds",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // common mandatory parameter retrieval
+
+        // type and name are both mandatory but type is only checked
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "logtail"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_EVENT_NAME))?.unwrap();
+
+        // specific mandatory parameter retrieval
+        let file = cfg_mandatory!(cfg_string(cfgmap, "file"))?.unwrap();
+        let pattern = cfg_mandatory!(cfg_string(cfgmap, "pattern"))?.unwrap();
+
+        // initialize the structure
+        // NOTE: the value of "event" for the condition type, which is
+        //       completely functionally equivalent to "bucket", can only
+        //       be set from the configuration file; programmatically built
+        //       conditions of this type will only report "bucket" as their
+        //       type, and "event" is only left for configuration readability
+        let mut new_event = LogTailEvent::new(&name);
+        new_event.condition_registry = Some(cond_registry);
+        new_event.condition_bucket = Some(bucket);
+        new_event.set_file(&file);
+
+        // common optional parameter initialization
+
+        // tags are always simply checked this way as no value is needed
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        let cur_key = "condition";
+        if let Some(v) = cfg_string_check_regex(cfgmap, "condition", &RE_COND_NAME)? {
+            if !new_event.condition_registry.unwrap().has_condition(&v) {
+                return Err(cfg_err_invalid_config(
+                    cur_key,
+                    &v,
+                    ERR_INVALID_EVENT_CONDITION,
+                ));
+            }
+            new_event.assign_condition(&v)?;
+        }
+
+        new_event.firing_policy = cfg_firing_policy(cfgmap)?;
+
+        // specific optional parameter initialization
+        let case_sensitive = cfg_bool(cfgmap, "case_sensitive")?.unwrap_or(false);
+        if new_event.set_pattern(&pattern, case_sensitive).is_err() {
+            return Err(cfg_err_invalid_config(
+                "pattern",
+                &pattern,
+                ERR_INVALID_VALUE,
+            ));
+        }
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "poll_seconds", 0)? {
+            new_event.poll_seconds = v as u64;
+        }
+
+        Ok(new_event)
+    }
+
+    /// Check a configuration map and return item name if Ok
+    ///
+    /// The check is performed exactly in the same way and in the same order
+    /// as in `load_cfgmap`, the only difference is that no actual item is
+    /// created and that a name is returned, which is the name of the item that
+    /// _would_ be created via the equivalent call to `load_cfgmap`
+    pub fn check_cfgmap(cfgmap: &CfgMap, available_conditions: &Vec<&str>) -> Result<String> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "condition",
+            "debounce_seconds",
+            "throttle_seconds",
+            "file",
+            "pattern",
+            "case_sensitive",
+            "poll_seconds",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // common mandatory parameter retrieval
+
+        // type and name are both mandatory: type is checked and name is kept
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "logtail"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_EVENT_NAME))?.unwrap();
+
+        // specific mandatory parameter check
+        cfg_mandatory!(cfg_string(cfgmap, "file"))?;
+        let pattern = cfg_mandatory!(cfg_string(cfgmap, "pattern"))?.unwrap();
+
+        // also for optional parameters just check and throw away the result
+
+        // tags are always simply checked this way
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // assigned condition is checked against the provided array
+        let cur_key = "condition";
+        if let Some(v) = cfg_string_check_regex(cfgmap, "condition", &RE_COND_NAME)?
+            && !available_conditions.contains(&v.as_str())
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                &v,
+                ERR_INVALID_EVENT_CONDITION,
+            ));
+        }
+
+        cfg_firing_policy(cfgmap)?;
+
+        // specific optional parameter check
+        let case_sensitive = cfg_bool(cfgmap, "case_sensitive")?.unwrap_or(false);
+        if build_matcher(&pattern, case_sensitive).is_err() {
+            return Err(cfg_err_invalid_config(
+                "pattern",
+                &pattern,
+                ERR_INVALID_VALUE,
+            ));
+        }
+        cfg_int_check_above_eq(cfgmap, "poll_seconds", 0)?;
+
+        Ok(name)
+    }
+}
+
+#[async_trait(?Send)]
+impl Event for LogTailEvent {
+    fn set_id(&mut self, id: i64) {
+        self.event_id = id;
+    }
+    fn get_name(&self) -> String {
+        self.event_name.clone()
+    }
+    fn get_id(&self) -> i64 {
+        self.event_id
+    }
+
+    /// Return a hash of this item for comparison
+    fn _hash(&self) -> u64 {
+        let mut s = DefaultHasher::new();
+        self.hash(&mut s);
+        s.finish()
+    }
+
+    fn get_condition(&self) -> Option<String> {
+        self.condition_name.clone()
+    }
+
+    fn set_condition_registry(&mut self, reg: &'static ConditionRegistry) {
+        self.condition_registry = Some(reg);
+    }
+
+    fn condition_registry(&self) -> Option<&'static ConditionRegistry> {
+        self.condition_registry
+    }
+
+    fn set_condition_bucket(&mut self, bucket: &'static ExecutionBucket) {
+        self.condition_bucket = Some(bucket);
+    }
+
+    fn condition_bucket(&self) -> Option<&'static ExecutionBucket> {
+        self.condition_bucket
+    }
+
+    fn firing_policy(&self) -> FiringPolicy {
+        self.firing_policy
+    }
+
+    fn _assign_condition(&mut self, cond_name: &str) {
+        // correctness has already been checked by the caller
+        self.condition_name = Some(String::from(cond_name));
+    }
+
+    // this function is a wrapper for the actual asynchronous event receiver
+    async fn event_triggered(&mut self) -> Result<Option<String>> {
+        let name = self.get_name();
+        assert!(
+            self.event_rx.is_some(),
+            "uninitialized event notification channel for LogTailEvent {name}",
+        );
+        assert!(
+            self.event_watcher.is_some(),
+            "uninitialized event notifier for LogTailEvent {name}",
+        );
+        let event_receiver = self.event_rx.as_mut().unwrap();
+
+        // only the notifications regarding the followed file are relevant,
+        // while the other files in the same directory are ignored
+        let Some(evt) = event_receiver.next().await else {
+            return Ok(None);
+        };
+        let evt = evt?;
+        let file_name = self.file.as_ref().unwrap().file_name();
+        if evt.kind.is_access() || !evt.paths.iter().any(|p| p.file_name() == file_name) {
+            return Ok(None);
+        }
+
+        let lines = match self.matching_lines() {
+            Ok(lines) => lines,
+            Err(e) => {
+                self.log(
+                    LogType::Warn,
+                    LOG_WHEN_PROC,
+                    LOG_STATUS_FAIL,
+                    &format!("could not read followed file: {e}"),
+                );
+                return Ok(None);
+            }
+        };
+        if lines.is_empty() {
+            return Ok(None);
+        }
+
+        self.log(
+            LogType::Debug,
+            LOG_WHEN_PROC,
+            LOG_STATUS_OK,
+            &format!("{} appended line(s) matched", lines.len()),
+        );
+        let mut payload = EventPayload::new(&name);
+        payload.set_text("path", &self.file.as_ref().unwrap().to_string_lossy());
+        payload.set_list("lines", lines);
+        if self.fire_condition_with_payload(payload) {
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_PROC,
+                LOG_STATUS_OK,
+                "condition fired successfully",
+            );
+        } else {
+            self.log(
+                LogType::Trace,
+                LOG_WHEN_PROC,
+                LOG_STATUS_MSG,
+                "condition already fired: further schedule skipped",
+            );
+        }
+
+        Ok(Some(name))
+    }
+
+    fn initial_setup(&mut self) -> Result<bool> {
+        assert!(
+            self.event_rx.is_none(),
+            "event listening channel for LogTailEvent {} is already initialized",
+            self.get_name(),
+        );
+        assert!(
+            self.file.is_some(),
+            "file not set for LogTailEvent {}",
+            self.get_name(),
+        );
+
+        // see the filesystem change based event for details
+        fn _build_watcher(
+            notify_cfg: notify::Config,
+        ) -> notify::Result<(
+            notify::RecommendedWatcher,
+            Receiver<notify::Result<notify::Event>>,
+        )> {
+            let (mut tx, rx) = channel(EVENT_CHANNEL_SIZE);
+
+            let watcher = notify::RecommendedWatcher::new(
+                move |res| {
+                    futures::executor::block_on(async {
+                        tx.send(res).await.unwrap();
+                    })
+                },
+                notify_cfg,
+            )?;
+            Ok((watcher, rx))
+        }
+
+        // start from the end of the file as it is now, if it exists
+        let path = self.file.clone().unwrap();
+        match fs::metadata(&path) {
+            Ok(meta) => {
+                self.restart(file_identity(&meta));
+                self.position = meta.len();
+                self.handle = fs::File::open(&path).ok();
+            }
+            Err(_) => {
+                self.log(
+                    LogType::Debug,
+                    LOG_WHEN_START,
+                    LOG_STATUS_MSG,
+                    &format!(
+                        "file `{}` does not exist yet: waiting for it to be created",
+                        path.to_string_lossy(),
+                    ),
+                );
+                self.restart(None);
+            }
+        }
+
+        let notify_cfg =
+            notify::Config::default().with_poll_interval(Duration::from_secs(self.poll_seconds));
+        let (mut watcher, event_rx) = _build_watcher(notify_cfg)?;
+        let dir = match path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        match watcher.watch(Path::new(&dir), notify::RecursiveMode::NonRecursive) {
+            Ok(_) => {
+                self.log(
+                    LogType::Debug,
+                    LOG_WHEN_START,
+                    LOG_STATUS_OK,
+                    &format!("following file `{}`", path.to_string_lossy()),
+                );
+            }
+            Err(e) => {
+                self.log(
+                    LogType::Warn,
+                    LOG_WHEN_START,
+                    LOG_STATUS_FAIL,
+                    &format!("could not watch directory `{}`: {e}", dir.to_string_lossy()),
+                );
+            }
+        }
+
+        self.event_rx = Some(event_rx);
+        self.event_watcher = Some(watcher);
+
+        Ok(true)
+    }
+
+    fn final_cleanup(&mut self) -> Result<bool> {
+        // destroy both the the receive channel and the watcher
+        if let Some(c) = self.event_rx.take() {
+            drop(c);
+        }
+        if let Some(w) = self.event_watcher.take() {
+            drop(w);
+        }
+        self.handle = None;
+        self.log(
+            LogType::Debug,
+            LOG_WHEN_START,
+            LOG_STATUS_MSG,
+            "log file tail event notifier has been reset",
+        );
+        Ok(false)
+    }
+}
+
+// end.
@@ -12,6 +12,7 @@ use cfgmap::CfgMap;
 use async_trait::async_trait;
 
 use super::base::Event;
+use super::firing::{FiringPolicy, cfg_firing_policy};
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::Result;
 use crate::condition::bucket_cond::ExecutionBucket;
@@ -30,6 +31,7 @@ pub struct ManualCommandEvent {
     event_id: i64,
     event_name: String,
     condition_name: Option<String>,
+    firing_policy: FiringPolicy,
 
     // internal values
     condition_registry: Option<&'static ConditionRegistry>,
@@ -50,6 +52,7 @@ impl Hash for ManualCommandEvent {
         if let Some(s) = &self.condition_name {
             s.hash(state);
         }
+        self.firing_policy.hash(state);
 
         // specific part
         // (none here)
@@ -66,6 +69,7 @@ impl Clone for ManualCommandEvent {
             // parameters
             event_name: self.event_name.clone(),
             condition_name: self.condition_name.clone(),
+            firing_policy: self.firing_policy,
 
             // internal values
             condition_registry: None,
@@ -97,6 +101,7 @@ impl ManualCommandEvent {
             // parameters
             event_name: String::from(name),
             condition_name: None,
+            firing_policy: FiringPolicy::default(),
 
             // internal values
             condition_registry: None,
@@ -119,7 +124,14 @@ impl ManualCommandEvent {
         cond_registry: &'static ConditionRegistry,
         bucket: &'static ExecutionBucket,
     ) -> Result<ManualCommandEvent> {
-        let check = vec!["type", "name", "tags", "condition"];
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "condition",
+            "debounce_seconds",
+            "throttle_seconds",
+        ];
         cfg_check_keys(cfgmap, &check)?;
 
         // common mandatory parameter retrieval
@@ -165,6 +177,8 @@ impl ManualCommandEvent {
             new_event.assign_condition(&v)?;
         }
 
+        new_event.firing_policy = cfg_firing_policy(cfgmap)?;
+
         // specific optional parameter initialization
         // (none here)
 
@@ -178,7 +192,14 @@ impl ManualCommandEvent {
     /// created and that a name is returned, which is the name of the item that
     /// _would_ be created via the equivalent call to `load_cfgmap`
     pub fn check_cfgmap(cfgmap: &CfgMap, available_conditions: &Vec<&str>) -> Result<String> {
-        let check = vec!["type", "name", "tags", "condition"];
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "condition",
+            "debounce_seconds",
+            "throttle_seconds",
+        ];
         cfg_check_keys(cfgmap, &check)?;
 
         // common mandatory parameter retrieval
@@ -214,6 +235,8 @@ impl ManualCommandEvent {
             ));
         }
 
+        cfg_firing_policy(cfgmap)?;
+
         Ok(name)
     }
 }
@@ -262,6 +285,10 @@ impl Event for ManualCommandEvent {
         self.condition_bucket
     }
 
+    fn firing_policy(&self) -> FiringPolicy {
+        self.firing_policy
+    }
+
     fn _assign_condition(&mut self, cond_name: &str) {
         // correctness has already been checked by the caller
         self.condition_name = Some(String::from(cond_name));
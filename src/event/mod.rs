@@ -2,11 +2,17 @@
 
 pub mod base; // this only defines the trait
 pub mod registry; // the main event registry
+pub mod firing; // debounce and throttle settings
+pub mod payload; // information passed on to tasks
 
 // specific event types
 pub mod fschange_event;
+pub mod logtail_event;
 pub mod manual_event;
 
+#[cfg(unix)]
+pub mod signal_event;
+
 #[cfg(feature = "dbus")]
 pub mod dbus_event;
 
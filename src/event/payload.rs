@@ -0,0 +1,105 @@
+//! Information attached by events to the conditions they fire
+//!
+//! When an event fires its condition, it can describe what happened using a
+//! _payload_: for instance the paths that changed for filesystem events, the
+//! signal arguments for DBus events, or the arguments given to the `trigger`
+//! command. The payload travels along with the condition name in the
+//! execution bucket, and is handed over to the tasks when the condition runs
+//! them: command tasks receive it as environment variables, Lua tasks as a
+//! table.
+//!
+//! Each value is identified by a lowercase name and is either a string or a
+//! list of strings. If the event fires again before its condition is checked,
+//! the most recent payload replaces the previous one, except that the lists
+//! found in both are merged: this way all the paths changed within a burst of
+//! filesystem notifications are reported.
+
+use std::collections::BTreeMap;
+
+/// A value carried by an event payload
+#[derive(Clone, Debug, PartialEq)]
+pub enum PayloadValue {
+    Text(String),
+    List(Vec<String>),
+}
+
+/// The payload attached by an event to the condition it fires
+#[derive(Clone, Debug)]
+pub struct EventPayload {
+    event: String,
+    values: BTreeMap<String, PayloadValue>,
+}
+
+impl EventPayload {
+    /// Create a payload for the named event, with no values
+    pub fn new(event: &str) -> Self {
+        EventPayload {
+            event: String::from(event),
+            values: BTreeMap::new(),
+        }
+    }
+
+    /// The name of the event that attached the payload
+    pub fn event(&self) -> &str {
+        &self.event
+    }
+
+    /// Set a string value
+    pub fn set_text(&mut self, name: &str, value: &str) {
+        self.values
+            .insert(String::from(name), PayloadValue::Text(String::from(value)));
+    }
+
+    /// Set a list value
+    pub fn set_list(&mut self, name: &str, value: Vec<String>) {
+        self.values
+            .insert(String::from(name), PayloadValue::List(value));
+    }
+
+    /// Iterate over the values, sorted by name
+    pub fn values(&self) -> impl Iterator<Item = (&String, &PayloadValue)> {
+        self.values.iter()
+    }
+
+    /// Merge the payload with a more recent one
+    ///
+    /// Lists are only merged when both payloads come from the same event.
+    pub fn merge(&mut self, newer: EventPayload) {
+        let mut older = std::mem::replace(self, newer);
+        if older.event != self.event {
+            return;
+        }
+        for (name, value) in self.values.iter_mut() {
+            if let PayloadValue::List(items) = value
+                && let Some(PayloadValue::List(mut old_items)) = older.values.remove(name)
+            {
+                for item in items.drain(..) {
+                    if !old_items.contains(&item) {
+                        old_items.push(item);
+                    }
+                }
+                *items = old_items;
+            }
+        }
+    }
+
+    /// Return the payload as a list of environment variables
+    ///
+    /// The event name is stored in a variable whose name is the given prefix,
+    /// and each value in a variable named after the prefix followed by an
+    /// underscore and the uppercase value name. Lists are stored one item per
+    /// line.
+    pub fn environment(&self, prefix: &str) -> Vec<(String, String)> {
+        let mut res = vec![(String::from(prefix), self.event.clone())];
+        for (name, value) in self.values.iter() {
+            let value = match value {
+                PayloadValue::Text(s) => s.clone(),
+                PayloadValue::List(v) => v.join("\n"),
+            };
+            res.push((format!("{prefix}_{}", name.to_ascii_uppercase()), value));
+        }
+        res
+    }
+}
+
+// end.
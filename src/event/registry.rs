@@ -22,6 +22,7 @@ use unique_id::Generator;
 use unique_id::sequence::SequenceGenerator;
 
 use super::base::{Event, EventRef};
+use super::payload::EventPayload;
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::{Error, Kind, Result};
 use crate::constants::*;
@@ -579,11 +580,14 @@ impl EventRegistry {
 
     /// If the event can be manually triggered, fire its condition.
     ///
+    /// The provided arguments are attached to the condition as the values
+    /// `arg0`, `arg1` and so on of the event payload.
+    ///
     /// # Panics
     ///
     /// When the event it is called upon is not registered: in no way this
     /// should be called for unregistered events.
-    pub fn trigger_event(&self, name: &str) -> bool {
+    pub fn trigger_event(&self, name: &str, args: &[String]) -> bool {
         assert!(
             self.has_event_triggerable(name),
             "event {name} is not a manually triggerable event",
@@ -603,8 +607,13 @@ impl EventRegistry {
             &format!("manually triggering event {name}"),
         );
 
+        let mut payload = EventPayload::new(name);
+        for (index, arg) in args.iter().enumerate() {
+            payload.set_text(&format!("arg{index}"), arg);
+        }
+
         // all checks have passed and the following cannot panic
-        event.fire_condition()
+        event.fire_condition_with_payload(payload)
     }
 }
 
@@ -0,0 +1,434 @@
+//! Define event based on signals received by the scheduler process
+//!
+//! The user states the name of a signal (such as `SIGUSR1`, `SIGUSR2`,
+//! `SIGHUP` or a realtime signal) and the associated condition is fired each
+//! time the scheduler receives it. This allows scripts to notify a running
+//! instance using `kill`, without having to use its _stdin_. Only available
+//! on Unix systems.
+
+#![cfg(unix)]
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::os::raw::c_int;
+
+use async_trait::async_trait;
+
+use cfgmap::CfgMap;
+
+use super::base::Event;
+use super::firing::{FiringPolicy, cfg_firing_policy};
+use super::payload::EventPayload;
+use crate::utility::logging::{LogType, log};
+use crate::utility::result::{Error, Kind, Result};
+use crate::utility::signals::{SignalReceiver, signal_name, signal_number};
+use crate::condition::bucket_cond::ExecutionBucket;
+use crate::condition::registry::ConditionRegistry;
+use crate::{cfg_mandatory, constants::*};
+
+use crate::cfghelp::*;
+
+/// Signal Based Event
+///
+/// Implements an event that fires its condition whenever the scheduler
+/// process receives a specific signal.
+#[allow(dead_code)]
+pub struct SignalEvent {
+    // common members
+    // parameters
+    event_id: i64,
+    event_name: String,
+    condition_name: Option<String>,
+    firing_policy: FiringPolicy,
+
+    // internal values
+    condition_registry: Option<&'static ConditionRegistry>,
+    condition_bucket: Option<&'static ExecutionBucket>,
+
+    // specific members
+    // parameters
+    signal: Option<c_int>,
+
+    // internal values
+    receiver: Option<SignalReceiver>,
+}
+
+// implement the hash protocol
+impl Hash for SignalEvent {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // common part
+        self.event_name.hash(state);
+        if let Some(s) = &self.condition_name {
+            s.hash(state);
+        }
+        self.firing_policy.hash(state);
+
+        // specific part
+        self.signal.hash(state);
+    }
+}
+
+// implement cloning
+impl Clone for SignalEvent {
+    fn clone(&self) -> Self {
+        SignalEvent {
+            // reset ID
+            event_id: 0,
+
+            // parameters
+            event_name: self.event_name.clone(),
+            condition_name: self.condition_name.clone(),
+            firing_policy: self.firing_policy,
+
+            // internal values
+            condition_registry: None,
+            condition_bucket: None,
+
+            // specific members initialization
+            // parameters
+            signal: self.signal,
+
+            // internal values
+            receiver: None,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl SignalEvent {
+    pub fn new(name: &str) -> Self {
+        log(
+            LogType::Debug,
+            LOG_EMITTER_EVENT_SIGNAL,
+            LOG_ACTION_NEW,
+            Some((name, 0)),
+            LOG_WHEN_INIT,
+            LOG_STATUS_MSG,
+            &format!("EVENT {name}: creating a new signal based event"),
+        );
+        SignalEvent {
+            // reset ID
+            event_id: 0,
+
+            // parameters
+            event_name: String::from(name),
+            condition_name: None,
+            firing_policy: FiringPolicy::default(),
+
+            // internal values
+            condition_registry: None,
+            condition_bucket: None,
+
+            // specific members initialization
+            // parameters
+            signal: None,
+
+            // internal values
+            receiver: None,
+        }
+    }
+
+    /// Set the signal to listen for, by name
+    pub fn set_signal(&mut self, name: &str) -> Result<bool> {
+        if let Some(signum) = signal_number(name) {
+            self.signal = Some(signum);
+            Ok(true)
+        } else {
+            Err(Error::new(
+                Kind::Invalid,
+                &format!("{ERR_INVALID_VALUE}: `{name}`"),
+            ))
+        }
+    }
+
+    /// Load a `SignalEvent` from a [`CfgMap`](https://docs.rs/cfgmap/latest/)
+    ///
+    /// The `SignalEvent` is initialized according to the values provided in
+    /// the `CfgMap` argument. If the `CfgMap` format does not comply with the
+    /// requirements of a `SignalEvent` an error is raised.
+    pub fn load_cfgmap(
+        cfgmap: &CfgMap,
+        cond_registry: &'static ConditionRegistry,
+        bucket: &'static ExecutionBucket,
+    ) -> Result<SignalEvent> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "condition",
+            "debounce_seconds",
+            "throttle_seconds",
+            "signal",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // common mandatory parameter retrieval
+
+        // type and name are both mandatory but type is only checked
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "signal"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_EVENT_NAME))?.unwrap();
+
+        // specific mandatory parameter retrieval
+        let signal = cfg_mandatory!(cfg_string(cfgmap, "signal"))?.unwrap();
+
+        // initialize the structure
+        // NOTE: the value of "event" for the condition type, which is
+        //       completely functionally equivalent to "bucket", can only
+        //       be set from the configuration file; programmatically built
+        //       conditions of this type will only report "bucket" as their
+        //       type, and "event" is only left for configuration readability
+        let mut new_event = SignalEvent::new(&name);
+        new_event.condition_registry = Some(cond_registry);
+        new_event.condition_bucket = Some(bucket);
+        if new_event.set_signal(&signal).is_err() {
+            return Err(cfg_err_invalid_config(
+                "signal",
+                &signal,
+                ERR_INVALID_VALUE,
+            ));
+        }
+
+        // common optional parameter initialization
+
+        // tags are always simply checked this way as no value is needed
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        let cur_key = "condition";
+        if let Some(v) = cfg_string_check_regex(cfgmap, "condition", &RE_COND_NAME)? {
+            if !new_event.condition_registry.unwrap().has_condition(&v) {
+                return Err(cfg_err_invalid_config(
+                    cur_key,
+                    &v,
+                    ERR_INVALID_EVENT_CONDITION,
+                ));
+            }
+            new_event.assign_condition(&v)?;
+        }
+
+        new_event.firing_policy = cfg_firing_policy(cfgmap)?;
+
+        // specific optional parameter initialization
+        // (none here)
+
+        Ok(new_event)
+    }
+
+    /// Check a configuration map and return item name if Ok
+    ///
+    /// The check is performed exactly in the same way and in the same order
+    /// as in `load_cfgmap`, the only difference is that no actual item is
+    /// created and that a name is returned, which is the name of the item that
+    /// _would_ be created via the equivalent call to `load_cfgmap`
+    pub fn check_cfgmap(cfgmap: &CfgMap, available_conditions: &Vec<&str>) -> Result<String> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "condition",
+            "debounce_seconds",
+            "throttle_seconds",
+            "signal",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // common mandatory parameter retrieval
+
+        // type and name are both mandatory: type is checked and name is kept
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "signal"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_EVENT_NAME))?.unwrap();
+
+        // specific mandatory parameter check
+        let signal = cfg_mandatory!(cfg_string(cfgmap, "signal"))?.unwrap();
+        if signal_number(&signal).is_none() {
+            return Err(cfg_err_invalid_config(
+                "signal",
+                &signal,
+                ERR_INVALID_VALUE,
+            ));
+        }
+
+        // also for optional parameters just check and throw away the result
+
+        // tags are always simply checked this way
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // assigned condition is checked against the provided array
+        let cur_key = "condition";
+        if let Some(v) = cfg_string_check_regex(cfgmap, "condition", &RE_COND_NAME)?
+            && !available_conditions.contains(&v.as_str())
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                &v,
+                ERR_INVALID_EVENT_CONDITION,
+            ));
+        }
+
+        cfg_firing_policy(cfgmap)?;
+
+        Ok(name)
+    }
+}
+
+#[async_trait(?Send)]
+impl Event for SignalEvent {
+    fn set_id(&mut self, id: i64) {
+        self.event_id = id;
+    }
+    fn get_name(&self) -> String {
+        self.event_name.clone()
+    }
+    fn get_id(&self) -> i64 {
+        self.event_id
+    }
+
+    /// Return a hash of this item for comparison
+    fn _hash(&self) -> u64 {
+        let mut s = DefaultHasher::new();
+        self.hash(&mut s);
+        s.finish()
+    }
+
+    fn get_condition(&self) -> Option<String> {
+        self.condition_name.clone()
+    }
+
+    fn set_condition_registry(&mut self, reg: &'static ConditionRegistry) {
+        self.condition_registry = Some(reg);
+    }
+
+    fn condition_registry(&self) -> Option<&'static ConditionRegistry> {
+        self.condition_registry
+    }
+
+    fn set_condition_bucket(&mut self, bucket: &'static ExecutionBucket) {
+        self.condition_bucket = Some(bucket);
+    }
+
+    fn condition_bucket(&self) -> Option<&'static ExecutionBucket> {
+        self.condition_bucket
+    }
+
+    fn firing_policy(&self) -> FiringPolicy {
+        self.firing_policy
+    }
+
+    fn _assign_condition(&mut self, cond_name: &str) {
+        // correctness has already been checked by the caller
+        self.condition_name = Some(String::from(cond_name));
+    }
+
+    // this function is a wrapper for the actual asynchronous event receiver
+    async fn event_triggered(&mut self) -> Result<Option<String>> {
+        let name = self.get_name();
+        assert!(
+            self.receiver.is_some(),
+            "uninitialized signal receiver for SignalEvent {name}",
+        );
+        let receiver = self.receiver.as_mut().unwrap();
+
+        // a closed receiver is just reported as no event
+        let count = receiver.recv().await?;
+        if count == 0 {
+            return Ok(None);
+        }
+
+        let signal = signal_name(self.signal.unwrap());
+        self.log(
+            LogType::Debug,
+            LOG_WHEN_PROC,
+            LOG_STATUS_OK,
+            &format!("signal {signal} received"),
+        );
+        let mut payload = EventPayload::new(&name);
+        payload.set_text("signal", &signal);
+        if self.fire_condition_with_payload(payload) {
+            self.log(
+                LogType::Debug,
+                LOG_WHEN_PROC,
+                LOG_STATUS_OK,
+                "condition fired successfully",
+            );
+        } else {
+            self.log(
+                LogType::Trace,
+                LOG_WHEN_PROC,
+                LOG_STATUS_MSG,
+                "condition already fired: further schedule skipped",
+            );
+        }
+
+        Ok(Some(name))
+    }
+
+    fn initial_setup(&mut self) -> Result<bool> {
+        assert!(
+            self.receiver.is_none(),
+            "signal receiver for SignalEvent {} is already initialized",
+            self.get_name(),
+        );
+        assert!(
+            self.signal.is_some(),
+            "signal not set for SignalEvent {}",
+            self.get_name(),
+        );
+
+        let signal = signal_name(self.signal.unwrap());
+        match SignalReceiver::new(self.signal.unwrap()) {
+            Ok(receiver) => {
+                self.receiver = Some(receiver);
+                self.log(
+                    LogType::Debug,
+                    LOG_WHEN_START,
+                    LOG_STATUS_OK,
+                    &format!("listening for signal {signal}"),
+                );
+                Ok(true)
+            }
+            Err(e) => {
+                self.log(
+                    LogType::Warn,
+                    LOG_WHEN_START,
+                    LOG_STATUS_FAIL,
+                    &format!("could not listen for signal {signal}: {e}"),
+                );
+                Err(e)
+            }
+        }
+    }
+
+    fn final_cleanup(&mut self) -> Result<bool> {
+        // dropping the receiver also removes the signal handler
+        if let Some(r) = self.receiver.take() {
+            drop(r);
+        }
+        self.log(
+            LogType::Debug,
+            LOG_WHEN_START,
+            LOG_STATUS_MSG,
+            "signal receiver has been reset",
+        );
+        Ok(false)
+    }
+}
+
+// end.
@@ -14,6 +14,7 @@ use async_trait::async_trait;
 use wmi::WMIConnection;
 
 use super::base::Event;
+use super::firing::{FiringPolicy, cfg_firing_policy};
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::Result;
 use crate::condition::bucket_cond::ExecutionBucket;
@@ -35,6 +36,7 @@ pub struct WmiQueryEvent {
     event_id: i64,
     event_name: String,
     condition_name: Option<String>,
+    firing_policy: FiringPolicy,
 
     // internal values
     condition_registry: Option<&'static ConditionRegistry>,
@@ -55,6 +57,7 @@ impl Hash for WmiQueryEvent {
         if let Some(s) = &self.condition_name {
             s.hash(state);
         }
+        self.firing_policy.hash(state);
 
         // specific part
         // 0 is hashed on the else branch in order to avoid that adjacent
@@ -83,6 +86,7 @@ impl Clone for WmiQueryEvent {
             // parameters
             event_name: self.event_name.clone(),
             condition_name: self.condition_name.clone(),
+            firing_policy: self.firing_policy,
 
             // internal values
             condition_registry: None,
@@ -118,6 +122,7 @@ impl WmiQueryEvent {
             // parameters
             event_name: String::from(name),
             condition_name: None,
+            firing_policy: FiringPolicy::default(),
 
             // internal values
             condition_registry: None,
@@ -165,7 +170,16 @@ impl WmiQueryEvent {
         cond_registry: &'static ConditionRegistry,
         bucket: &'static ExecutionBucket,
     ) -> Result<WmiQueryEvent> {
-        let check = vec!["type", "name", "tags", "condition", "namespace", "query"];
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "condition",
+            "debounce_seconds",
+            "throttle_seconds",
+            "namespace",
+            "query",
+        ];
         cfg_check_keys(cfgmap, &check)?;
 
         // common mandatory parameter retrieval
@@ -215,6 +229,8 @@ impl WmiQueryEvent {
             new_event.assign_condition(&v)?;
         }
 
+        new_event.firing_policy = cfg_firing_policy(cfgmap)?;
+
         // specific optional parameter initialization
         if let Some(v) = cfg_string_check_regex(cfgmap, "namespace", &RE_WMI_NAMESPACE)? {
             new_event.namespace = Some(v.replace("/", "\\"));
@@ -230,7 +246,16 @@ impl WmiQueryEvent {
     /// created and that a name is returned, which is the name of the item that
     /// _would_ be created via the equivalent call to `load_cfgmap`
     pub fn check_cfgmap(cfgmap: &CfgMap, available_conditions: &Vec<&str>) -> Result<String> {
-        let check = vec!["type", "name", "tags", "condition", "namespace", "query"];
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "condition",
+            "debounce_seconds",
+            "throttle_seconds",
+            "namespace",
+            "query",
+        ];
         cfg_check_keys(cfgmap, &check)?;
 
         // common mandatory parameter retrieval
@@ -267,6 +292,8 @@ impl WmiQueryEvent {
             ));
         }
 
+        cfg_firing_policy(cfgmap)?;
+
         // specific optional parameter check
         cfg_string_check_regex(cfgmap, "namespace", &RE_WMI_NAMESPACE)?;
 
@@ -313,6 +340,10 @@ impl Event for WmiQueryEvent {
         self.condition_bucket
     }
 
+    fn firing_policy(&self) -> FiringPolicy {
+        self.firing_policy
+    }
+
     fn _assign_condition(&mut self, cond_name: &str) {
         // correctness has already been checked by the caller
         self.condition_name = Some(String::from(cond_name));
@@ -6,13 +6,17 @@
 use parking_lot::{Mutex, RwLock};
 use rand::{Rng, rng};
 use std::io::{BufRead, Stdin, stdin};
+use std::path::{Path, PathBuf};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 
 use lazy_static::lazy_static;
+use notify::Watcher;
 
-use cfgmap::CfgValue;
+use cfgmap::{CfgMap, CfgValue};
+use chrono::{DateTime, Local, SecondsFormat};
 use clokwerk::{Scheduler, TimeUnits};
+use serde_json::{Map, Value, json};
 use single_instance::SingleInstance;
 use whoami::username;
 
@@ -35,9 +39,17 @@ use task::registry::TaskRegistry;
 use condition::bucket_cond::ExecutionBucket;
 use task::internal_task::set_command_runner;
 
+use cfghelp::{cfg_find_item, cfg_items_with_tag, cfg_map_to_json};
 use config::*;
 use constants::*;
+#[cfg(unix)]
+use utility::control;
+#[cfg(unix)]
+use utility::signals;
 use utility::logging::{LogType, init as log_init, log};
+#[cfg(unix)]
+use utility::protocol::reply_status;
+use utility::protocol::{is_json_request, json_command};
 use utility::result::{Error, Kind, Result};
 
 lazy_static! {
@@ -71,6 +83,16 @@ lazy_static! {
     // set this if the application is paused waiting for reconfiguration
     static ref APPLICATION_IS_RECONFIGURING: RwLock<bool> = RwLock::new(false);
 
+    // the currently active configuration, as a reference for introspection:
+    // references are not replaced, so that no secrets can be disclosed
+    static ref CONFIGURATION: RwLock<Option<CfgMap>> = RwLock::new(None);
+
+    // the file the currently active configuration has been loaded from
+    static ref CONFIGURATION_FILE: RwLock<Option<String>> = RwLock::new(None);
+
+    // this is to avoid overlapping reconfigurations
+    static ref RECONFIGURE_LOCK: Mutex<()> = Mutex::new(());
+
     // this is to have the input command executor only run a command at a time
     static ref INPUT_COMMAND_LOCK: Mutex<()> = Mutex::new(());
 
@@ -78,6 +100,7 @@ lazy_static! {
     static ref NO_DELAY_CONDITIONS: Vec<String> = vec![
         String::from("interval"),
         String::from("time"),
+        String::from("cron"),
         String::from("idle"),
         ];
 
@@ -152,6 +175,9 @@ fn sched_tick(rand_millis_range: Option<u64>) -> bool {
         return false;
     }
 
+    // conditions only used as inputs are evaluated again in this tick
+    CONDITION_REGISTRY.advance_tick();
+
     if let Some(names) = CONDITION_REGISTRY.condition_names() {
         for name in names {
             // go away if condition is busy
@@ -259,6 +285,40 @@ macro_rules! exit_if_fails {
     };
 }
 
+// expand the `tag:<tag>` selectors found among the arguments of a command
+// into the sorted names of the items of a section carrying that tag, as
+// found in the currently active configuration; other arguments are kept
+fn expand_tag_selectors(section: &str, args: &[&str]) -> Vec<String> {
+    let mut res: Vec<String> = Vec::new();
+    for arg in args {
+        if let Some(tag) = arg.strip_prefix(TAG_SELECTOR_PREFIX) {
+            let names = match &*CONFIGURATION.read() {
+                Some(configuration) => cfg_items_with_tag(configuration, section, tag),
+                None => Vec::new(),
+            };
+            if names.is_empty() {
+                log(
+                    LogType::Warn,
+                    LOG_EMITTER_MAIN,
+                    LOG_ACTION_RUN_COMMAND,
+                    None,
+                    LOG_WHEN_PROC,
+                    LOG_STATUS_MSG,
+                    &format!("no {section}s found with tag {tag}"),
+                );
+            }
+            for name in names {
+                if !res.contains(&name) {
+                    res.push(name);
+                }
+            }
+        } else if !res.iter().any(|x| x == arg) {
+            res.push(String::from(*arg));
+        }
+    }
+    res
+}
+
 // reset the conditions whose names are provided in a vector of &str
 fn reset_conditions(names: &[String]) -> bool {
     let mut outcome = true;
@@ -429,6 +489,7 @@ fn set_suspended_condition(name: &str, suspended: bool) {
 
 // attempt to reconfigure the application using the provided config file name
 fn reconfigure(config_file: &str) -> Result<()> {
+    let _lock = RECONFIGURE_LOCK.lock();
     if let Err(e) = check_configuration(config_file) {
         log(
             LogType::Error,
@@ -465,6 +526,8 @@ fn reconfigure(config_file: &str) -> Result<()> {
             *APPLICATION_IS_RECONFIGURING.write() = false;
             match res {
                 Ok(_) => {
+                    *CONFIGURATION.write() = read_uninterpolated_configuration(config_file).ok();
+                    *CONFIGURATION_FILE.write() = Some(String::from(config_file));
                     log(
                         LogType::Info,
                         LOG_EMITTER_MAIN,
@@ -506,8 +569,177 @@ fn reconfigure(config_file: &str) -> Result<()> {
     Ok(())
 }
 
-// attempt to trigger an event
-fn trigger_event(name: &str) {
+// reload the file that the active configuration has been loaded from: this
+// is used when a reload is requested via SIGHUP or the file has changed, and
+// an invalid file is just reported in the log by `reconfigure()`
+fn reload_configuration(reason: &str) {
+    let Some(config_file) = CONFIGURATION_FILE.read().clone() else {
+        return;
+    };
+    log(
+        LogType::Info,
+        LOG_EMITTER_MAIN,
+        LOG_ACTION_RECONFIGURE,
+        None,
+        LOG_WHEN_START,
+        LOG_STATUS_MSG,
+        &format!("{reason}: reloading configuration file `{config_file}`"),
+    );
+    let _ = reconfigure(&config_file);
+}
+
+// watch the active configuration file along with the included ones, and
+// reload the configuration once they have not been modified for a while, so
+// that a file being written is not read halfway: if the file is changed by a
+// `configure` command, the new one is watched, and if the contents of all the
+// files are the same as the last time nothing is reloaded
+fn watch_configuration() -> Result<()> {
+    // the files that make up the configuration, and the directories to be
+    // watched: if the main file cannot be read, at least its directory is
+    fn _sources(config_file: &Path) -> (Vec<PathBuf>, Vec<PathBuf>) {
+        configuration_files(&config_file.to_string_lossy()).unwrap_or_else(|_| {
+            let dir = match config_file.parent() {
+                Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+                _ => PathBuf::from("."),
+            };
+            (vec![config_file.to_path_buf()], vec![dir])
+        })
+    }
+
+    // the contents of the files, to be compared with later ones
+    fn _snapshot(files: &[PathBuf]) -> Vec<(PathBuf, Option<Vec<u8>>)> {
+        files
+            .iter()
+            .map(|f| (f.clone(), std::fs::read(f).ok()))
+            .collect()
+    }
+
+    // watch the directories that were not watched yet, and stop watching
+    // the ones that are not needed anymore
+    fn _watch_dirs(
+        watcher: &mut notify::RecommendedWatcher,
+        watched_dirs: &mut Vec<PathBuf>,
+        dirs: Vec<PathBuf>,
+    ) {
+        for dir in watched_dirs.iter() {
+            if !dirs.contains(dir) {
+                let _ = watcher.unwatch(dir);
+            }
+        }
+        watched_dirs.retain(|dir| dirs.contains(dir));
+        for dir in dirs {
+            if watched_dirs.contains(&dir) {
+                continue;
+            }
+            if let Err(e) = watcher.watch(&dir, notify::RecursiveMode::NonRecursive) {
+                log(
+                    LogType::Warn,
+                    LOG_EMITTER_MAIN,
+                    LOG_ACTION_RECONFIGURE,
+                    None,
+                    LOG_WHEN_START,
+                    LOG_STATUS_FAIL,
+                    &format!(
+                        "cannot watch configuration directory `{}`: {e}",
+                        dir.display(),
+                    ),
+                );
+            } else {
+                watched_dirs.push(dir);
+            }
+        }
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    let stable = Duration::from_millis(CONFIG_WATCH_STABLE_MILLISECONDS);
+    let poll = Duration::from_millis(CONFIG_WATCH_POLL_MILLISECONDS);
+
+    thread::spawn(move || {
+        let mut watched: Option<PathBuf> = None;
+        let mut watched_dirs: Vec<PathBuf> = Vec::new();
+        let mut contents: Vec<(PathBuf, Option<Vec<u8>>)> = Vec::new();
+        let mut changed_at: Option<Instant> = None;
+        loop {
+            // follow the active configuration file, and watch the directories
+            // so that files replaced by editors and new drop-in files are
+            // still noticed
+            let Some(config_file) = CONFIGURATION_FILE.read().clone().map(PathBuf::from) else {
+                thread::sleep(poll);
+                continue;
+            };
+            if watched.as_ref() != Some(&config_file) {
+                let (files, dirs) = _sources(&config_file);
+                _watch_dirs(&mut watcher, &mut watched_dirs, dirs);
+                log(
+                    LogType::Debug,
+                    LOG_EMITTER_MAIN,
+                    LOG_ACTION_RECONFIGURE,
+                    None,
+                    LOG_WHEN_START,
+                    LOG_STATUS_OK,
+                    &format!(
+                        "watching configuration file `{}` ({} file(s) in total)",
+                        config_file.display(),
+                        files.len(),
+                    ),
+                );
+                contents = _snapshot(&files);
+                changed_at = None;
+                watched = Some(config_file.clone());
+            }
+
+            // besides the known files, any new TOML file may be an include
+            match rx.recv_timeout(poll) {
+                Ok(Ok(evt)) => {
+                    if !evt.kind.is_access()
+                        && evt.paths.iter().any(|p| {
+                            p.extension().is_some_and(|x| x == "toml")
+                                || contents.iter().any(|(f, _)| f.file_name() == p.file_name())
+                        })
+                    {
+                        changed_at = Some(Instant::now());
+                    }
+                }
+                Ok(Err(_)) | Err(std::sync::mpsc::RecvTimeoutError::Timeout) => (),
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+
+            if changed_at.is_some_and(|t| t.elapsed() >= stable) {
+                changed_at = None;
+                let (files, dirs) = _sources(&config_file);
+                let new_contents = _snapshot(&files);
+                if new_contents.first().is_some_and(|(_, c)| c.is_some())
+                    && new_contents != contents
+                {
+                    contents = new_contents;
+                    _watch_dirs(&mut watcher, &mut watched_dirs, dirs);
+                    reload_configuration("configuration file changed");
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+// save the state of all conditions, only logging possible errors
+fn save_condition_state() {
+    if let Err(e) = CONDITION_REGISTRY.save_state() {
+        log(
+            LogType::Warn,
+            LOG_EMITTER_MAIN,
+            LOG_ACTION_MAIN_EXIT,
+            None,
+            LOG_WHEN_END,
+            LOG_STATUS_FAIL,
+            &format!("could not save condition state: {e}"),
+        );
+    }
+}
+
+// attempt to trigger an event, passing on the provided arguments
+fn trigger_event(name: &str, args: &[String]) {
     if EVENT_REGISTRY.has_event_triggerable(name) {
         log(
             LogType::Debug,
@@ -518,7 +750,7 @@ fn trigger_event(name: &str) {
             LOG_STATUS_OK,
             &format!("triggering event {name}"),
         );
-        if EVENT_REGISTRY.trigger_event(name) {
+        if EVENT_REGISTRY.trigger_event(name, args) {
             log(
                 LogType::Info,
                 LOG_EMITTER_MAIN,
@@ -552,8 +784,171 @@ fn trigger_event(name: &str) {
     }
 }
 
-// this function actually interprets and runs a command, passed as a string
+// convert an instant to a human readable local time for reporting purposes
+fn instant_to_string(instant: Instant) -> String {
+    let elapsed = Instant::now().saturating_duration_since(instant);
+    let time: DateTime<Local> = (SystemTime::now() - elapsed).into();
+    time.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+// list the names of the items of the provided kind, sorted alphabetically;
+// when a `tag:<tag>` selector is given instead, the names of the items of
+// all kinds carrying that tag are reported, grouped by section
+fn query_list(args: &[&str]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(Error::new(
+            Kind::Invalid,
+            "invalid number of arguments for command `list`",
+        ));
+    }
+    if let Some(tag) = args[0].strip_prefix(TAG_SELECTOR_PREFIX) {
+        let mut res = Map::new();
+        if let Some(configuration) = &*CONFIGURATION.read() {
+            for section in ["task", "condition", "event"] {
+                let names = cfg_items_with_tag(configuration, section, tag);
+                res.insert(String::from(section), json!(names));
+            }
+        }
+        return Ok(Value::Object(res));
+    }
+    let mut names = match args[0] {
+        "conditions" => CONDITION_REGISTRY.condition_names(),
+        "tasks" => TASK_REGISTRY.task_names(),
+        "events" => EVENT_REGISTRY.event_names(),
+        t => {
+            return Err(Error::new(
+                Kind::Invalid,
+                &format!("invalid item type for command `list`: `{t}`"),
+            ));
+        }
+    }
+    .unwrap_or_default();
+    names.sort();
+    Ok(json!(names))
+}
+
+// report the state of a condition: if the condition is busy its internal
+// state cannot be read, and only the busy flag is reported as meaningful
+fn query_status(args: &[&str]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(Error::new(
+            Kind::Invalid,
+            "invalid number of arguments for command `status`",
+        ));
+    }
+    let name = args[0];
+    if !CONDITION_REGISTRY.has_condition(name) {
+        return Err(Error::new(
+            Kind::Unavailable,
+            &format!("condition {name} not found"),
+        ));
+    }
+    if let Some(state) = CONDITION_REGISTRY.condition_state(name) {
+        Ok(json!({
+            "name": name,
+            "type": state.cond_type,
+            "busy": false,
+            "suspended": state.suspended,
+            "recurring": state.recurring,
+            "has_succeeded": state.has_succeeded,
+            "last_checked": state.last_checked.map(instant_to_string),
+            "last_succeeded": state.last_succeeded.map(instant_to_string),
+            "left_retries": state.left_retries,
+            "tasks": state.task_names,
+        }))
+    } else {
+        Ok(json!({
+            "name": name,
+            "busy": true,
+        }))
+    }
+}
+
+// report the configuration of all the items with the provided name, as
+// found in the currently active configuration
+fn query_describe(args: &[&str]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(Error::new(
+            Kind::Invalid,
+            "invalid number of arguments for command `describe`",
+        ));
+    }
+    let name = args[0];
+    let mut res = Map::new();
+    if let Some(configuration) = &*CONFIGURATION.read() {
+        for section in ["task", "condition", "event"] {
+            if let Some(item) = cfg_find_item(configuration, section, name) {
+                res.insert(String::from(section), cfg_map_to_json(item));
+            }
+        }
+    }
+    if res.is_empty() {
+        Err(Error::new(
+            Kind::Unavailable,
+            &format!("item {name} not found"),
+        ))
+    } else {
+        Ok(Value::Object(res))
+    }
+}
+
+// interpret a command that only returns information: `None` is returned if
+// the command is not one of these, so that it can be handled as an action
+fn run_query(cmd: &str, args: &[&str]) -> Option<Result<Value>> {
+    match cmd {
+        "list" => Some(query_list(args)),
+        "status" => Some(query_status(args)),
+        "describe" => Some(query_describe(args)),
+        _ => None,
+    }
+}
+
+// interpret and run a command, passed as a string, returning its payload
+// if any: queries do not need to take the command lock, as they cannot
+// modify the state of the application
+pub fn run_command_payload(line: &str) -> Result<(bool, Option<Value>)> {
+    let v: Vec<&str> = line.split_whitespace().collect();
+    if let Some(cmd) = v.first()
+        && let Some(res) = run_query(cmd, &v[1..])
+    {
+        match res {
+            Ok(payload) => {
+                log(
+                    LogType::Debug,
+                    LOG_EMITTER_MAIN,
+                    LOG_ACTION_RUN_COMMAND,
+                    None,
+                    LOG_WHEN_PROC,
+                    LOG_STATUS_OK,
+                    &format!("query `{cmd}` result: {payload}"),
+                );
+                Ok((true, Some(payload)))
+            }
+            Err(e) => {
+                log(
+                    LogType::Error,
+                    LOG_EMITTER_MAIN,
+                    LOG_ACTION_RUN_COMMAND,
+                    None,
+                    LOG_WHEN_PROC,
+                    LOG_STATUS_ERR,
+                    &format!("query `{cmd}` failed: {e}"),
+                );
+                Err(e)
+            }
+        }
+    } else {
+        run_action(line).map(|performed| (performed, None))
+    }
+}
+
+// this is the command runner that does not need the payload
 pub fn run_command(line: &str) -> Result<bool> {
+    run_command_payload(line).map(|(performed, _)| performed)
+}
+
+// this function actually interprets and runs a command, passed as a string
+fn run_action(line: &str) -> Result<bool> {
     // first of all, lock the command execution feature to avoid overlaps
     let _l = INPUT_COMMAND_LOCK.lock();
 
@@ -770,9 +1165,9 @@ pub fn run_command(line: &str) -> Result<bool> {
                     // another name; however creating `v` here allows for
                     // moving it into the new thread without having problems
                     // concerning its lifetime
-                    let mut v: Vec<String> = Vec::new();
-                    for a in args {
-                        v.push(String::from(*a));
+                    let v = expand_tag_selectors("condition", args);
+                    if v.is_empty() {
+                        return Ok(false);
                     }
                     log(
                         LogType::Debug,
@@ -822,10 +1217,16 @@ pub fn run_command(line: &str) -> Result<bool> {
                         LOG_STATUS_MSG,
                         &format!("attempting to suspend condition {}", args[0]),
                     );
+                    // a tag selector may refer to several conditions
+                    let v = expand_tag_selectors("condition", args);
+                    if v.is_empty() {
+                        return Ok(false);
+                    }
                     // same considerations as above
-                    let arg = args[0].to_string();
                     thread::spawn(move || {
-                        set_suspended_condition(&arg, true);
+                        for name in v {
+                            set_suspended_condition(&name, true);
+                        }
                     });
                     Ok(true)
                 }
@@ -853,11 +1254,17 @@ pub fn run_command(line: &str) -> Result<bool> {
                         LOG_STATUS_MSG,
                         &format!("attempting to resume condition {}", args[0]),
                     );
+                    // a tag selector may refer to several conditions
+                    let v = expand_tag_selectors("condition", args);
+                    if v.is_empty() {
+                        return Ok(false);
+                    }
                     // same considerations as above
                     // condition is freed and the command can be executed
-                    let arg = args[0].to_string();
                     thread::spawn(move || {
-                        set_suspended_condition(&arg, false);
+                        for name in v {
+                            set_suspended_condition(&name, false);
+                        }
                     });
                     Ok(true)
                 }
@@ -886,7 +1293,7 @@ pub fn run_command(line: &str) -> Result<bool> {
                 Ok(true)
             }
             "trigger" => {
-                if args.len() != 1 {
+                if args.is_empty() {
                     let msg = "invalid number of arguments for command `trigger`";
                     log(
                         LogType::Error,
@@ -910,8 +1317,9 @@ pub fn run_command(line: &str) -> Result<bool> {
                     );
                     // same considerations as above
                     let arg = args[0].to_string();
+                    let event_args: Vec<String> = args[1..].iter().map(|x| x.to_string()).collect();
                     thread::spawn(move || {
-                        trigger_event(&arg);
+                        trigger_event(&arg, &event_args);
                     });
                     Ok(true)
                 }
@@ -958,9 +1366,19 @@ fn command_loop() {
     let rest_time = Duration::from_millis(MAIN_STDIN_READ_WAIT_MILLISECONDS);
     let mut handle = STDIN.lock();
 
-    while let Ok(_n) = handle.read_line(&mut buffer) {
-        // we will decide what to do with this, for now ignore it and just log
-        let _res = run_command(&buffer);
+    // stop reading at the end of input (e.g. when stdin is `/dev/null`, as
+    // it happens for services): commands may still come from the socket
+    while let Ok(n) = handle.read_line(&mut buffer)
+        && n > 0
+    {
+        // JSON requests are answered on stdout, while the outcome of plain
+        // text commands is only reported in the log, except for the data
+        // returned by queries that is printed on stdout as well
+        if is_json_request(&buffer) {
+            println!("{}", json_command(&buffer, run_command_payload));
+        } else if let Ok((_, Some(payload))) = run_command_payload(&buffer) {
+            println!("{payload}");
+        }
 
         // clear the buffer immediately after consuming the line
         buffer.clear();
@@ -969,6 +1387,8 @@ fn command_loop() {
 }
 
 // argument parsing and command execution: doc comments are used by clap
+#[cfg(unix)]
+use clap::Subcommand;
 use clap::{Parser, ValueEnum};
 
 /// A lightweight task scheduler and automation tool
@@ -1022,9 +1442,38 @@ struct Args {
     #[arg(short = 'J', long, group = "logformat")]
     log_json: bool,
 
+    /// Reload the configuration file automatically when it changes
+    #[arg(short, long)]
+    watch_config: bool,
+
+    /// Accept commands on a control socket (optionally at the given path)
+    #[cfg(unix)]
+    #[arg(short = 'S', long, value_name = "PATH", require_equals = true)]
+    socket: Option<Option<String>>,
+
     /// Path to configuration file
     #[arg(value_name = "CONFIG")]
     config: Option<String>,
+
+    #[cfg(unix)]
+    #[command(subcommand)]
+    mode: Option<Mode>,
+}
+
+// alternative operating modes, that do not start the scheduler
+#[cfg(unix)]
+#[derive(Subcommand)]
+enum Mode {
+    /// Send a command to a running instance through its control socket
+    Ctl {
+        /// Path to the control socket of the running instance
+        #[arg(short = 'S', long, value_name = "PATH")]
+        socket: Option<String>,
+
+        /// Command to send, followed by its arguments
+        #[arg(value_name = "COMMAND", required = true, trailing_var_arg = true)]
+        command: Vec<String>,
+    },
 }
 
 // this is redundant but necessary for clap (the `type` alias does not work)
@@ -1037,11 +1486,53 @@ enum LogLevel {
     Error,
 }
 
+// send a single command to a running instance and exit: the exit code is 0
+// if the command was performed, 1 if it was ignored and 2 on errors
+#[cfg(unix)]
+fn control_client(quiet: bool, socket: Option<String>, command: &[String]) -> ! {
+    let path = socket.map_or_else(control::default_socket_path, PathBuf::from);
+    match control::send_command(&path, &command.join(" ")) {
+        Ok(reply) => {
+            if !quiet {
+                println!("{reply}");
+            }
+            let status = if is_json_request(&reply) {
+                match reply_status(&reply).as_deref() {
+                    Some(PROTOCOL_STATUS_OK) => CONTROL_REPLY_OK,
+                    Some(PROTOCOL_STATUS_FAIL) => CONTROL_REPLY_FAIL,
+                    _ => CONTROL_REPLY_ERR,
+                }
+            } else {
+                reply.split(' ').next().unwrap_or_default()
+            };
+            if status == CONTROL_REPLY_OK {
+                std::process::exit(0);
+            } else if status == CONTROL_REPLY_FAIL {
+                std::process::exit(1);
+            } else {
+                std::process::exit(2);
+            }
+        }
+        Err(e) => {
+            if !quiet {
+                eprintln!("{APP_NAME} error: {e}");
+            }
+            std::process::exit(2);
+        }
+    }
+}
+
 // entry point
 fn main() {
     // parse arguments
     let args = Args::parse();
 
+    // the client mode does not need anything else than the arguments
+    #[cfg(unix)]
+    if let Some(Mode::Ctl { socket, command }) = args.mode {
+        control_client(args.quiet, socket, &command);
+    }
+
     // check that no other instance is running
     let instance = exit_if_fails!(args.quiet, SingleInstance::new(&INSTANCE_GUID));
 
@@ -1080,6 +1571,8 @@ fn main() {
             "lua_httpreq",
             #[cfg(feature = "lua_unsafe")]
             "lua_unsafe",
+            #[cfg(feature = "http")]
+            "http",
         ];
         println!("options: {}", options.join(" "));
         std::process::exit(0);
@@ -1131,6 +1624,41 @@ fn main() {
         })
     );
 
+    // SIGTERM is handled the same way, while SIGHUP causes the configuration
+    // file to be reloaded as most daemons do, unless an event has claimed it
+    #[cfg(unix)]
+    exit_if_fails!(
+        args.quiet,
+        signals::spawn_handler(&[libc::SIGTERM, libc::SIGHUP], |signum| {
+            if signum == libc::SIGHUP {
+                if signals::is_claimed(libc::SIGHUP) {
+                    log(
+                        LogType::Debug,
+                        LOG_EMITTER_MAIN,
+                        LOG_ACTION_RECONFIGURE,
+                        None,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_MSG,
+                        "hangup signal handled by events: configuration not reloaded",
+                    );
+                } else {
+                    thread::spawn(|| reload_configuration("caught hangup signal"));
+                }
+                return;
+            }
+            log(
+                LogType::Warn,
+                LOG_EMITTER_MAIN,
+                LOG_ACTION_MAIN_EXIT,
+                None,
+                LOG_WHEN_END,
+                LOG_STATUS_MSG,
+                "caught termination signal: terminating application",
+            );
+            *APPLICATION_MUST_EXIT.write() = true;
+        })
+    );
+
     // write a banner to the log file, stating app name and version
     log(
         LogType::Info,
@@ -1161,6 +1689,22 @@ fn main() {
         .as_bool()
         .unwrap_or(&DEFAULT_RANDOMIZE_CHECKS_WITHIN_TICKS);
 
+    // set the file to persist condition state to, if any: an invalid state
+    // file is not fatal, because it will be overwritten with a new state
+    if let Some(state_file) = configuration.get("state_file").and_then(|v| v.as_str())
+        && let Err(e) = CONDITION_REGISTRY.set_state_file(Path::new(state_file))
+    {
+        log(
+            LogType::Warn,
+            LOG_EMITTER_MAIN,
+            LOG_ACTION_MAIN_START,
+            None,
+            LOG_WHEN_INIT,
+            LOG_STATUS_FAIL,
+            &format!("could not restore condition state from `{state_file}`: {e}"),
+        );
+    }
+
     // set the unique command runner for internal command based tasks
     exit_if_fails!(args.quiet, set_command_runner(run_command));
 
@@ -1176,6 +1720,13 @@ fn main() {
             scheduler_tick_seconds,
         )
     );
+    *CONFIGURATION.write() = read_uninterpolated_configuration(&config).ok();
+    *CONFIGURATION_FILE.write() = Some(config);
+
+    // watch the configuration file for changes if requested
+    if args.watch_config {
+        exit_if_fails!(args.quiet, watch_configuration());
+    }
 
     // first of all check whether the application is started in paused mode
     // and if so check the appropriate flag and emit an info log message
@@ -1201,6 +1752,20 @@ fn main() {
         );
     }
 
+    // open the control socket if requested, using the default path if none
+    // is provided: this must succeed, as the user explicitly asked for it
+    #[cfg(unix)]
+    let control_socket = args
+        .socket
+        .map(|p| p.map_or_else(control::default_socket_path, PathBuf::from));
+    #[cfg(unix)]
+    if let Some(path) = &control_socket {
+        exit_if_fails!(
+            args.quiet,
+            control::start_listener(path, run_command_payload)
+        );
+    }
+
     // add a thread for stdin interpreter (no args function thus no closure)
     // this thread can be abruptly killed without worrying, so it is not added
     // to the threads to wait for before leaving
@@ -1250,6 +1815,11 @@ fn main() {
                     LOG_STATUS_OK,
                     "application exiting: all activity will be forced to stop",
                 );
+                save_condition_state();
+                #[cfg(unix)]
+                if let Some(path) = &control_socket {
+                    control::remove_socket(path);
+                }
                 std::process::exit(1);
             } else {
                 log(
@@ -1280,6 +1850,15 @@ fn main() {
                         &format!("error stopping event listener: {e}"),
                     );
                 }
+
+                // persist the state of conditions, if requested
+                save_condition_state();
+
+                // remove the control socket, if any
+                #[cfg(unix)]
+                if let Some(path) = &control_socket {
+                    control::remove_socket(path);
+                }
                 break;
             }
         }
@@ -9,6 +9,10 @@
 //! and read/write access to its ID in the form of an unsigned integer. A zero
 //! ID is used for _inactive_ tasks.
 
+use std::collections::HashMap;
+
+use super::graph::TaskDependencies;
+use crate::event::payload::EventPayload;
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::Result;
 use crate::constants::*;
@@ -29,6 +33,24 @@ pub trait Task: Send {
     /// Return the ID of the `Task`
     fn get_id(&self) -> i64;
 
+    /// Return the relationships with the tasks this `Task` depends on
+    fn dependencies(&self) -> TaskDependencies;
+
+    /// Receive the variables captured by tasks that ran before in the same
+    /// run, to be made available to the next execution: tasks that cannot
+    /// use them just ignore them
+    fn set_received_variables(&mut self, _vars: &HashMap<String, String>) {}
+
+    /// Receive the payload attached by the event that fired the triggering
+    /// condition, if any: tasks that cannot use it just ignore it
+    fn set_event_payload(&mut self, _payload: Option<&EventPayload>) {}
+
+    /// Return the name and value of the variable captured by the last run,
+    /// if this `Task` captures one and a value was actually found
+    fn captured_variable(&self) -> Option<(String, String)> {
+        None
+    }
+
     /// Tell whether or not another `Task` is equal to this
     fn eq(&self, other: &dyn Task) -> bool {
         self._hash() == other._hash()
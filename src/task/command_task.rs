@@ -30,6 +30,7 @@ use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
 use itertools::Itertools;
+use regex::Regex;
 
 use subprocess::{Exec, Redirection};
 
@@ -37,7 +38,9 @@ use cfgmap::CfgMap;
 
 // we implement the Task trait here in order to enqueue tasks
 use super::base::Task;
+use super::graph::{TaskDependencies, cfg_task_dependencies};
 use crate::common::cmditem::*;
+use crate::event::payload::EventPayload;
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::{Error, Kind, Result};
 use crate::{cfg_mandatory, constants::*};
@@ -52,6 +55,7 @@ pub struct CommandTask {
     // common members
     task_id: i64,
     task_name: String,
+    dependencies: TaskDependencies,
 
     // specific members
     // parameters
@@ -63,6 +67,8 @@ pub struct CommandTask {
     case_sensitive: bool,
     set_envvars: bool,
     environment_vars: HashMap<String, String>,
+    capture_var: Option<String>,
+    capture_regexp: Option<String>,
 
     // internal values
     success_stdout: Option<String>,
@@ -78,12 +84,16 @@ pub struct CommandTask {
     _process_status: u32,
     _process_failed: bool,
     _process_duration: Duration,
+    _captured_value: Option<String>,
+    _received_vars: HashMap<String, String>,
+    _event_payload: Option<EventPayload>,
 }
 
 // implement the hash protocol
 impl Hash for CommandTask {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.task_name.hash(state);
+        self.dependencies.hash(state);
         self.command.hash(state);
         self.args.hash(state);
         self.startup_dir.hash(state);
@@ -93,6 +103,8 @@ impl Hash for CommandTask {
         self.include_env.hash(state);
         self.set_envvars.hash(state);
         self.timeout.hash(state);
+        self.capture_var.hash(state);
+        self.capture_regexp.hash(state);
 
         // 0 is hashed on the else branch because if we get two items, for
         // instance, one of which has only success_stdout defined as a string
@@ -173,6 +185,7 @@ impl CommandTask {
 
             // parameters
             task_name: String::from(name),
+            dependencies: TaskDependencies::default(),
 
             // specific members initialization
             // parameters
@@ -192,6 +205,8 @@ impl CommandTask {
             failure_stderr: None,
             failure_status: None,
             timeout: None,
+            capture_var: None,
+            capture_regexp: None,
 
             // internal values
             _process_stdout: String::new(),
@@ -199,6 +214,25 @@ impl CommandTask {
             _process_status: 0,
             _process_failed: false,
             _process_duration: Duration::ZERO,
+            _captured_value: None,
+            _received_vars: HashMap::new(),
+            _event_payload: None,
+        }
+    }
+
+    // extract the value to be captured from the output: when a regular
+    // expression is given, the first capture group is used if present, and
+    // the whole match otherwise; unmatched output does not set the variable
+    fn capture_output(&self) -> Option<String> {
+        let output = self._process_stdout.trim();
+        if let Some(re) = &self.capture_regexp {
+            let captures = Regex::new(re).ok()?.captures(output)?;
+            captures
+                .get(1)
+                .or(captures.get(0))
+                .map(|m| String::from(m.as_str()))
+        } else {
+            Some(String::from(output))
         }
     }
 
@@ -362,6 +396,9 @@ impl CommandTask {
             "type",
             "name",
             "tags",
+            "after",
+            "on_success",
+            "on_failure",
             "command",
             "command_arguments",
             "startup_path",
@@ -378,6 +415,8 @@ impl CommandTask {
             "failure_stderr",
             "failure_status",
             "timeout_seconds",
+            "capture_stdout",
+            "capture_regular_expression",
         ];
         cfg_check_keys(cfgmap, &check)?;
 
@@ -416,6 +455,9 @@ impl CommandTask {
             ));
         }
 
+        // dependencies on other tasks are common to all task types
+        new_task.dependencies = cfg_task_dependencies(cfgmap)?;
+
         // specific optional parameter initialization
         if let Some(v) = cfg_bool(cfgmap, "match_exact")? {
             new_task.match_exact = v;
@@ -496,6 +538,16 @@ impl CommandTask {
             new_task.timeout = Some(Duration::from_secs(v as u64));
         }
 
+        // the output can only be filtered when it is captured
+        new_task.capture_var = cfg_string_check_regex(cfgmap, "capture_stdout", &RE_VAR_NAME)?;
+        let cur_key = "capture_regular_expression";
+        if let Some(v) = cfg_string(cfgmap, cur_key)? {
+            if new_task.capture_var.is_none() || Regex::new(&v).is_err() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_VALUE));
+            }
+            new_task.capture_regexp = Some(v);
+        }
+
         Ok(new_task)
     }
 
@@ -510,6 +562,9 @@ impl CommandTask {
             "type",
             "name",
             "tags",
+            "after",
+            "on_success",
+            "on_failure",
             "command",
             "command_arguments",
             "startup_path",
@@ -526,6 +581,8 @@ impl CommandTask {
             "failure_stderr",
             "failure_status",
             "timeout_seconds",
+            "capture_stdout",
+            "capture_regular_expression",
         ];
         cfg_check_keys(cfgmap, &check)?;
 
@@ -562,6 +619,9 @@ impl CommandTask {
             ));
         }
 
+        // dependencies are checked against existing tasks later
+        cfg_task_dependencies(cfgmap)?;
+
         cfg_bool(cfgmap, "match_exact")?;
         cfg_bool(cfgmap, "match_regular_expression")?;
         cfg_bool(cfgmap, "case_sensitive")?;
@@ -622,6 +682,14 @@ impl CommandTask {
 
         cfg_int_check_above_eq(cfgmap, "timeout_seconds", 0)?;
 
+        let capture = cfg_string_check_regex(cfgmap, "capture_stdout", &RE_VAR_NAME)?;
+        let cur_key = "capture_regular_expression";
+        if let Some(v) = cfg_string(cfgmap, cur_key)?
+            && (capture.is_none() || Regex::new(&v).is_err())
+        {
+            return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_VALUE));
+        }
+
         Ok(name)
     }
 }
@@ -637,6 +705,19 @@ impl Task for CommandTask {
     fn get_id(&self) -> i64 {
         self.task_id
     }
+    fn dependencies(&self) -> TaskDependencies {
+        self.dependencies.clone()
+    }
+
+    fn set_received_variables(&mut self, vars: &HashMap<String, String>) {
+        self._received_vars = vars.clone();
+    }
+    fn set_event_payload(&mut self, payload: Option<&EventPayload>) {
+        self._event_payload = payload.cloned();
+    }
+    fn captured_variable(&self) -> Option<(String, String)> {
+        Some((self.capture_var.clone()?, self._captured_value.clone()?))
+    }
 
     /// Return a hash of this item for comparison
     fn _hash(&self) -> u64 {
@@ -680,6 +761,16 @@ impl Task for CommandTask {
         if self.set_envvars {
             temp_env.insert(ENVVAR_NAME_COND.to_string(), String::from(trigger_name));
             temp_env.insert(ENVVAR_NAME_TASK.to_string(), self.task_name.clone());
+            if let Some(payload) = &self._event_payload {
+                for (var, value) in payload.environment(&ENVVAR_NAME_EVENT) {
+                    temp_env.insert(var, value);
+                }
+            }
+        }
+
+        // then the variables captured by tasks that ran before in the same run
+        for (var, value) in self._received_vars.iter() {
+            temp_env.insert(var.clone(), value.clone());
         }
 
         // at last insert user supplied variables
@@ -715,6 +806,7 @@ impl Task for CommandTask {
         self._process_status = 0;
         self._process_stderr = String::new();
         self._process_stdout = String::new();
+        self._captured_value = None;
         let startup_time = SystemTime::now();
 
         let process = Exec::cmd(self.command.as_os_str())
@@ -801,6 +893,12 @@ impl Task for CommandTask {
                 self._process_failed = ck_process_failed;
                 failure_reason = ck_failure_reason;
 
+                // the output is captured whatever the outcome, as the tasks
+                // that receive it may depend on this one in several ways
+                if self.capture_var.is_some() {
+                    self._captured_value = self.capture_output();
+                }
+
                 self.log(
                     log_severity,
                     log_when,
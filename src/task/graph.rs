@@ -0,0 +1,140 @@
+//! Dependencies between tasks
+//!
+//! A task can declare that it has to be run after other tasks, using three
+//! kinds of relationship, each listing the names of the _upstream_ tasks:
+//!
+//! * `after` - the task is run after the listed tasks have finished,
+//!   regardless of their outcome
+//! * `on_success` - the task is only run if all the listed tasks succeeded
+//! * `on_failure` - the task is only run if at least one of the listed tasks
+//!   failed, which is useful for cleanup tasks.
+//!
+//! When a condition runs tasks that have dependencies, the tasks form a graph
+//! in which independent branches are run simultaneously, and the tasks that
+//! depend on other ones wait for them to finish. Only the tasks associated to
+//! the condition are considered: an upstream task that is not run never has
+//! an outcome, and therefore never satisfies `on_success` or `on_failure`.
+
+use std::collections::HashMap;
+
+use cfgmap::CfgMap;
+
+use crate::cfghelp::*;
+use crate::constants::*;
+use crate::utility::result::{Error, Kind, Result};
+
+/// The relationships of a task with the tasks it depends on
+#[derive(Clone, Default, Hash)]
+pub struct TaskDependencies {
+    pub after: Vec<String>,
+    pub on_success: Vec<String>,
+    pub on_failure: Vec<String>,
+}
+
+impl TaskDependencies {
+    /// Return `true` if the task does not depend on any other task
+    pub fn is_empty(&self) -> bool {
+        self.after.is_empty() && self.on_success.is_empty() && self.on_failure.is_empty()
+    }
+
+    /// Return the names of all the upstream tasks, once each
+    pub fn upstream(&self) -> Vec<String> {
+        let mut res: Vec<String> = Vec::new();
+        for name in self
+            .after
+            .iter()
+            .chain(self.on_success.iter())
+            .chain(self.on_failure.iter())
+        {
+            if !res.contains(name) {
+                res.push(name.clone());
+            }
+        }
+        res
+    }
+
+    /// Tell whether the task can run given the outcomes of upstream tasks
+    ///
+    /// The outcomes map associates the names of finished tasks to `true` on
+    /// success, `false` on failure or error, and `None` when the outcome is
+    /// undefined or the task has been skipped.
+    pub fn allows(&self, outcomes: &HashMap<String, Option<bool>>) -> bool {
+        self.on_success
+            .iter()
+            .all(|x| outcomes.get(x) == Some(&Some(true)))
+            && (self.on_failure.is_empty()
+                || self
+                    .on_failure
+                    .iter()
+                    .any(|x| outcomes.get(x) == Some(&Some(false))))
+    }
+}
+
+/// Retrieve the dependencies of a task from its configuration
+pub fn cfg_task_dependencies(cfgmap: &CfgMap) -> Result<TaskDependencies> {
+    let mut res = TaskDependencies::default();
+    if let Some(v) = cfg_vec_string_check_regex(cfgmap, "after", &RE_TASK_NAME)? {
+        res.after = v;
+    }
+    if let Some(v) = cfg_vec_string_check_regex(cfgmap, "on_success", &RE_TASK_NAME)? {
+        res.on_success = v;
+    }
+    if let Some(v) = cfg_vec_string_check_regex(cfgmap, "on_failure", &RE_TASK_NAME)? {
+        res.on_failure = v;
+    }
+    Ok(res)
+}
+
+/// Verify a dependency graph
+///
+/// The graph maps every known task name to the names of its upstream tasks:
+/// an error is returned if a task depends on an unknown task or if there are
+/// circular dependencies.
+pub fn check_task_graph(graph: &HashMap<String, Vec<String>>) -> Result<()> {
+    for (name, upstream) in graph.iter() {
+        for u in upstream {
+            if !graph.contains_key(u) {
+                return Err(Error::new(
+                    Kind::Invalid,
+                    &format!("{ERR_TASK_UNKNOWN_DEPENDENCY}: {name} -> {u}"),
+                ));
+            }
+        }
+    }
+
+    // depth first search, where tasks on the current path are marked as
+    // `false` and fully explored ones as `true`: reaching a task that is
+    // on the current path means that there is a cycle
+    fn visit(
+        name: &str,
+        graph: &HashMap<String, Vec<String>>,
+        visited: &mut HashMap<String, bool>,
+    ) -> Option<String> {
+        match visited.get(name) {
+            Some(true) => return None,
+            Some(false) => return Some(String::from(name)),
+            None => (),
+        }
+        visited.insert(String::from(name), false);
+        for u in graph.get(name).unwrap() {
+            if let Some(found) = visit(u, graph, visited) {
+                return Some(found);
+            }
+        }
+        visited.insert(String::from(name), true);
+        None
+    }
+
+    let mut visited: HashMap<String, bool> = HashMap::new();
+    for name in graph.keys() {
+        if let Some(found) = visit(name, graph, &mut visited) {
+            return Err(Error::new(
+                Kind::Invalid,
+                &format!("{ERR_TASK_DEPENDENCY_CYCLE}: {found}"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+// end.
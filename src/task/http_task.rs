@@ -0,0 +1,712 @@
+//! Define an HTTP request based task
+//!
+//! This type of task performs a single HTTP(S) request, which is useful for
+//! webhooks and health pings that would otherwise require an external tool
+//! or a Lua script. The request is described by its method, URL, headers and
+//! an optional body, that can be either provided inline or read from a file
+//! each time the task runs. The outcome of the task is determined by checking
+//! the response:
+//!
+//! * matching the status code with a given value (by default any `2xx` code
+//!   is considered successful)
+//! * testing the body for the presence of a particular string or against a
+//!   regular expression
+//!
+//! using the same rules that apply to the output of command based tasks.
+
+#![cfg(feature = "http")]
+
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use itertools::Itertools;
+
+use cfgmap::CfgMap;
+
+// we implement the Task trait here in order to enqueue tasks
+use super::base::Task;
+use super::graph::{TaskDependencies, cfg_task_dependencies};
+use crate::common::httpitem::*;
+use crate::utility::logging::{LogType, log};
+use crate::utility::result::Result;
+use crate::{cfg_mandatory, constants::*};
+
+use crate::cfghelp::*;
+
+/// HTTP Request Based Task
+///
+/// This type of task performs an HTTP request and checks its outcome by
+/// examining the status code and/or the body of the response.
+pub struct HttpTask {
+    // common members
+    task_id: i64,
+    task_name: String,
+    dependencies: TaskDependencies,
+
+    // specific members
+    // parameters
+    url: String,
+    method: String,
+    headers: HashMap<String, String>,
+    body: Option<String>,
+    body_file: Option<PathBuf>,
+    checks: ResponseChecks,
+    timeout: Option<Duration>,
+
+    // internal values
+    _response_status: u32,
+    _response_body: String,
+    _request_failed: bool,
+    _request_duration: Duration,
+}
+
+// implement the hash protocol
+impl Hash for HttpTask {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.task_name.hash(state);
+        self.dependencies.hash(state);
+        self.url.hash(state);
+        self.method.hash(state);
+        self.body.hash(state);
+        self.body_file.hash(state);
+        self.checks.hash(state);
+        self.timeout.hash(state);
+
+        // the order in which headers are defined is not significant
+        for key in self.headers.keys().sorted() {
+            key.hash(state);
+            self.headers[key].hash(state);
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl HttpTask {
+    /// Create a new HTTP request based task
+    ///
+    /// The only parameters that have to be set mandatorily upon creation of
+    /// an HTTP request based task are the following.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - a string containing the name of the task
+    /// * `url` - the URL the request is sent to
+    ///
+    /// The request uses the `GET` method by default.
+    pub fn new(name: &str, url: &str) -> Self {
+        log(
+            LogType::Debug,
+            LOG_EMITTER_TASK_HTTP,
+            LOG_ACTION_NEW,
+            Some((name, 0)),
+            LOG_WHEN_INIT,
+            LOG_STATUS_MSG,
+            &format!("TASK {name}: creating a new HTTP request based task"),
+        );
+        HttpTask {
+            // common members initialization
+            // reset ID to zero
+            task_id: 0,
+
+            // parameters
+            task_name: String::from(name),
+            dependencies: TaskDependencies::default(),
+
+            // specific members initialization
+            // parameters
+            url: String::from(url),
+            method: String::from("GET"),
+            headers: HashMap::new(),
+            body: None,
+            body_file: None,
+            checks: ResponseChecks::default(),
+            timeout: None,
+
+            // internal values
+            _response_status: 0,
+            _response_body: String::new(),
+            _request_failed: false,
+            _request_duration: Duration::ZERO,
+        }
+    }
+
+    // build a short description of the request, only for logging purposes
+    fn request_line(&self) -> String {
+        format!("{} {}", self.method, self.url)
+    }
+
+    /// Set a header to be sent along with the request
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the header name
+    /// * `value` - the value assigned to the named header.
+    pub fn set_header(&mut self, name: &str, value: &str) -> Option<String> {
+        self.headers.insert(String::from(name), String::from(value))
+    }
+
+    /// Remove a header from the ones to be sent along with the request
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the header to be removed.
+    pub fn unset_header(&mut self, name: &str) -> Option<String> {
+        self.headers.remove(name)
+    }
+
+    /// Constructor modifier to use a specific method
+    ///
+    /// The method name is not case sensitive: unsupported methods are
+    /// ignored, leaving the previously set method in place.
+    pub fn with_method(mut self, method: &str) -> Self {
+        if http_method(method).is_some() {
+            self.method = method.to_ascii_uppercase();
+        }
+        self
+    }
+
+    /// Constructor modifier to send a body with the request
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.body = Some(String::from(body));
+        self.body_file = None;
+        self
+    }
+
+    /// Constructor modifier to send the contents of a file as the body
+    ///
+    /// The file is read each time the request is performed.
+    pub fn with_body_file(mut self, path: &Path) -> Self {
+        self.body_file = Some(PathBuf::from(path));
+        self.body = None;
+        self
+    }
+
+    /// Constructor modifier to match against regular expressions
+    ///
+    /// Specifies that the values to match against the response body have to
+    /// be considered as regular expressions when the argument is set to
+    /// `true`. The default behaviour is to consider them as simple strings.
+    pub fn matches_regexp(mut self, yes: bool) -> Self {
+        self.checks.match_regexp = yes;
+        self
+    }
+
+    /// Constructor modifier exactly match against a string
+    ///
+    /// Specifies that the entire response body must match against the
+    /// provided value, when the argument is set to `true`. The default
+    /// behaviour is to _partially_ match the body.
+    pub fn matches_exact(mut self, yes: bool) -> Self {
+        self.checks.match_exact = yes;
+        self
+    }
+
+    /// Constructor modifier for case-sensitive match
+    ///
+    /// Specifies that the matching against the response body is to be
+    /// performed case-sensitively when set to `true`. The default behaviour
+    /// is to ignore case.
+    pub fn matches_case(mut self, yes: bool) -> Self {
+        self.checks.case_sensitive = yes;
+        self
+    }
+
+    /// Constructor modifier to match against the response body
+    ///
+    /// Specifies that the provided check value must match the body of the
+    /// response in order for the request to be considered successful.
+    pub fn expects_body(mut self, s: &str) -> Self {
+        self.checks.success_body = Some(s.to_string());
+        self
+    }
+
+    /// Constructor modifier to not match against the response body
+    ///
+    /// Specifies that the provided check value must not match the body of
+    /// the response in order for the request to be considered successful.
+    pub fn rejects_body(mut self, s: &str) -> Self {
+        self.checks.failure_body = Some(s.to_string());
+        self
+    }
+
+    /// Constructor modifier to expect a status code
+    ///
+    /// Provide a status code that must be matched for the request to be
+    /// considered successful.
+    pub fn expects_status(mut self, c: u32) -> Self {
+        self.checks.success_status = Some(c);
+        self
+    }
+
+    /// Constructor modifier to reject a status code
+    ///
+    /// Provide a status code that must be matched for the request to be
+    /// considered failed.
+    pub fn rejects_status(mut self, c: u32) -> Self {
+        self.checks.failure_status = Some(c);
+        self
+    }
+
+    /// If set, the request times out after specified duration
+    pub fn times_out_after(mut self, delta: Duration) -> Self {
+        self.timeout = Some(delta);
+        self
+    }
+
+    /// Load an `HttpTask` from a [`CfgMap`](https://docs.rs/cfgmap/latest/)
+    ///
+    /// The `HttpTask` is initialized according to the values provided in the
+    /// `CfgMap` argument. If the `CfgMap` format does not comply with the
+    /// requirements of an `HttpTask` an error is raised.
+    pub fn load_cfgmap(cfgmap: &CfgMap) -> Result<HttpTask> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "after",
+            "on_success",
+            "on_failure",
+            "url",
+            "method",
+            "headers",
+            "body",
+            "body_file",
+            "match_exact",
+            "match_regular_expression",
+            "case_sensitive",
+            "success_body",
+            "success_status",
+            "failure_body",
+            "failure_status",
+            "timeout_seconds",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // type and name are both mandatory but type is only checked
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "http"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_TASK_NAME))?.unwrap();
+
+        // specific mandatory parameter retrieval
+        let url = cfg_mandatory!(cfg_string_check_regex(cfgmap, "url", &RE_HTTP_URL))?.unwrap();
+
+        // initialize the structure
+        let mut new_task = HttpTask::new(&name, &url);
+
+        // common optional parameter initialization
+
+        // tags are always simply checked this way as no value is needed
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // dependencies on other tasks are common to all task types
+        new_task.dependencies = cfg_task_dependencies(cfgmap)?;
+
+        // specific optional parameter initialization
+        let cur_key = "method";
+        if let Some(v) = cfg_string(cfgmap, cur_key)? {
+            if http_method(&v).is_none() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_VALUE));
+            }
+            new_task.method = v.to_ascii_uppercase();
+        }
+        new_task.headers = cfg_http_headers(cfgmap, "headers")?;
+
+        // the body can be given either inline or as a file, but not both
+        new_task.body = cfg_string(cfgmap, "body")?;
+        let cur_key = "body_file";
+        if let Some(v) = cfg_string(cfgmap, cur_key)? {
+            if new_task.body.is_some() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_PARAMETER));
+            }
+            let body_file = PathBuf::from(&v);
+            if !body_file.is_file() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_FILESPEC));
+            }
+            new_task.body_file = Some(body_file);
+        }
+
+        new_task.checks = cfg_response_checks(cfgmap)?;
+
+        if let Some(v) = cfg_int_check_above_eq(cfgmap, "timeout_seconds", 0)?
+            && v > 0
+        {
+            new_task.timeout = Some(Duration::from_secs(v as u64));
+        }
+
+        Ok(new_task)
+    }
+
+    /// Check a configuration map and return item name if Ok
+    ///
+    /// The check is performed exactly in the same way and in the same order
+    /// as in `load_cfgmap`, the only difference is that no actual item is
+    /// created and that a name is returned, which is the name of the item that
+    /// _would_ be created via the equivalent call to `load_cfgmap`
+    pub fn check_cfgmap(cfgmap: &CfgMap) -> Result<String> {
+        let check = vec![
+            "type",
+            "name",
+            "tags",
+            "after",
+            "on_success",
+            "on_failure",
+            "url",
+            "method",
+            "headers",
+            "body",
+            "body_file",
+            "match_exact",
+            "match_regular_expression",
+            "case_sensitive",
+            "success_body",
+            "success_status",
+            "failure_body",
+            "failure_status",
+            "timeout_seconds",
+        ];
+        cfg_check_keys(cfgmap, &check)?;
+
+        // common mandatory parameter check
+
+        // type and name are both mandatory: type is checked and name is kept
+        cfg_mandatory!(cfg_string_check_exact(cfgmap, "type", "http"))?;
+        let name = cfg_mandatory!(cfg_string_check_regex(cfgmap, "name", &RE_TASK_NAME))?.unwrap();
+
+        // specific mandatory parameter check
+        cfg_mandatory!(cfg_string_check_regex(cfgmap, "url", &RE_HTTP_URL))?;
+
+        // also for optional parameters just check and throw away the result
+        // tags are always simply checked this way
+        let cur_key = "tags";
+        if let Some(item) = cfgmap.get(cur_key)
+            && !item.is_list()
+            && !item.is_map()
+        {
+            return Err(cfg_err_invalid_config(
+                cur_key,
+                STR_UNKNOWN_VALUE,
+                ERR_INVALID_PARAMETER,
+            ));
+        }
+
+        // dependencies are checked against existing tasks later
+        cfg_task_dependencies(cfgmap)?;
+
+        let cur_key = "method";
+        if let Some(v) = cfg_string(cfgmap, cur_key)?
+            && http_method(&v).is_none()
+        {
+            return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_VALUE));
+        }
+        cfg_http_headers(cfgmap, "headers")?;
+
+        let body = cfg_string(cfgmap, "body")?;
+        let cur_key = "body_file";
+        if let Some(v) = cfg_string(cfgmap, cur_key)? {
+            if body.is_some() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_PARAMETER));
+            }
+            if !PathBuf::from(&v).is_file() {
+                return Err(cfg_err_invalid_config(cur_key, &v, ERR_INVALID_FILESPEC));
+            }
+        }
+
+        cfg_response_checks(cfgmap)?;
+
+        cfg_int_check_above_eq(cfgmap, "timeout_seconds", 0)?;
+
+        Ok(name)
+    }
+}
+
+// implement the Task trait
+impl Task for HttpTask {
+    fn set_id(&mut self, id: i64) {
+        self.task_id = id;
+    }
+    fn get_name(&self) -> String {
+        self.task_name.clone()
+    }
+    fn get_id(&self) -> i64 {
+        self.task_id
+    }
+    fn dependencies(&self) -> TaskDependencies {
+        self.dependencies.clone()
+    }
+
+    /// Return a hash of this item for comparison
+    fn _hash(&self) -> u64 {
+        let mut s = DefaultHasher::new();
+        self.hash(&mut s);
+        s.finish()
+    }
+
+    /// Execute this `HttpTask`
+    ///
+    /// This implementation of the trait `run()` function obeys to the main
+    /// trait's constraints, and returns
+    ///
+    /// * `Ok(Some(true))` on success
+    /// * `Ok(Some(false))` on failure
+    ///
+    /// Requests that cannot be performed at all, for instance because the
+    /// server cannot be reached or the body file cannot be read, are logged
+    /// and considered failed.
+    fn _run(&mut self, trigger_name: &str) -> Result<Option<bool>> {
+        self._request_failed = false;
+        self._response_status = 0;
+        self._response_body = String::new();
+        self._request_duration = Duration::ZERO;
+
+        // the body file is read each time, as its contents may change
+        let body = if let Some(body_file) = &self.body_file {
+            match fs::read(body_file) {
+                Ok(contents) => Some(contents),
+                Err(e) => {
+                    self.log(
+                        LogType::Warn,
+                        LOG_WHEN_END,
+                        LOG_STATUS_FAIL,
+                        &format!(
+                            "(trigger: {trigger_name}) could not read request body from `{}` (reason: {e})",
+                            body_file.to_string_lossy(),
+                        ),
+                    );
+                    self._request_failed = true;
+                    return Ok(Some(false));
+                }
+            }
+        } else {
+            self.body.as_ref().map(|b| b.as_bytes().to_vec())
+        };
+
+        self.log(
+            LogType::Debug,
+            LOG_WHEN_START,
+            LOG_STATUS_MSG,
+            &format!(
+                "(trigger: {trigger_name}) performing request: `{}`",
+                self.request_line()
+            ),
+        );
+
+        let failure_reason = match send_request(
+            &self.method,
+            &self.url,
+            &self.headers,
+            body.as_deref(),
+            self.timeout,
+        ) {
+            Ok(response) => {
+                let (ck_failure_reason, log_severity, log_when, log_status, log_message) =
+                    check_response_outcome(&response, &self.checks);
+                self._response_status = response.status;
+                self._response_body = response.body;
+                self._request_duration = response.duration;
+                self.log(
+                    log_severity,
+                    log_when,
+                    log_status,
+                    &format!("(trigger: {trigger_name}) {log_message}"),
+                );
+                ck_failure_reason
+            }
+            // the request could not be performed thus an error is reported
+            Err(e) => {
+                self.log(
+                    LogType::Warn,
+                    LOG_WHEN_END,
+                    LOG_STATUS_FAIL,
+                    &format!(
+                        "(trigger: {trigger_name}) could not perform request: `{}` (reason: {e})",
+                        self.request_line(),
+                    ),
+                );
+                FailureReason::Other
+            }
+        };
+
+        // return true on success of false otherwise
+        match failure_reason {
+            FailureReason::NoFailure => {
+                self.log(
+                    LogType::Debug,
+                    LOG_WHEN_END,
+                    LOG_STATUS_OK,
+                    &format!(
+                        "(trigger: {trigger_name}) task exited successfully in {:.2}s",
+                        self._request_duration.as_secs_f64()
+                    ),
+                );
+                Ok(Some(true))
+            }
+            FailureReason::Other => {
+                self._request_failed = true;
+                Ok(Some(false))
+            }
+            reason => {
+                self._request_failed = true;
+                self.log(
+                    LogType::Debug,
+                    LOG_WHEN_END,
+                    LOG_STATUS_OK,
+                    &format!(
+                        "(trigger: {trigger_name}) task exited unsuccessfully ({} check) in {:.2}s",
+                        reason.check_name(),
+                        self._request_duration.as_secs_f64()
+                    ),
+                );
+                Ok(Some(false))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+    use std::time::Instant;
+
+    // serve a single canned response on a local port, returning the URL to
+    // be queried and a handle that yields the raw request that was received
+    fn serve(status: u32, body: &str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/check", listener.local_addr().unwrap());
+        let response = format!(
+            "HTTP/1.1 {status} Canned\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some((name, value)) = line.split_once(':')
+                    && name.eq_ignore_ascii_case("content-length")
+                {
+                    content_length = value.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            request.push_str(&String::from_utf8_lossy(&body));
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            request
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn method_and_body_are_sent() {
+        let (url, server) = serve(200, "ok");
+        let mut task = HttpTask::new("post", &url)
+            .with_method("post")
+            .with_body("inline body");
+        assert_eq!(task._run("test").unwrap(), Some(true));
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /check "));
+        assert!(request.ends_with("\r\n\r\ninline body"));
+
+        // the body file is read when the request is performed
+        let path = std::env::temp_dir().join(format!("whenever-http-{}", std::process::id()));
+        fs::write(&path, "file body").unwrap();
+        let (url, server) = serve(200, "ok");
+        let mut task = HttpTask::new("put", &url)
+            .with_method("PUT")
+            .with_body_file(&path);
+        let outcome = task._run("test");
+        fs::remove_file(&path).unwrap();
+        assert_eq!(outcome.unwrap(), Some(true));
+        let request = server.join().unwrap();
+        assert!(request.starts_with("PUT /check "));
+        assert!(request.ends_with("\r\n\r\nfile body"));
+
+        // a missing body file fails the task without performing the request
+        let mut task = HttpTask::new("missing", "http://127.0.0.1:1/").with_body_file(&path);
+        assert_eq!(task._run("test").unwrap(), Some(false));
+    }
+
+    #[test]
+    fn status_checks() {
+        let (url, _) = serve(404, "");
+        let mut task = HttpTask::new("default", &url);
+        assert_eq!(task._run("test").unwrap(), Some(false));
+
+        let (url, _) = serve(404, "");
+        let mut task = HttpTask::new("expected", &url).expects_status(404);
+        assert_eq!(task._run("test").unwrap(), Some(true));
+
+        let (url, _) = serve(200, "");
+        let mut task = HttpTask::new("unexpected", &url).expects_status(201);
+        assert_eq!(task._run("test").unwrap(), Some(false));
+
+        let (url, _) = serve(503, "");
+        let mut task = HttpTask::new("rejected", &url)
+            .expects_status(503)
+            .rejects_status(503);
+        assert_eq!(task._run("test").unwrap(), Some(false));
+    }
+
+    #[test]
+    fn body_regex_checks() {
+        let run = |body: &str, task: HttpTask| {
+            let (url, _) = serve(200, body);
+            let mut task = HttpTask { url, ..task };
+            task._run("test").unwrap()
+        };
+        let task = || {
+            HttpTask::new("regex", "")
+                .matches_regexp(true)
+                .expects_body(r"^status: (ok|fine)$")
+        };
+        assert_eq!(run("Status: FINE", task()), Some(true));
+        assert_eq!(run("status: bad", task()), Some(false));
+        assert_eq!(run("Status: FINE", task().matches_case(true)), Some(false));
+
+        let task = HttpTask::new("regex", "")
+            .matches_regexp(true)
+            .rejects_body(r"err(or)?\b");
+        assert_eq!(run("an error occurred", task), Some(false));
+    }
+
+    #[test]
+    fn unanswered_request_times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            // keep the connection open without ever answering
+            let (_stream, _) = listener.accept().unwrap();
+            thread::sleep(Duration::from_secs(10));
+        });
+        let mut task = HttpTask::new("timeout", &url).times_out_after(Duration::from_secs(1));
+        let start = Instant::now();
+        assert_eq!(task._run("test").unwrap(), Some(false));
+        assert!(task._request_failed);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
+
+// end.
@@ -16,6 +16,7 @@ use lazy_static::lazy_static;
 
 // we implement the Task trait here in order to enqueue tasks
 use super::base::Task;
+use super::graph::{TaskDependencies, cfg_task_dependencies};
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::{Error, Kind, Result};
 use crate::{cfg_mandatory, constants::*};
@@ -63,6 +64,7 @@ pub struct InternalTask {
     // common members
     task_id: i64,
     task_name: String,
+    dependencies: TaskDependencies,
 
     // specific members
     // parameters
@@ -75,6 +77,7 @@ pub struct InternalTask {
 impl Hash for InternalTask {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.task_name.hash(state);
+        self.dependencies.hash(state);
         self.command.hash(state);
     }
 }
@@ -106,6 +109,7 @@ impl InternalTask {
         Self {
             task_id: 0,
             task_name: String::from(name),
+            dependencies: TaskDependencies::default(),
             command: String::from(command),
         }
     }
@@ -116,7 +120,14 @@ impl InternalTask {
     /// the `CfgMap` argument. If the `CfgMap` format does not comply with
     /// the requirements of a `InternalTask` an error is raised.
     pub fn load_cfgmap(cfgmap: &CfgMap) -> Result<InternalTask> {
-        let check = vec!["type", "name", "command"];
+        let check = vec![
+            "type",
+            "name",
+            "command",
+            "after",
+            "on_success",
+            "on_failure",
+        ];
         cfg_check_keys(cfgmap, &check)?;
 
         // type and name are both mandatory but type is only checked
@@ -127,7 +138,7 @@ impl InternalTask {
         let command = cfg_mandatory!(cfg_string(cfgmap, "command"))?.unwrap();
 
         // initialize the structure
-        let new_task = InternalTask::new(&name, &command);
+        let mut new_task = InternalTask::new(&name, &command);
 
         // common optional parameter initialization
 
@@ -144,6 +155,9 @@ impl InternalTask {
             ));
         }
 
+        // dependencies on other tasks are common to all task types
+        new_task.dependencies = cfg_task_dependencies(cfgmap)?;
+
         // specific optional parameter initialization
         // (none here)
 
@@ -157,7 +171,14 @@ impl InternalTask {
     /// created and that a name is returned, which is the name of the item that
     /// _would_ be created via the equivalent call to `load_cfgmap`
     pub fn check_cfgmap(cfgmap: &CfgMap) -> Result<String> {
-        let check = vec!["type", "name", "command"];
+        let check = vec![
+            "type",
+            "name",
+            "command",
+            "after",
+            "on_success",
+            "on_failure",
+        ];
         cfg_check_keys(cfgmap, &check)?;
 
         // type and name are both mandatory but type is only checked
@@ -181,6 +202,9 @@ impl InternalTask {
             ));
         }
 
+        // dependencies are checked against existing tasks later
+        cfg_task_dependencies(cfgmap)?;
+
         Ok(name)
     }
 }
@@ -195,6 +219,9 @@ impl Task for InternalTask {
     fn get_id(&self) -> i64 {
         self.task_id
     }
+    fn dependencies(&self) -> TaskDependencies {
+        self.dependencies.clone()
+    }
 
     /// Return a hash of this item for comparison
     fn _hash(&self) -> u64 {
@@ -19,8 +19,10 @@ use mlua;
 
 // we implement the Task trait here in order to enqueue tasks
 use super::base::Task;
+use super::graph::{TaskDependencies, cfg_task_dependencies};
 use crate::utility::logging::{LogType, log};
 use crate::common::luaitem::*;
+use crate::event::payload::{EventPayload, PayloadValue};
 use crate::utility::result::{Error, Kind, Result};
 use crate::{cfg_mandatory, constants::*};
 
@@ -34,6 +36,7 @@ pub struct LuaTask {
     // common members
     task_id: i64,
     task_name: String,
+    dependencies: TaskDependencies,
 
     // specific members
     // parameters
@@ -45,6 +48,8 @@ pub struct LuaTask {
     expect_all: bool,
 
     // internal values
+    received_vars: HashMap<String, String>,
+    event_payload: Option<EventPayload>,
     #[cfg(feature = "lua_sync")]
     state: LuaState,
 }
@@ -53,6 +58,7 @@ pub struct LuaTask {
 impl Hash for LuaTask {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.task_name.hash(state);
+        self.dependencies.hash(state);
         self.script.hash(state);
         self.set_vars.hash(state);
         self.expect_all.hash(state);
@@ -120,6 +126,7 @@ impl LuaTask {
         LuaTask {
             task_id: 0,
             task_name: String::from(name),
+            dependencies: TaskDependencies::default(),
 
             // specific members initialization
             script: String::from(script),
@@ -129,6 +136,8 @@ impl LuaTask {
             init_script: None,
             expect_all: false,
 
+            received_vars: HashMap::new(),
+            event_payload: None,
             #[cfg(feature = "lua_sync")]
             state: HashMap::new(),
         }
@@ -231,6 +240,9 @@ impl LuaTask {
             "type",
             "name",
             "tags",
+            "after",
+            "on_success",
+            "on_failure",
             "script",
             "expect_all",
             "variables_to_set",
@@ -264,6 +276,9 @@ impl LuaTask {
             ));
         }
 
+        // dependencies on other tasks are common to all task types
+        new_task.dependencies = cfg_task_dependencies(cfgmap)?;
+
         // specific optional parameter initialization
         if let Some(v) = cfg_bool(cfgmap, "expect_all")? {
             new_task.expect_all = v;
@@ -387,6 +402,9 @@ impl LuaTask {
             "type",
             "name",
             "tags",
+            "after",
+            "on_success",
+            "on_failure",
             "script",
             "expect_all",
             "variables_to_set",
@@ -419,6 +437,9 @@ impl LuaTask {
             ));
         }
 
+        // dependencies are checked against existing tasks later
+        cfg_task_dependencies(cfgmap)?;
+
         cfg_bool(cfgmap, "expect_all")?;
 
         // variables to set are in a complex map, thus no shortcut is given
@@ -537,6 +558,17 @@ impl Task for LuaTask {
     fn get_id(&self) -> i64 {
         self.task_id
     }
+    fn dependencies(&self) -> TaskDependencies {
+        self.dependencies.clone()
+    }
+
+    fn set_received_variables(&mut self, vars: &HashMap<String, String>) {
+        self.received_vars = vars.clone();
+    }
+
+    fn set_event_payload(&mut self, payload: Option<&EventPayload>) {
+        self.event_payload = payload.cloned();
+    }
 
     /// Return a hash of this item for comparison
     fn _hash(&self) -> u64 {
@@ -644,6 +676,36 @@ impl Task for LuaTask {
                     }
                 }
             }
+
+            // values captured by previous tasks are always strings
+            for (varname, v) in self.received_vars.iter() {
+                if globals.set(varname.as_str(), v.as_str()).is_err() {
+                    self.log(
+                        LogType::Warn,
+                        LOG_WHEN_START,
+                        LOG_STATUS_ERR,
+                        &format!("(trigger: {trigger_name}) cannot set variable `{varname}`"),
+                    );
+                    failure_reason = FailureReason::InitError;
+                }
+            }
+
+            // the event payload is a table containing the event name and
+            // the values, where lists are converted to sequences
+            if let Some(payload) = &self.event_payload {
+                let evttab = lua.create_table()?;
+                evttab.set("name", payload.event())?;
+                for (name, value) in payload.values() {
+                    match value {
+                        PayloadValue::Text(x) => evttab.set(name.as_str(), x.as_str())?,
+                        PayloadValue::List(x) => evttab.set(
+                            name.as_str(),
+                            lua.create_sequence_from(x.iter().map(|i| i.as_str()))?,
+                        )?,
+                    }
+                }
+                globals.set(LUAVAR_NAME_EVENT.as_str(), evttab)?;
+            }
         }
 
         // create functions for logging in a table called `log`
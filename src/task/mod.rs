@@ -2,10 +2,14 @@
 
 pub mod base; // this only defines the trait
 pub mod registry; // the main task registry
+pub mod graph; // dependencies between tasks
 
 // specific task types
 pub mod command_task;
 pub mod internal_task;
 pub mod lua_task;
 
+#[cfg(feature = "http")]
+pub mod http_task;
+
 // end.
@@ -8,7 +8,8 @@
 //! trait does not allow running a task when it does not have an ID), and it
 //! can be executed in a series either sequentially or simultaneously with
 //! other tasks, respectively using the `run_tasks_seq` and `run_tasks_par`
-//! functions.
+//! functions, or according to the dependencies between tasks using the
+//! `run_tasks_graph` function.
 
 use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
@@ -22,6 +23,8 @@ use unique_id::Generator;
 use unique_id::sequence::SequenceGenerator;
 
 use super::base::{Task, TaskRef};
+use super::graph::{TaskDependencies, check_task_graph};
+use crate::event::payload::EventPayload;
 use crate::utility::logging::{LogType, log};
 use crate::utility::result::{Error, Kind, Result};
 use crate::constants::*;
@@ -272,7 +275,8 @@ impl TaskRegistry {
     /// `break_failure`) is set to `true`. Of course both can be set to `true`,
     /// as there may be tasks that exit with an indefinite state. The name of a
     /// `Condition` trigger must be provided for logging purposes. The outcomes
-    /// are returned in a `HashMap`.
+    /// are returned in a `HashMap`. Variables captured by a task are passed
+    /// on to all the tasks that follow it in the list.
     ///
     /// **Note:** this function runs in the calling thread, that is blocked
     /// until it returns.
@@ -283,6 +287,7 @@ impl TaskRegistry {
     /// * `names` - a vector containing the names of the tasks
     /// * `break_failure` - if set break on first failure
     /// * `break_success` - if set break on first success
+    /// * `payload` - the payload attached by the event that fired the condition
     ///
     /// # Returns
     ///
@@ -301,6 +306,7 @@ impl TaskRegistry {
         names: &Vec<&str>,
         break_failure: bool,
         break_success: bool,
+        payload: Option<&EventPayload>,
     ) -> Result<HashMap<String, Result<Option<bool>>>> {
         assert!(
             self.has_all_tasks(names),
@@ -309,6 +315,9 @@ impl TaskRegistry {
 
         let mut res: HashMap<String, Result<Option<bool>>> = HashMap::new();
 
+        // variables captured by the tasks run so far, passed to the next ones
+        let mut vars: HashMap<String, String> = HashMap::new();
+
         // count the active running sessions: there can be more than a
         // command task session in execution at the moment, and knowing
         // that none is running anymore is necessary to handle changes
@@ -336,7 +345,12 @@ impl TaskRegistry {
             drop(tl0);
 
             let mut t0 = task.lock();
+            t0.set_received_variables(&vars);
+            t0.set_event_payload(payload);
             let cur_res = t0.run(trigger_name);
+            if let Some((var, value)) = t0.captured_variable() {
+                vars.insert(var, value);
+            }
             log(
                 LogType::Trace,
                 LOG_EMITTER_TASK_REGISTRY,
@@ -377,79 +391,8 @@ impl TaskRegistry {
             }
         }
 
-        // decrease the number of running sessions: if the number reaches zero
-        // then perform the item add/removal routine while the session counter
-        // is locked, so that no one else can modify the current items list;
-        // note that since the counter is locked, no other sessions can be run
-        // in other possible threads
-        {
-            let mut sessions = sessions.lock();
-            *sessions -= 1;
-
-            if *sessions == 0 {
-                let rm_queue = self.items_to_remove.clone();
-                {
-                    let queue = rm_queue.lock();
-                    for name in queue.iter() {
-                        if let Ok(item) = self.remove_task(name) {
-                            if let Some(item) = item {
-                                let name = item.get_name();
-                                log(
-                                    LogType::Debug,
-                                    LOG_EMITTER_TASK_REGISTRY,
-                                    LOG_ACTION_UNINSTALL,
-                                    None,
-                                    LOG_WHEN_PROC,
-                                    LOG_STATUS_OK,
-                                    &format!("successfully removed task {name} from the registry"),
-                                );
-                            } else {
-                                log(
-                                    LogType::Debug,
-                                    LOG_EMITTER_TASK_REGISTRY,
-                                    LOG_ACTION_UNINSTALL,
-                                    None,
-                                    LOG_WHEN_PROC,
-                                    LOG_STATUS_OK,
-                                    &format!("task to remove {name} not found in the registry"),
-                                );
-                            }
-                        }
-                    }
-                }
-                let add_queue = self.items_to_add.clone();
-                {
-                    let mut queue = add_queue.lock();
-                    while !queue.is_empty() {
-                        if let Some(item_ref) = queue.pop() {
-                            let name = item_ref.get_name();
-                            let id = self.task_id(&name).unwrap();
-                            if self.add_task(item_ref) {
-                                log(
-                                    LogType::Debug,
-                                    LOG_EMITTER_TASK_REGISTRY,
-                                    LOG_ACTION_INSTALL,
-                                    Some((&name, id)),
-                                    LOG_WHEN_PROC,
-                                    LOG_STATUS_OK,
-                                    "successfully added queued task to the registry",
-                                );
-                            } else {
-                                log(
-                                    LogType::Debug,
-                                    LOG_EMITTER_TASK_REGISTRY,
-                                    LOG_ACTION_INSTALL,
-                                    Some((&name, id)),
-                                    LOG_WHEN_PROC,
-                                    LOG_STATUS_FAIL,
-                                    "queued task already present in the registry",
-                                );
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        // decrease the number of running sessions and handle the queues
+        self.leave_session(LOG_ACTION_INSTALL);
 
         log(
             LogType::Trace,
@@ -480,6 +423,7 @@ impl TaskRegistry {
     ///
     /// * `trigger_name` - the name of the triggering `Condition`
     /// * `names` - a vector containing the names of the tasks
+    /// * `payload` - the payload attached by the event that fired the condition
     ///
     /// # Returns
     ///
@@ -495,6 +439,7 @@ impl TaskRegistry {
         &self,
         trigger_name: &str,
         names: &Vec<&str>,
+        payload: Option<&EventPayload>,
     ) -> Result<HashMap<String, Result<Option<bool>>>> {
         assert!(
             self.has_all_tasks(names),
@@ -541,8 +486,13 @@ impl TaskRegistry {
             let aname = Arc::new(String::from(*name));
             let atrname = atrname.clone().to_string();
             let atx = atx.clone();
+            let payload = payload.cloned();
             let handle = spawn(move || {
+                // tasks run in parallel cannot receive captured variables, but
+                // the ones left by a previous sequential run must be cleared
                 let mut task = task.lock();
+                task.set_received_variables(&HashMap::new());
+                task.set_event_payload(payload.as_ref());
                 let outcome = task.run(&atrname);
                 let atx = atx.lock();
                 let _ = atx.send((aname.clone(), outcome));
@@ -588,79 +538,299 @@ impl TaskRegistry {
             );
         }
 
-        // decrease the number of running sessions: if the number reaches zero
-        // then perform the item add/removal routine while the session counter
-        // is locked, so that no one else can modify the current items list
+        // decrease the number of running sessions and handle the queues
+        self.leave_session(LOG_ACTION_NEW);
+
+        Ok(res)
+    }
+
+    /// Tell whether any of the listed tasks depends on other tasks
+    ///
+    /// When this is the case the tasks have to be run using `run_tasks_graph`
+    /// instead of `run_tasks_seq` or `run_tasks_par`.
+    pub fn has_dependencies(&self, names: &Vec<&str>) -> bool {
+        let tl0 = self.task_list.read();
+        names.iter().any(|name| {
+            tl0.get(*name)
+                .is_some_and(|task| !task.clone().lock().dependencies().is_empty())
+        })
+    }
+
+    /// Verify the dependencies between registered tasks
+    ///
+    /// An error is returned if any task depends on a task that is not in the
+    /// registry, or if the dependencies are circular.
+    pub fn check_dependencies(&self) -> Result<()> {
+        let tl0 = self.task_list.read();
+        let graph: HashMap<String, Vec<String>> = tl0
+            .iter()
+            .map(|(name, task)| (name.clone(), task.clone().lock().dependencies().upstream()))
+            .collect();
+        check_task_graph(&graph)
+    }
+
+    /// Run a list of tasks according to their dependencies
+    ///
+    /// Executes the tasks in the provided list as a graph: each task is run
+    /// in a separate thread as soon as all the tasks it depends on (among the
+    /// ones in the list) have finished, so that independent tasks are run
+    /// simultaneously. Tasks whose `on_success` or `on_failure` requirements
+    /// are not met by the outcomes of their upstream tasks are skipped, and
+    /// each task receives the variables captured by the tasks that finished
+    /// before it was started. The name of a `Condition` trigger must be
+    /// provided for logging purposes.
+    ///
+    /// **Note:** this function runs in the calling thread, that is blocked
+    /// until it returns.
+    ///
+    /// # Arguments
+    ///
+    /// * `trigger_name` - the name of the triggering `Condition`
+    /// * `names` - a vector containing the names of the tasks
+    /// * `payload` - the payload attached by the event that fired the condition
+    ///
+    /// # Returns
+    ///
+    /// A `HashMap` whose keys are the names of the tasks _that have been run_
+    /// (that is, skipped tasks are not included) and whose elements are their
+    /// respective outcomes.
+    ///
+    /// # Panics
+    ///
+    /// If one or more task names are not in the registry the function panics:
+    /// in no way there should be the option that this function is invoked with
+    /// task names that are unknown.
+    pub fn run_tasks_graph(
+        &self,
+        trigger_name: &str,
+        names: &Vec<&str>,
+        payload: Option<&EventPayload>,
+    ) -> Result<HashMap<String, Result<Option<bool>>>> {
+        assert!(
+            self.has_all_tasks(names),
+            "some tasks not found in registry for condition `{trigger_name}`",
+        );
+
+        // increase the number of running sessions before running tasks
         {
+            let sessions = self.running_sessions.clone();
             let mut sessions = sessions.lock();
-            *sessions -= 1;
+            *sessions += 1;
+        }
 
-            if *sessions == 0 {
-                let rm_queue = self.items_to_remove.clone();
-                {
-                    let queue = rm_queue.lock();
-                    for name in queue.iter() {
-                        if let Ok(item) = self.remove_task(name) {
-                            if let Some(item) = item {
-                                let name = item.get_name();
-                                log(
-                                    LogType::Debug,
-                                    LOG_EMITTER_TASK_REGISTRY,
-                                    LOG_ACTION_UNINSTALL,
-                                    None,
-                                    LOG_WHEN_PROC,
-                                    LOG_STATUS_OK,
-                                    &format!("successfully removed task {name} from the registry"),
-                                );
-                            } else {
-                                log(
-                                    LogType::Debug,
-                                    LOG_EMITTER_TASK_REGISTRY,
-                                    LOG_ACTION_UNINSTALL,
-                                    None,
-                                    LOG_WHEN_PROC,
-                                    LOG_STATUS_OK,
-                                    &format!("task to remove {name} not found in the registry"),
-                                );
-                            }
+        let mut res: HashMap<String, Result<Option<bool>>> = HashMap::new();
+
+        // the outcomes of finished (or skipped) tasks, used to decide which
+        // tasks can be started and whether or not they should be skipped
+        let mut outcomes: HashMap<String, Option<bool>> = HashMap::new();
+        let mut vars: HashMap<String, String> = HashMap::new();
+        let mut pending: Vec<String> = names.iter().map(|x| x.to_string()).collect();
+        let mut dependencies: HashMap<String, TaskDependencies> = HashMap::new();
+        let tl0 = self.task_list.read();
+        for name in names.iter() {
+            let task = tl0.get(*name).unwrap().clone();
+            let deps = task.lock().dependencies();
+            dependencies.insert(name.to_string(), deps);
+        }
+        drop(tl0);
+
+        let mut handles: Vec<JoinHandle<()>> = Vec::new();
+        let (tx, rx) = channel();
+        let mut running = 0;
+
+        loop {
+            // start (or skip) all the tasks whose upstream tasks in the list
+            // have finished: skipping a task may in turn make other tasks
+            // ready, thus the list is scanned until nothing changes
+            let mut changed = true;
+            while changed {
+                changed = false;
+                for name in pending.clone() {
+                    let deps = dependencies.get(&name).unwrap();
+                    if deps
+                        .upstream()
+                        .iter()
+                        .any(|x| names.contains(&x.as_str()) && !outcomes.contains_key(x))
+                    {
+                        continue;
+                    }
+                    pending.retain(|x| x != &name);
+                    changed = true;
+                    let id = self.task_id(&name).unwrap();
+                    if deps.allows(&outcomes) {
+                        log(
+                            LogType::Trace,
+                            LOG_EMITTER_TASK_REGISTRY,
+                            LOG_ACTION_RUN_TASKS_GRAPH,
+                            Some((&name, id)),
+                            LOG_WHEN_START,
+                            LOG_STATUS_MSG,
+                            &format!("starting task {name}"),
+                        );
+                        let tl0 = self.task_list.read();
+                        let task = tl0
+                            .get(&name)
+                            .expect("cannot retrieve task for running")
+                            .clone();
+                        drop(tl0);
+                        let trname = trigger_name.to_string();
+                        let tx = tx.clone();
+                        let vars = vars.clone();
+                        let payload = payload.cloned();
+                        let handle = spawn(move || {
+                            let mut task = task.lock();
+                            task.set_received_variables(&vars);
+                            task.set_event_payload(payload.as_ref());
+                            let outcome = task.run(&trname);
+                            let captured = task.captured_variable();
+                            let _ = tx.send((name, outcome, captured));
+                        });
+                        handles.push(handle);
+                        running += 1;
+                    } else {
+                        log(
+                            LogType::Debug,
+                            LOG_EMITTER_TASK_REGISTRY,
+                            LOG_ACTION_RUN_TASKS_GRAPH,
+                            Some((&name, id)),
+                            LOG_WHEN_PROC,
+                            LOG_STATUS_MSG,
+                            &format!("skipping task {name}: requirements on other tasks not met"),
+                        );
+                        outcomes.insert(name, None);
+                    }
+                }
+            }
+
+            // wait for one of the running tasks to finish, so that the tasks
+            // that depend on it can be considered at the next iteration
+            if running == 0 {
+                break;
+            }
+            let Ok((name, outcome, captured)) = rx.recv() else {
+                break;
+            };
+            running -= 1;
+            if let Some((var, value)) = captured {
+                vars.insert(var, value);
+            }
+            outcomes.insert(
+                name.clone(),
+                match &outcome {
+                    Ok(v) => *v,
+                    Err(_) => Some(false),
+                },
+            );
+            res.insert(name, outcome);
+        }
+
+        for handle in handles.into_iter() {
+            let _ = handle.join();
+        }
+
+        // this should never happen, as circular dependencies are rejected
+        // when the configuration is loaded
+        if !pending.is_empty() {
+            log(
+                LogType::Warn,
+                LOG_EMITTER_TASK_REGISTRY,
+                LOG_ACTION_RUN_TASKS_GRAPH,
+                None,
+                LOG_WHEN_END,
+                LOG_STATUS_ERR,
+                &format!("tasks could not be run due to dependencies: {}", pending.join(" ")),
+            );
+        }
+
+        // decrease the number of running sessions and handle the queues
+        self.leave_session(LOG_ACTION_INSTALL);
+
+        log(
+            LogType::Trace,
+            LOG_EMITTER_TASK_REGISTRY,
+            LOG_ACTION_RUN_TASKS_GRAPH,
+            None,
+            LOG_WHEN_END,
+            LOG_STATUS_MSG,
+            &format!("finished running {}/{} tasks", res.len(), names.len()),
+        );
+        Ok(res)
+    }
+
+    // decrease the number of running sessions: if the number reaches zero
+    // then perform the item add/removal routine while the session counter
+    // is locked, so that no one else can modify the current items list;
+    // note that since the counter is locked, no other sessions can be run
+    // in other possible threads; the action used to log the addition of
+    // queued tasks is provided by the caller, as it differs among runners
+    fn leave_session(&self, add_action: &str) {
+        let sessions = self.running_sessions.clone();
+        let mut sessions = sessions.lock();
+        *sessions -= 1;
+
+        if *sessions == 0 {
+            let rm_queue = self.items_to_remove.clone();
+            {
+                let queue = rm_queue.lock();
+                for name in queue.iter() {
+                    if let Ok(item) = self.remove_task(name) {
+                        if let Some(item) = item {
+                            let name = item.get_name();
+                            log(
+                                LogType::Debug,
+                                LOG_EMITTER_TASK_REGISTRY,
+                                LOG_ACTION_UNINSTALL,
+                                None,
+                                LOG_WHEN_PROC,
+                                LOG_STATUS_OK,
+                                &format!("successfully removed task {name} from the registry"),
+                            );
+                        } else {
+                            log(
+                                LogType::Debug,
+                                LOG_EMITTER_TASK_REGISTRY,
+                                LOG_ACTION_UNINSTALL,
+                                None,
+                                LOG_WHEN_PROC,
+                                LOG_STATUS_OK,
+                                &format!("task to remove {name} not found in the registry"),
+                            );
                         }
                     }
                 }
-                let add_queue = self.items_to_add.clone();
-                {
-                    let mut queue = add_queue.lock();
-                    while !queue.is_empty() {
-                        if let Some(item_ref) = queue.pop() {
-                            let name = item_ref.get_name();
-                            let id = self.task_id(&name).unwrap();
-                            if self.add_task(item_ref) {
-                                log(
-                                    LogType::Debug,
-                                    LOG_EMITTER_TASK_REGISTRY,
-                                    LOG_ACTION_NEW,
-                                    Some((&name, id)),
-                                    LOG_WHEN_PROC,
-                                    LOG_STATUS_OK,
-                                    "successfully added queued task to the registry",
-                                );
-                            } else {
-                                log(
-                                    LogType::Debug,
-                                    LOG_EMITTER_TASK_REGISTRY,
-                                    LOG_ACTION_NEW,
-                                    Some((&name, id)),
-                                    LOG_WHEN_PROC,
-                                    LOG_STATUS_FAIL,
-                                    "queued task already present in the registry",
-                                );
-                            }
+            }
+            let add_queue = self.items_to_add.clone();
+            {
+                let mut queue = add_queue.lock();
+                while !queue.is_empty() {
+                    if let Some(item_ref) = queue.pop() {
+                        let name = item_ref.get_name();
+                        let id = self.task_id(&name).unwrap();
+                        if self.add_task(item_ref) {
+                            log(
+                                LogType::Debug,
+                                LOG_EMITTER_TASK_REGISTRY,
+                                add_action,
+                                Some((&name, id)),
+                                LOG_WHEN_PROC,
+                                LOG_STATUS_OK,
+                                "successfully added queued task to the registry",
+                            );
+                        } else {
+                            log(
+                                LogType::Debug,
+                                LOG_EMITTER_TASK_REGISTRY,
+                                add_action,
+                                Some((&name, id)),
+                                LOG_WHEN_PROC,
+                                LOG_STATUS_FAIL,
+                                "queued task already present in the registry",
+                            );
                         }
                     }
                 }
             }
         }
-
-        Ok(res)
     }
 }
 
@@ -0,0 +1,221 @@
+//! A local control channel based on an Unix domain socket.
+//!
+//! This module implements a listener that accepts _command lines_ from any
+//! number of concurrent local clients, and dispatches them to the same command
+//! runner that is used to interpret the standard input. For every command line
+//! received, a single reply line is written back to the client:
+//!
+//! * `OK` if the command has been accepted and performed (or initiated),
+//!   possibly followed by a space and the data returned by the command
+//!   in JSON format
+//! * `FAIL` if the command was valid but ignored (e.g. `pause` when paused)
+//! * `ERR` followed by a space and an error message in case of errors.
+//!
+//! Command lines in JSON format are also accepted, and in this case the reply
+//! is a JSON object as described in the `utility::protocol` module.
+//!
+//! The socket is created with permissions restricted to the owner, so that
+//! only the user running the scheduler is allowed to send commands. The same
+//! module also provides the client side, used by the `ctl` mode.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::Shutdown;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use crate::constants::*;
+use crate::utility::logging::{LogType, log};
+use crate::utility::protocol::{CommandRunner, is_json_request, json_command};
+use crate::utility::result::{Error, Kind, Result};
+
+/// Return the default path for the control socket
+///
+/// The socket is created in the directory specified by `XDG_RUNTIME_DIR`
+/// when available, as it is private to the user; otherwise the temporary
+/// directory is used, and the user name is added to the file name.
+pub fn default_socket_path() -> PathBuf {
+    if let Ok(dir) = std::env::var("XDG_RUNTIME_DIR")
+        && !dir.is_empty()
+    {
+        Path::new(&dir).join(format!("{APP_NAME}{CONTROL_SOCKET_SUFFIX}.sock"))
+    } else {
+        let user = whoami::username().unwrap_or(String::from(STR_UNKNOWN_VALUE));
+        std::env::temp_dir().join(format!("{APP_NAME}-{user}{CONTROL_SOCKET_SUFFIX}.sock"))
+    }
+}
+
+// remove a stale socket left by an instance that did not exit cleanly: if
+// something is still listening on it, or the file is not a socket at all,
+// the path is not touched and an error is returned instead
+fn remove_stale_socket(path: &Path) -> Result<()> {
+    if let Ok(meta) = fs::symlink_metadata(path) {
+        if !meta.file_type().is_socket() {
+            return Err(Error::new(Kind::Forbidden, ERR_CONTROL_PATH_NOT_SOCKET));
+        }
+        if UnixStream::connect(path).is_ok() {
+            return Err(Error::new(Kind::Busy, ERR_CONTROL_SOCKET_IN_USE));
+        }
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+// serve a single client until it closes the connection: each line is passed
+// as it is (that is, including the trailing newline) to the command runner,
+// so that commands are interpreted exactly as if they came from stdin
+fn serve_client(stream: UnixStream, runner: CommandRunner) -> Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut buffer = String::new();
+
+    while reader.read_line(&mut buffer)? > 0 {
+        if !buffer.ends_with('\n') {
+            buffer.push('\n');
+        }
+        let reply = if is_json_request(&buffer) {
+            json_command(&buffer, runner)
+        } else {
+            match runner(&buffer) {
+                Ok((true, Some(payload))) => format!("{CONTROL_REPLY_OK} {payload}"),
+                Ok((true, None)) => String::from(CONTROL_REPLY_OK),
+                Ok((false, _)) => String::from(CONTROL_REPLY_FAIL),
+                Err(e) => format!("{CONTROL_REPLY_ERR} {e}"),
+            }
+        };
+        writer.write_all(format!("{reply}\n").as_bytes())?;
+        writer.flush()?;
+        buffer.clear();
+    }
+
+    Ok(())
+}
+
+/// Start listening for commands on the control socket
+///
+/// The socket is created at the provided path, possibly replacing a stale
+/// one, and it is only ever accessible to the owner. A thread is then
+/// spawned to accept connections, and each client is served in a separate
+/// thread: command execution is serialized by the command runner itself.
+///
+/// # Arguments
+///
+/// * `path` - the path of the socket to be created
+/// * `runner` - the function that interprets command lines
+pub fn start_listener(path: &Path, runner: CommandRunner) -> Result<()> {
+    remove_stale_socket(path)?;
+
+    // the socket is bound under a restrictive umask, so that it is never
+    // accessible to other users, not even before permissions are changed:
+    // the previous umask is restored immediately whatever the outcome
+    let old_umask = unsafe { libc::umask(0o177) };
+    let listener = UnixListener::bind(path);
+    unsafe { libc::umask(old_umask) };
+    let listener = listener?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+
+    log(
+        LogType::Info,
+        LOG_EMITTER_CONTROL,
+        LOG_ACTION_MAIN_LISTENER,
+        None,
+        LOG_WHEN_START,
+        LOG_STATUS_OK,
+        &format!("control socket listening on `{}`", path.display()),
+    );
+
+    // the accepting thread has the same lifetime as the main thread, as in
+    // the case of the stdin command loop, and it is never explicitly joined
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    log(
+                        LogType::Debug,
+                        LOG_EMITTER_CONTROL,
+                        LOG_ACTION_MAIN_LISTENER,
+                        None,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_MSG,
+                        "control client connected",
+                    );
+                    thread::spawn(move || {
+                        if let Err(e) = serve_client(stream, runner) {
+                            log(
+                                LogType::Warn,
+                                LOG_EMITTER_CONTROL,
+                                LOG_ACTION_MAIN_LISTENER,
+                                None,
+                                LOG_WHEN_PROC,
+                                LOG_STATUS_FAIL,
+                                &format!("control client connection dropped: {e}"),
+                            );
+                        }
+                    });
+                }
+                Err(e) => {
+                    log(
+                        LogType::Warn,
+                        LOG_EMITTER_CONTROL,
+                        LOG_ACTION_MAIN_LISTENER,
+                        None,
+                        LOG_WHEN_PROC,
+                        LOG_STATUS_ERR,
+                        &format!("could not accept control client: {e}"),
+                    );
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Remove the control socket from the file system
+///
+/// This is meant to be called when the application exits, and only logs a
+/// message at debug level on failure, because there is no way to recover.
+pub fn remove_socket(path: &Path) {
+    if let Err(e) = fs::remove_file(path) {
+        log(
+            LogType::Debug,
+            LOG_EMITTER_CONTROL,
+            LOG_ACTION_MAIN_EXIT,
+            None,
+            LOG_WHEN_END,
+            LOG_STATUS_FAIL,
+            &format!("could not remove control socket `{}`: {e}", path.display()),
+        );
+    }
+}
+
+/// Send a command line to a running instance and return the reply line
+///
+/// This is the client side of the control channel: it connects to the
+/// socket, sends the command and waits for the single reply line, which is
+/// returned without the trailing newline.
+///
+/// # Arguments
+///
+/// * `path` - the path of the socket of the running instance
+/// * `line` - the command line to send, without trailing newline
+pub fn send_command(path: &Path, line: &str) -> Result<String> {
+    let mut stream = UnixStream::connect(path)?;
+    stream.write_all(format!("{line}\n").as_bytes())?;
+    stream.flush()?;
+    stream.shutdown(Shutdown::Write)?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    if reply.is_empty() {
+        Err(Error::new(Kind::Empty, ERR_CONTROL_NO_REPLY))
+    } else {
+        Ok(String::from(reply.trim_end()))
+    }
+}
+
+// end.
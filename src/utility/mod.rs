@@ -1,3 +1,6 @@
+pub mod control;
 pub mod logging;
 pub mod named_mutex;
+pub mod protocol;
 pub mod result;
+pub mod signals;
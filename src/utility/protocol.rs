@@ -0,0 +1,130 @@
+//! Structured requests and replies for internal commands.
+//!
+//! Besides plain text command lines, the command interpreter accepts requests
+//! in JSON format, each one on a single line, with the following fields:
+//!
+//! * `id` (optional): any JSON value, that is echoed back in the reply
+//! * `command`: the name of the command, as in text mode
+//! * `args` (optional): a list of strings, the command arguments, that must
+//!   be non-empty and cannot contain whitespace.
+//!
+//! Each request receives a reply consisting in a single line JSON object
+//! with the same `id`, a `status` that is `ok`, `fail` (command ignored) or
+//! `error`, an `error` object (carrying `kind`, `origin` and `message`) that
+//! is `null` unless the status is `error`, and a `payload` whose contents
+//! depend on the command and is `null` when there is nothing to return.
+
+use serde_json::{Value, json};
+
+use crate::constants::*;
+use crate::utility::result::{Error, Kind, Result};
+
+/// The type of command runners that may return a payload
+pub type CommandRunner = fn(&str) -> Result<(bool, Option<Value>)>;
+
+/// Tell whether or not a command line should be interpreted as JSON
+pub fn is_json_request(line: &str) -> bool {
+    line.trim_start().starts_with('{')
+}
+
+// extract the command line from the request: the line is terminated by a
+// newline, so that it is exactly the same as a line read from stdin
+fn request_command_line(request: &Value) -> Result<String> {
+    let Some(command) = request.get("command").and_then(|v| v.as_str()) else {
+        return Err(Error::new(Kind::Invalid, ERR_PROTOCOL_MISSING_COMMAND));
+    };
+    let mut line = String::from(command.trim());
+    match request.get("args") {
+        None | Some(Value::Null) => (),
+        Some(Value::Array(args)) => {
+            for arg in args {
+                // arguments are joined in a command line that is split again
+                // on whitespace, so they would not survive if they contain it
+                let Some(arg) = arg.as_str() else {
+                    return Err(Error::new(Kind::Invalid, ERR_PROTOCOL_INVALID_ARGS));
+                };
+                if arg.is_empty() || arg.contains(char::is_whitespace) {
+                    return Err(Error::new(Kind::Invalid, ERR_PROTOCOL_INVALID_ARGS));
+                }
+                line.push(' ');
+                line.push_str(arg);
+            }
+        }
+        _ => return Err(Error::new(Kind::Invalid, ERR_PROTOCOL_INVALID_ARGS)),
+    }
+    line.push('\n');
+    Ok(line)
+}
+
+// build the reply for an outcome
+fn reply(id: Value, outcome: Result<(bool, Value)>) -> String {
+    match outcome {
+        Ok((performed, payload)) => json!({
+            "id": id,
+            "status": if performed { PROTOCOL_STATUS_OK } else { PROTOCOL_STATUS_FAIL },
+            "error": Value::Null,
+            "payload": payload,
+        }),
+        Err(e) => json!({
+            "id": id,
+            "status": PROTOCOL_STATUS_ERR,
+            "error": {
+                "kind": e.kind().code(),
+                "origin": e.origin().to_string(),
+                "message": e.message(),
+            },
+            "payload": Value::Null,
+        }),
+    }
+    .to_string()
+}
+
+/// Interpret a JSON request and return the JSON reply as a string
+///
+/// The request is translated into a command line that is passed to the
+/// provided command runner, and its outcome is converted into a reply: if
+/// the request cannot be parsed, an error reply is returned whose `id` is
+/// `null` when it could not be retrieved.
+///
+/// # Arguments
+///
+/// * `line` - the line containing the JSON request
+/// * `runner` - the function that interprets command lines
+pub fn json_command(line: &str, runner: CommandRunner) -> String {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(e) => {
+            return reply(
+                Value::Null,
+                Err(Error::new(
+                    Kind::Unparsed,
+                    &format!("{ERR_PROTOCOL_INVALID_REQUEST}: {e}"),
+                )),
+            );
+        }
+    };
+    if !request.is_object() {
+        return reply(
+            Value::Null,
+            Err(Error::new(Kind::Invalid, ERR_PROTOCOL_INVALID_REQUEST)),
+        );
+    }
+
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let outcome = request_command_line(&request)
+        .and_then(|line| runner(&line))
+        .map(|(performed, payload)| (performed, payload.unwrap_or(Value::Null)));
+    reply(id, outcome)
+}
+
+/// Retrieve the status from a JSON reply, if it can be parsed
+#[cfg(unix)]
+pub fn reply_status(reply: &str) -> Option<String> {
+    let reply: Value = serde_json::from_str(reply).ok()?;
+    reply
+        .get("status")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+}
+
+// end.
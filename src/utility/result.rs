@@ -46,6 +46,24 @@ impl fmt::Display for Kind {
     }
 }
 
+impl Kind {
+    /// Short identifier of the error kind, suitable for machine use
+    pub fn code(&self) -> &'static str {
+        match self {
+            Kind::Forbidden => "forbidden",
+            Kind::Unsupported => "unsupported",
+            Kind::Unavailable => "unavailable",
+            Kind::Unconverted => "unconverted",
+            Kind::Unparsed => "unparsed",
+            Kind::Busy => "busy",
+            Kind::Invalid => "invalid",
+            Kind::Failed => "failed",
+            Kind::Empty => "empty",
+            Kind::Unknown => "unknown",
+        }
+    }
+}
+
 /// Describes the origin of the error: if `Native` the error was originated
 /// natively, otherwise the field is set by another error that is converted
 /// into `Error` via a dedicated `From` trait implementation.
@@ -0,0 +1,197 @@
+//! Delivery of Unix signals to the rest of the application.
+//!
+//! Signal handlers are only allowed to perform a very limited set of
+//! operations, therefore the handlers installed here just write the signal
+//! number to one end of a socket pair: the other end is read either by a
+//! dedicated thread, which runs a callback for each received signal, or
+//! asynchronously by the signal based events.
+//!
+//! The module also keeps track of the signals that events are listening for,
+//! so that a signal normally handled by the application itself can be
+//! _claimed_ by an event instead.
+
+#![cfg(unix)]
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::os::raw::c_int;
+use std::os::unix::net::UnixStream;
+use std::thread;
+
+use async_std::io::ReadExt;
+use async_std::os::unix::net::UnixStream as AsyncUnixStream;
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use signal_hook_registry::{SigId, register, unregister};
+
+use crate::utility::result::Result;
+
+lazy_static! {
+    // number of active receivers for each signal
+    static ref CLAIMED_SIGNALS: Mutex<HashMap<c_int, usize>> = Mutex::new(HashMap::new());
+}
+
+// the signals that can be given by name, without the `SIG` prefix
+const NAMED_SIGNALS: [(&str, c_int); 3] = [
+    ("HUP", libc::SIGHUP),
+    ("USR1", libc::SIGUSR1),
+    ("USR2", libc::SIGUSR2),
+];
+
+// realtime signals are only available on some platforms
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn realtime_range() -> Option<(c_int, c_int)> {
+    Some((libc::SIGRTMIN(), libc::SIGRTMAX()))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn realtime_range() -> Option<(c_int, c_int)> {
+    None
+}
+
+/// Convert a signal name to the corresponding signal number
+///
+/// The accepted names are `SIGHUP`, `SIGUSR1`, `SIGUSR2` and, where realtime
+/// signals are supported, `SIGRTMIN`, `SIGRTMIN+n`, `SIGRTMAX` and
+/// `SIGRTMAX-n`; the `SIG` prefix can be omitted. `None` is returned if the
+/// name is unknown or the signal is out of range.
+pub fn signal_number(name: &str) -> Option<c_int> {
+    let name = name.strip_prefix("SIG").unwrap_or(name);
+    if let Some((_, signum)) = NAMED_SIGNALS.iter().find(|(n, _)| *n == name) {
+        return Some(*signum);
+    }
+
+    let (rtmin, rtmax) = realtime_range()?;
+    let signum = if let Some(offset) = name.strip_prefix("RTMIN") {
+        if offset.is_empty() {
+            rtmin
+        } else {
+            rtmin + offset.strip_prefix('+')?.parse::<c_int>().ok()?
+        }
+    } else if let Some(offset) = name.strip_prefix("RTMAX") {
+        if offset.is_empty() {
+            rtmax
+        } else {
+            rtmax - offset.strip_prefix('-')?.parse::<c_int>().ok()?
+        }
+    } else {
+        return None;
+    };
+    if (rtmin..=rtmax).contains(&signum) {
+        Some(signum)
+    } else {
+        None
+    }
+}
+
+/// Return the canonical name of a signal number
+///
+/// Realtime signals are always named relative to `SIGRTMIN`.
+pub fn signal_name(signum: c_int) -> String {
+    if let Some((name, _)) = NAMED_SIGNALS.iter().find(|(_, n)| *n == signum) {
+        return format!("SIG{name}");
+    }
+    match realtime_range() {
+        Some((rtmin, _)) if signum == rtmin => String::from("SIGRTMIN"),
+        Some((rtmin, rtmax)) if signum > rtmin && signum <= rtmax => {
+            format!("SIGRTMIN+{}", signum - rtmin)
+        }
+        _ => format!("SIG{signum}"),
+    }
+}
+
+/// Tell whether at least one event is listening for the given signal
+pub fn is_claimed(signum: c_int) -> bool {
+    CLAIMED_SIGNALS.lock().get(&signum).is_some_and(|n| *n > 0)
+}
+
+// install handlers that write the signal number to the provided stream: the
+// stream is non blocking, so that signals are dropped when nobody reads them
+// instead of blocking the handler
+fn install_handlers(signals: &[c_int], stream: UnixStream) -> Result<Vec<SigId>> {
+    stream.set_nonblocking(true)?;
+    let mut ids = Vec::new();
+    for signum in signals {
+        let signum = *signum;
+        let stream = stream.try_clone()?;
+        // SAFETY: the action only performs a write on a socket, which is
+        // one of the operations allowed in a signal handler
+        let res = unsafe {
+            register(signum, move || {
+                let _ = (&stream).write(&[signum as u8]);
+            })
+        };
+        match res {
+            Ok(id) => ids.push(id),
+            Err(e) => {
+                for id in ids {
+                    unregister(id);
+                }
+                return Err(e.into());
+            }
+        }
+    }
+    Ok(ids)
+}
+
+/// Run a callback in a separate thread whenever one of the signals is caught
+///
+/// The handlers stay installed for the entire life of the application.
+pub fn spawn_handler<F>(signals: &[c_int], callback: F) -> Result<()>
+where
+    F: Fn(c_int) + Send + 'static,
+{
+    let (mut reader, writer) = UnixStream::pair()?;
+    install_handlers(signals, writer)?;
+    thread::spawn(move || {
+        let mut buffer = [0u8; 1];
+        while let Ok(1) = reader.read(&mut buffer) {
+            callback(buffer[0] as c_int);
+        }
+    });
+    Ok(())
+}
+
+/// An asynchronous receiver for a single signal
+///
+/// The signal is claimed as long as the receiver exists, and the handler is
+/// removed when the receiver is dropped.
+pub struct SignalReceiver {
+    signum: c_int,
+    id: SigId,
+    reader: AsyncUnixStream,
+}
+
+impl SignalReceiver {
+    /// Start listening for the given signal
+    pub fn new(signum: c_int) -> Result<Self> {
+        let (reader, writer) = UnixStream::pair()?;
+        let id = install_handlers(&[signum], writer)?.pop().unwrap();
+        *CLAIMED_SIGNALS.lock().entry(signum).or_insert(0) += 1;
+        Ok(SignalReceiver {
+            signum,
+            id,
+            reader: AsyncUnixStream::from(reader),
+        })
+    }
+
+    /// Wait for the signal, and return how many times it has been received
+    ///
+    /// Signals that arrive close to each other may be reported together.
+    pub async fn recv(&mut self) -> Result<usize> {
+        let mut buffer = [0u8; 64];
+        let count = self.reader.read(&mut buffer).await?;
+        Ok(count)
+    }
+}
+
+impl Drop for SignalReceiver {
+    fn drop(&mut self) {
+        unregister(self.id);
+        if let Some(n) = CLAIMED_SIGNALS.lock().get_mut(&self.signum) {
+            *n = n.saturating_sub(1);
+        }
+    }
+}
+
+// end.